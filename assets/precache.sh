@@ -0,0 +1,20 @@
+#!/bin/sh
+# Trunk 构建后的钩子：把输出目录中的文件列表写入 precache.json，供 service worker 安装时缓存，
+# 并把清单的校验和写进 sw.js 的缓存名
+#
+# 文件名带有哈希，Web Worker 的脚本也不出现在 index.html 中，因此直接列出构建产物。
+# 多语言文案已编译进 wasm，locales 目录不需要缓存。
+set -eu
+cd "$TRUNK_STAGING_DIR"
+{
+  printf '["/"'
+  find . -type f ! -name precache.json ! -name sw.js ! -path './locales/*' | LC_ALL=C sort | while read -r file; do
+    printf ',\n "%s"' "${file#.}"
+  done
+  printf ']\n'
+} > precache.json
+
+# 产物的文件名带有内容哈希，清单不变说明构建结果不变
+hash=$(cksum < precache.json | cut -d ' ' -f 1)
+sed "s/BUILD_HASH/$hash/" sw.js > sw.js.tmp
+mv sw.js.tmp sw.js
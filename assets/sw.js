@@ -0,0 +1,75 @@
+// Service worker：缓存应用外壳，使工具在离线时也能使用
+//
+// Trunk 构建出的 wasm/js/css 文件名带有哈希，构建后的钩子（assets/precache.sh）把
+// 全部产物写入 precache.json，安装时据此缓存；之后的同源请求优先使用缓存，页面导航
+// 优先使用网络。任何一项缓存失败都会让安装失败，旧版本的 service worker 继续生效。
+
+// 钩子把 BUILD_HASH 替换为 precache.json 的校验和：每次部署 sw.js 都会变化，浏览器因此
+// 安装新的 service worker，激活时删除上一版的缓存
+const CACHE = "dev-tools-BUILD_HASH";
+
+async function precache() {
+  const response = await fetch("/precache.json", { cache: "no-store" });
+  if (!response.ok) {
+    throw new Error(`precache.json: HTTP ${response.status}`);
+  }
+  const files = await response.json();
+  const cache = await caches.open(CACHE);
+  // 逐个缓存，失败时报告是哪个文件
+  await Promise.all(
+    files.map((file) =>
+      cache.add(new Request(file, { cache: "reload" })).catch((e) => {
+        throw new Error(`failed to precache ${file}: ${e.message}`);
+      })
+    )
+  );
+}
+
+self.addEventListener("install", (event) => {
+  event.waitUntil(precache().then(() => self.skipWaiting()));
+});
+
+self.addEventListener("activate", (event) => {
+  event.waitUntil(
+    caches
+      .keys()
+      .then((keys) => Promise.all(keys.filter((key) => key !== CACHE).map((key) => caches.delete(key))))
+      .then(() => self.clients.claim())
+  );
+});
+
+self.addEventListener("fetch", (event) => {
+  const request = event.request;
+  const url = new URL(request.url);
+  if (request.method !== "GET" || url.origin !== self.location.origin) {
+    return;
+  }
+
+  // 所有路由（/json、/cron 等）都是同一个 index.html，离线时回退到缓存
+  if (request.mode === "navigate") {
+    event.respondWith(
+      fetch(request)
+        .then((response) => {
+          const copy = response.clone();
+          caches.open(CACHE).then((cache) => cache.put("/index.html", copy));
+          return response;
+        })
+        .catch(() => caches.match("/index.html"))
+    );
+    return;
+  }
+
+  event.respondWith(
+    caches.match(request).then(
+      (cached) =>
+        cached ||
+        fetch(request).then((response) => {
+          if (response.ok) {
+            const copy = response.clone();
+            caches.open(CACHE).then((cache) => cache.put(request, copy));
+          }
+          return response;
+        })
+    )
+  );
+});
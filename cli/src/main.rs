@@ -0,0 +1,712 @@
+//! Command-line front end for the conversions behind the web tools.
+//!
+//! Input is read from a file or stdin and the result is written to stdout, using
+//! the same `dev-tools-core` functions as the browser so both produce the same output.
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use dev_tools_core::cron::{self, Field, FieldPattern, OutputFormat};
+use dev_tools_core::json::{self, FormatOptions, FormatType, Indent};
+use dev_tools_core::convert::{self, SourceFormat};
+use dev_tools_core::diff::{self, Change, DiffOptions};
+use dev_tools_core::infer::{self, InferOptions, Target};
+use dev_tools_core::patch::{self, PatchFormat};
+use dev_tools_core::repair;
+use dev_tools_core::schema::{self, Draft};
+use dev_tools_core::stringified;
+use dev_tools_core::table::{ArrayMode, Delimiter, TableOptions};
+use dev_tools_core::{base64, query, url};
+use serde_json::Value;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+#[derive(Parser)]
+#[command(name = "dev-tools", version, about = "Developer tools for the command line")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Format, convert, query, compare, validate or generate types from JSON
+    Json {
+        #[command(subcommand)]
+        command: JsonCommand,
+    },
+    /// Encode or decode Base64
+    Base64 {
+        #[command(subcommand)]
+        command: Codec,
+    },
+    /// Percent-encode or decode URL components
+    Url {
+        #[command(subcommand)]
+        command: Codec,
+    },
+    /// Inspect cron expressions
+    Cron {
+        #[command(subcommand)]
+        command: CronCommand,
+    },
+}
+
+#[derive(Subcommand)]
+enum JsonCommand {
+    /// Pretty-print a document, or convert it between JSON, YAML, TOML, XML, CSV and NDJSON
+    Fmt {
+        /// Output style
+        #[arg(long, value_enum, default_value_t = JsonFormat::Standard)]
+        format: JsonFormat,
+        /// Shorthand for `--format yaml`
+        #[arg(long, conflicts_with = "format")]
+        yaml: bool,
+        /// How arrays inside records are written to CSV: json, index (`tags.0` columns) or join (`a;b`)
+        #[arg(long, default_value = "json", value_parser = parse_array_mode)]
+        arrays: ArrayMode,
+        #[command(flatten)]
+        source: SourceArgs,
+        #[command(flatten)]
+        output: OutputArgs,
+        #[command(flatten)]
+        layout: LayoutArgs,
+        #[command(flatten)]
+        input: InputArgs,
+    },
+    /// Remove all insignificant whitespace
+    Minify {
+        #[command(flatten)]
+        source: SourceArgs,
+        #[command(flatten)]
+        output: OutputArgs,
+        #[command(flatten)]
+        input: InputArgs,
+    },
+    /// Write the document as a JSON string literal
+    Escape {
+        #[command(flatten)]
+        source: SourceArgs,
+        #[command(flatten)]
+        output: OutputArgs,
+        #[command(flatten)]
+        input: InputArgs,
+    },
+    /// Read a JSON string literal, quotes optional, and pretty-print the document inside it
+    Unescape {
+        #[command(flatten)]
+        output: OutputArgs,
+        #[command(flatten)]
+        layout: LayoutArgs,
+        #[command(flatten)]
+        input: InputArgs,
+    },
+    /// Expand string fields that hold JSON, however deeply stringified, and pretty-print the result
+    Unwrap {
+        #[command(flatten)]
+        source: SourceArgs,
+        #[command(flatten)]
+        output: OutputArgs,
+        #[command(flatten)]
+        layout: LayoutArgs,
+        #[command(flatten)]
+        input: InputArgs,
+    },
+    /// Fix almost-JSON such as Python dicts, JS object literals or cut-off payloads, listing each fix
+    Repair {
+        #[command(flatten)]
+        output: OutputArgs,
+        #[command(flatten)]
+        layout: LayoutArgs,
+        #[command(flatten)]
+        input: InputArgs,
+    },
+    /// Run a JSONPath (`$.a[*].b`) or jq-style (`.a[] | .b`) query, one result per line
+    Query {
+        /// Query; JSONPath when it starts with `$`, otherwise jq
+        query: String,
+        /// Print each JSONPath result's location before its value
+        #[arg(long)]
+        paths: bool,
+        #[command(flatten)]
+        source: SourceArgs,
+        #[command(flatten)]
+        input: InputArgs,
+    },
+    /// List added, removed and changed paths between two documents
+    Diff {
+        /// The original document
+        left: PathBuf,
+        /// The changed document; reads stdin when `-`
+        right: PathBuf,
+        /// Match array elements regardless of their position
+        #[arg(long)]
+        ignore_array_order: bool,
+        /// Print the difference as an RFC 6902 JSON Patch
+        #[arg(long)]
+        patch: bool,
+        /// Print the difference as an RFC 7386 Merge Patch
+        #[arg(long, conflicts_with_all = ["patch", "ignore_array_order"])]
+        merge_patch: bool,
+        #[command(flatten)]
+        lenient: LenientArg,
+    },
+    /// Apply an RFC 6902 JSON Patch or, with `--merge`, an RFC 7386 Merge Patch to a document
+    Patch {
+        /// The patch file
+        #[arg(long)]
+        patch: PathBuf,
+        /// Read the patch as a Merge Patch
+        #[arg(long)]
+        merge: bool,
+        #[command(flatten)]
+        lenient: LenientArg,
+        #[command(flatten)]
+        output: OutputArgs,
+        #[command(flatten)]
+        layout: LayoutArgs,
+        #[command(flatten)]
+        input: InputArgs,
+    },
+    /// Validate a document against a JSON Schema
+    Validate {
+        /// The JSON Schema file
+        #[arg(long)]
+        schema: PathBuf,
+        /// Schema draft; taken from `$schema` when omitted, 2020-12 if it has none
+        #[arg(long, value_parser = parse_draft)]
+        draft: Option<Draft>,
+        #[command(flatten)]
+        lenient: LenientArg,
+        #[command(flatten)]
+        input: InputArgs,
+    },
+    /// Infer types from sample documents and print them as code
+    Infer {
+        /// Output language: json-schema, rust or typescript
+        #[arg(long, value_parser = parse_target, default_value = "typescript")]
+        target: Target,
+        /// Name of the top-level type
+        #[arg(long, default_value = "Root")]
+        name: String,
+        /// Keep repeated strings as plain strings instead of enums
+        #[arg(long)]
+        no_enums: bool,
+        #[command(flatten)]
+        lenient: LenientArg,
+        /// Sample files, each holding one or more whitespace-separated documents; reads stdin when omitted
+        files: Vec<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum Codec {
+    /// Encode the input
+    Encode {
+        #[command(flatten)]
+        input: InputArgs,
+    },
+    /// Decode the input
+    Decode {
+        #[command(flatten)]
+        input: InputArgs,
+    },
+}
+
+#[derive(Subcommand)]
+enum CronCommand {
+    /// List the next execution times
+    Next {
+        /// Cron expression, e.g. "0 9 * * 1-5"
+        expression: String,
+        /// Number of execution times to list
+        #[arg(short = 'n', long, default_value_t = 5)]
+        count: usize,
+        /// Time format
+        #[arg(long, value_enum, default_value_t = TimeFormat::Default)]
+        format: TimeFormat,
+        #[command(flatten)]
+        seconds: SecondsArg,
+    },
+    /// Explain each field of the expression
+    Describe {
+        /// Cron expression, e.g. "0 9 * * 1-5"
+        expression: String,
+        #[command(flatten)]
+        seconds: SecondsArg,
+    },
+}
+
+#[derive(Args)]
+struct InputArgs {
+    /// Input file; reads stdin when omitted or `-`
+    file: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct LenientArg {
+    /// Accept comments, trailing commas, single-quoted strings and unquoted keys
+    #[arg(long)]
+    lenient: bool,
+}
+
+#[derive(Args)]
+struct SourceArgs {
+    /// Input format; YAML with several documents is read as an array
+    #[arg(long, value_enum, default_value_t = InputFormat::Json)]
+    from: InputFormat,
+    #[command(flatten)]
+    lenient: LenientArg,
+    #[command(flatten)]
+    table: TableArgs,
+}
+
+#[derive(Args)]
+struct TableArgs {
+    /// CSV delimiter: comma, tab, semicolon or pipe
+    #[arg(long, default_value = "comma", value_parser = parse_delimiter)]
+    delimiter: Delimiter,
+    /// CSV has no header row; rows are read and written as arrays
+    #[arg(long)]
+    no_header: bool,
+    /// Read every CSV cell as a string instead of inferring numbers, booleans and null
+    #[arg(long)]
+    no_infer: bool,
+}
+
+#[derive(Args)]
+struct OutputArgs {
+    /// Sort object keys at every level instead of keeping their input order
+    #[arg(long)]
+    sort_keys: bool,
+    /// Write non-ASCII characters as \uXXXX escapes
+    #[arg(long)]
+    ascii: bool,
+}
+
+#[derive(Args)]
+struct LayoutArgs {
+    /// Indentation: 2, 4, 8 or tab
+    #[arg(long, default_value = "2", value_parser = parse_indent)]
+    indent: Indent,
+    /// Keep arrays and objects on one line when they fit within this many columns
+    #[arg(long, default_value_t = 0)]
+    width: usize,
+}
+
+fn parse_indent(value: &str) -> Result<Indent, String> {
+    Indent::from_param(value).ok_or_else(|| "expected 2, 4, 8 or tab".to_string())
+}
+
+fn parse_delimiter(value: &str) -> Result<Delimiter, String> {
+    Delimiter::from_param(value).ok_or_else(|| "expected comma, tab, semicolon or pipe".to_string())
+}
+
+fn parse_array_mode(value: &str) -> Result<ArrayMode, String> {
+    ArrayMode::from_param(value).ok_or_else(|| "expected json, index or join".to_string())
+}
+
+fn parse_draft(value: &str) -> Result<Draft, String> {
+    Draft::from_param(value).ok_or_else(|| "expected draft7, 2019-09 or 2020-12".to_string())
+}
+
+fn parse_target(value: &str) -> Result<Target, String> {
+    Target::from_param(value).ok_or_else(|| "expected json-schema, rust or typescript".to_string())
+}
+
+impl TableArgs {
+    fn options(&self) -> TableOptions {
+        TableOptions {
+            delimiter: self.delimiter,
+            header: !self.no_header,
+            infer_types: !self.no_infer,
+            ..Default::default()
+        }
+    }
+}
+
+impl LayoutArgs {
+    fn options(&self, options: FormatOptions) -> FormatOptions {
+        FormatOptions {
+            indent: self.indent,
+            max_width: self.width,
+            ..options
+        }
+    }
+}
+
+impl OutputArgs {
+    fn options(&self) -> FormatOptions {
+        FormatOptions {
+            sort_keys: self.sort_keys,
+            escape_non_ascii: self.ascii,
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Args)]
+struct SecondsArg {
+    /// The expression starts with a seconds field
+    #[arg(long)]
+    seconds: bool,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum JsonFormat {
+    Standard,
+    SingleQuote,
+    NoQuote,
+    Python,
+    Yaml,
+    Toml,
+    Xml,
+    Csv,
+    Ndjson,
+}
+
+impl From<JsonFormat> for FormatType {
+    fn from(format: JsonFormat) -> Self {
+        match format {
+            JsonFormat::Standard => FormatType::Standard,
+            JsonFormat::SingleQuote => FormatType::SingleQuote,
+            JsonFormat::NoQuote => FormatType::NoQuote,
+            JsonFormat::Python => FormatType::Python,
+            JsonFormat::Yaml => FormatType::Yaml,
+            JsonFormat::Toml => FormatType::Toml,
+            JsonFormat::Xml => FormatType::Xml,
+            JsonFormat::Csv => FormatType::Csv,
+            JsonFormat::Ndjson => FormatType::Ndjson,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum InputFormat {
+    Json,
+    Yaml,
+    Toml,
+    Xml,
+    Csv,
+    Ndjson,
+}
+
+impl From<InputFormat> for SourceFormat {
+    fn from(format: InputFormat) -> Self {
+        match format {
+            InputFormat::Json => SourceFormat::Json,
+            InputFormat::Yaml => SourceFormat::Yaml,
+            InputFormat::Toml => SourceFormat::Toml,
+            InputFormat::Xml => SourceFormat::Xml,
+            InputFormat::Csv => SourceFormat::Csv,
+            InputFormat::Ndjson => SourceFormat::Ndjson,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum TimeFormat {
+    Default,
+    Iso8601,
+    Unix,
+}
+
+impl From<TimeFormat> for OutputFormat {
+    fn from(format: TimeFormat) -> Self {
+        match format {
+            TimeFormat::Default => OutputFormat::Default,
+            TimeFormat::Iso8601 => OutputFormat::ISO8601,
+            TimeFormat::Unix => OutputFormat::Unix,
+        }
+    }
+}
+
+impl InputArgs {
+    fn read(&self) -> Result<String, String> {
+        String::from_utf8(self.read_bytes()?).map_err(|e| format!("input is not UTF-8: {}", e))
+    }
+
+    fn read_bytes(&self) -> Result<Vec<u8>, String> {
+        match &self.file {
+            Some(path) if path.as_os_str() != "-" => {
+                fs::read(path).map_err(|e| format!("cannot read {}: {}", path.display(), e))
+            }
+            _ => {
+                let mut input = Vec::new();
+                io::stdin()
+                    .read_to_end(&mut input)
+                    .map_err(|e| format!("cannot read stdin: {}", e))?;
+                Ok(input)
+            }
+        }
+    }
+
+    /// Reads the input without the final line break, which `echo` and most editors add
+    /// but which is rarely part of the value being encoded.
+    fn read_line(&self) -> Result<String, String> {
+        String::from_utf8(self.read_line_bytes()?).map_err(|e| format!("input is not UTF-8: {}", e))
+    }
+
+    /// Same as [`Self::read_line`] for input that may not be text, such as a file to encode.
+    fn read_line_bytes(&self) -> Result<Vec<u8>, String> {
+        let mut input = self.read_bytes()?;
+        if input.ends_with(b"\n") {
+            input.pop();
+            if input.ends_with(b"\r") {
+                input.pop();
+            }
+        }
+        Ok(input)
+    }
+}
+
+impl SourceArgs {
+    /// Parses the input in its format; `--lenient` only applies to JSON.
+    fn parse(&self, input: &str) -> Result<Value, String> {
+        match self.from.into() {
+            SourceFormat::Json => self.lenient.parse(input),
+            source => convert::parse(input, source, &self.table.options())
+                .map_err(|e| format!("invalid {}: {}", source.as_param().to_uppercase(), e)),
+        }
+    }
+}
+
+impl LenientArg {
+    /// Parses the input, noting on stderr which relaxations a lenient parse relied on.
+    fn parse(&self, input: &str) -> Result<Value, String> {
+        if !self.lenient {
+            return json::parse(input).map_err(|e| format!("invalid JSON: {}", e));
+        }
+        let (value, relaxations) =
+            json::parse_lenient(input).map_err(|e| format!("invalid JSON: {}", e))?;
+        if !relaxations.is_empty() {
+            let names = relaxations
+                .iter()
+                .map(|relaxation| relaxation.as_param().replace('_', " "))
+                .collect::<Vec<_>>()
+                .join(", ");
+            eprintln!("dev-tools: accepted non-standard JSON: {}", names);
+        }
+        Ok(value)
+    }
+}
+
+fn field_label(field: Field) -> &'static str {
+    match field {
+        Field::Second => "Second",
+        Field::Minute => "Minute",
+        Field::Hour => "Hour",
+        Field::Day => "Day",
+        Field::Month => "Month",
+        Field::Week => "Week",
+    }
+}
+
+fn describe_pattern(pattern: &FieldPattern) -> String {
+    match pattern {
+        FieldPattern::Every => "Every".to_string(),
+        FieldPattern::Any => "Any".to_string(),
+        FieldPattern::Step(step) => format!("Every {}", step),
+        FieldPattern::Range(from, to) => format!("From {} {}", from, to),
+        FieldPattern::List(values) => format!("Specific values [{}]", values),
+        FieldPattern::Value(value) => value.clone(),
+    }
+}
+
+/// What a command writes to stdout.
+enum Output {
+    /// Text, written with a final line break.
+    Text(String),
+    /// Decoded bytes, written exactly as they are so binary data can be piped on.
+    Bytes(Vec<u8>),
+}
+
+fn run_json(command: JsonCommand) -> Result<String, String> {
+    match command {
+        JsonCommand::Fmt { format, yaml, arrays, source, output, layout, input } => {
+            let format = if yaml { FormatType::Yaml } else { format.into() };
+            let value = source.parse(&input.read()?)?;
+            let options = layout.options(FormatOptions {
+                table: TableOptions {
+                    arrays,
+                    ..source.table.options()
+                },
+                ..output.options()
+            });
+            json::format_value(&value, format, &options).map_err(|e| e.to_string())
+        }
+        JsonCommand::Minify { source, output, input } => {
+            let value = source.parse(&input.read()?)?;
+            json::minify_value(&value, &output.options()).map_err(|e| e.to_string())
+        }
+        JsonCommand::Escape { source, output, input } => {
+            let value = source.parse(&input.read()?)?;
+            stringified::escape(&value, &output.options()).map_err(|e| e.to_string())
+        }
+        JsonCommand::Unescape { output, layout, input } => {
+            let value = stringified::unescape(&input.read()?).map_err(|e| e.to_string())?;
+            json::format_value(&value, FormatType::Standard, &layout.options(output.options()))
+                .map_err(|e| e.to_string())
+        }
+        JsonCommand::Unwrap { source, output, layout, input } => {
+            let (value, expanded) = stringified::unwrap(source.parse(&input.read()?)?);
+            if !expanded.is_empty() {
+                let paths = expanded.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ");
+                eprintln!("dev-tools: expanded stringified JSON at {}", paths);
+            }
+            json::format_value(&value, FormatType::Standard, &layout.options(output.options()))
+                .map_err(|e| e.to_string())
+        }
+        JsonCommand::Repair { output, layout, input } => {
+            let repaired = repair::repair(&input.read()?).map_err(|e| format!("cannot repair: {}", e))?;
+            for summary in repair::summarize(&repaired.repairs) {
+                let times = if summary.count > 1 { format!(" ({} times)", summary.count) } else { String::new() };
+                eprintln!(
+                    "dev-tools: line {} column {}: {}{}",
+                    summary.first.line, summary.first.column, summary.kind.description(), times
+                );
+            }
+            let value = json::parse(&repaired.text).map_err(|e| e.to_string())?;
+            json::format_value(&value, FormatType::Standard, &layout.options(output.options()))
+                .map_err(|e| e.to_string())
+        }
+        JsonCommand::Query { query, paths, source, input } => {
+            let value = source.parse(&input.read()?)?;
+            let matches = query::evaluate(&query, &value).map_err(|e| format!("invalid query: {}", e))?;
+            let lines = matches
+                .into_iter()
+                .map(|m| {
+                    let value = json::minify_value(&m.value, &FormatOptions::default()).map_err(|e| e.to_string())?;
+                    Ok(match m.path {
+                        Some(path) if paths => format!("{}\t{}", path, value),
+                        _ => value,
+                    })
+                })
+                .collect::<Result<Vec<_>, String>>()?;
+            Ok(lines.join("\n"))
+        }
+        JsonCommand::Diff { left, right, ignore_array_order, patch, merge_patch, lenient } => {
+            let left = lenient.parse(&InputArgs { file: Some(left) }.read()?)?;
+            let right = lenient.parse(&InputArgs { file: Some(right) }.read()?)?;
+            if merge_patch {
+                let patch = patch::merge_patch(&left, &right).map_err(|e| e.to_string())?;
+                return json::format_value(&patch, FormatType::Standard, &FormatOptions::default())
+                    .map_err(|e| e.to_string());
+            }
+            let changes = diff::diff(&left, &right, &DiffOptions { ignore_array_order });
+            if patch {
+                let patch = diff::to_patch(&changes);
+                return json::format_value(&patch, FormatType::Standard, &FormatOptions::default())
+                    .map_err(|e| e.to_string());
+            }
+            let compact = |value: &Value| json::minify_value(value, &FormatOptions::default()).unwrap_or_default();
+            Ok(changes
+                .iter()
+                .map(|change| match change {
+                    Change::Added { path, value } => format!("+ {}: {}", path, compact(value)),
+                    Change::Removed { path, value } => format!("- {}: {}", path, compact(value)),
+                    Change::Changed { path, old, new } => {
+                        format!("~ {}: {} -> {}", path, compact(old), compact(new))
+                    }
+                })
+                .collect::<Vec<_>>()
+                .join("\n"))
+        }
+        JsonCommand::Patch { patch, merge, lenient, output, layout, input } => {
+            let patch = lenient.parse(&InputArgs { file: Some(patch) }.read()?)?;
+            let document = lenient.parse(&input.read()?)?;
+            let format = if merge { PatchFormat::MergePatch } else { PatchFormat::JsonPatch };
+            let value = patch::apply(&document, &patch, format).map_err(|e| format!("cannot apply patch: {}", e))?;
+            json::format_value(&value, FormatType::Standard, &layout.options(output.options()))
+                .map_err(|e| e.to_string())
+        }
+        JsonCommand::Validate { schema, draft, lenient, input } => {
+            let schema = lenient.parse(&InputArgs { file: Some(schema) }.read()?)?;
+            let document = lenient.parse(&input.read()?)?;
+            let errors = schema::validate(&schema, &document, draft).map_err(|e| format!("invalid schema: {}", e))?;
+            if errors.is_empty() {
+                return Ok("valid".to_string());
+            }
+            let lines: Vec<String> = errors
+                .iter()
+                .map(|e| format!("{}: {} (#{})", e.instance_path, e.message, e.schema_path.to_pointer()))
+                .collect();
+            Err(format!("{} validation error(s)\n{}", errors.len(), lines.join("\n")))
+        }
+        JsonCommand::Infer { target, name, no_enums, lenient, files } => {
+            let inputs = if files.is_empty() {
+                vec![InputArgs { file: None }]
+            } else {
+                files.into_iter().map(|file| InputArgs { file: Some(file) }).collect()
+            };
+            let mut samples = Vec::new();
+            for input in inputs {
+                let text = input.read()?;
+                if lenient.lenient {
+                    samples.push(lenient.parse(&text)?);
+                } else {
+                    samples.extend(infer::parse_samples(&text).map_err(|e| format!("invalid JSON: {}", e))?);
+                }
+            }
+            let options = InferOptions {
+                root_name: name,
+                detect_enums: !no_enums,
+            };
+            Ok(infer::generate(&samples, target, &options).trim_end().to_string())
+        }
+    }
+}
+
+fn run(command: Command) -> Result<Output, String> {
+    match command {
+        Command::Json { command } => run_json(command).map(Output::Text),
+        Command::Base64 { command } => match command {
+            Codec::Encode { input } => Ok(Output::Text(base64::encode_bytes(&input.read_line_bytes()?))),
+            Codec::Decode { input } => base64::decode_bytes(&input.read_line()?)
+                .map(Output::Bytes)
+                .map_err(|e| format!("invalid Base64: {}", e)),
+        },
+        Command::Url { command } => match command {
+            Codec::Encode { input } => Ok(Output::Text(url::encode_bytes(&input.read_line_bytes()?))),
+            Codec::Decode { input } => Ok(Output::Bytes(url::decode_bytes(&input.read_line()?))),
+        },
+        Command::Cron { command } => match command {
+            CronCommand::Next { expression, count, format, seconds } => {
+                let schedule = cron::parse_cron(&expression, seconds.seconds)
+                    .map_err(|e| format!("invalid cron expression: {}", e))?;
+                Ok(cron::get_next_occurrences(&schedule, count, format.into()).join("\n"))
+            }
+            CronCommand::Describe { expression, seconds } => {
+                let fields = cron::describe_cron(&expression, seconds.seconds)
+                    .map_err(|e| format!("invalid cron expression: {}", e))?;
+                Ok(fields
+                    .iter()
+                    .map(|desc| format!("{}: {}", field_label(desc.field), describe_pattern(&desc.pattern)))
+                    .collect::<Vec<_>>()
+                    .join("\n"))
+            }
+        }
+        .map(Output::Text),
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match run(cli.command) {
+        Ok(Output::Text(mut output)) => {
+            if !output.ends_with('\n') {
+                output.push('\n');
+            }
+            let _ = io::stdout().write_all(output.as_bytes());
+            ExitCode::SUCCESS
+        }
+        Ok(Output::Bytes(output)) => {
+            let _ = io::stdout().write_all(&output);
+            ExitCode::SUCCESS
+        }
+        Err(message) => {
+            eprintln!("dev-tools: {}", message);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,52 @@
+//! Runs the `dev-tools` binary and checks its stdout against `dev-tools-core`
+//! byte for byte.
+
+use dev_tools_core::json::{self, FormatOptions, FormatType};
+use dev_tools_core::{base64, url};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Runs the binary with `stdin` and returns its stdout, failing the test on a non-zero exit.
+fn run(args: &[&str], stdin: &[u8]) -> Vec<u8> {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_dev-tools"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("the binary starts");
+    child.stdin.take().unwrap().write_all(stdin).unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    output.stdout
+}
+
+/// Bytes that are not UTF-8, like the start of a gzip file.
+const BINARY: &[u8] = &[0x1f, 0x8b, 0x08, 0x00, 0xff, 0xfe, b'\n', 0x00, 0x80];
+
+#[test]
+fn base64_round_trips_binary_data() {
+    let encoded = run(&["base64", "encode"], BINARY);
+    assert_eq!(encoded, format!("{}\n", base64::encode_bytes(BINARY)).into_bytes());
+    let decoded = run(&["base64", "decode"], &encoded);
+    assert_eq!(decoded, BINARY);
+    assert_eq!(decoded, base64::decode_bytes(&base64::encode_bytes(BINARY)).unwrap());
+}
+
+#[test]
+fn url_decodes_to_the_exact_bytes() {
+    let encoded = url::encode_bytes(BINARY);
+    assert_eq!(run(&["url", "encode"], BINARY), format!("{}\n", encoded).into_bytes());
+    assert_eq!(run(&["url", "decode"], encoded.as_bytes()), BINARY);
+    assert_eq!(run(&["url", "decode"], b"a%20b\n"), b"a b");
+}
+
+#[test]
+fn json_output_matches_the_core() {
+    let input = r#"{"b": [1, 2.50], "a": {"c": "é"}}"#;
+    let value = json::parse(input).unwrap();
+    let expected = json::format_value(&value, FormatType::Standard, &FormatOptions::default()).unwrap();
+    assert_eq!(run(&["json", "fmt"], input.as_bytes()), format!("{}\n", expected).into_bytes());
+    let expected = json::minify_value(&value, &FormatOptions::default()).unwrap();
+    assert_eq!(run(&["json", "minify"], input.as_bytes()), format!("{}\n", expected).into_bytes());
+}
@@ -0,0 +1,121 @@
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use std::fmt;
+use std::string::FromUtf8Error;
+
+#[derive(Debug)]
+pub enum DecodeError {
+    /// The input is not valid standard Base64.
+    Base64(base64::DecodeError),
+    /// The decoded bytes are not valid UTF-8 text.
+    Utf8(FromUtf8Error),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::Base64(e) => e.fmt(f),
+            DecodeError::Utf8(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+pub fn encode(input: &str) -> String {
+    encode_bytes(input.as_bytes())
+}
+
+/// Encodes arbitrary bytes, such as a certificate or an image.
+pub fn encode_bytes(input: &[u8]) -> String {
+    STANDARD.encode(input)
+}
+
+pub fn decode(input: &str) -> Result<String, DecodeError> {
+    String::from_utf8(decode_bytes(input)?).map_err(DecodeError::Utf8)
+}
+
+/// Decodes to bytes without requiring them to be UTF-8 text.
+pub fn decode_bytes(input: &str) -> Result<Vec<u8>, DecodeError> {
+    STANDARD.decode(input.as_bytes()).map_err(DecodeError::Base64)
+}
+
+/// Input processed between two progress reports: a multiple of 3 bytes when
+/// encoding and of 4 characters when decoding, so chunks can be joined as-is.
+const CHUNK_GROUPS: usize = 256 * 1024;
+
+fn percent(done: usize, total: usize) -> u8 {
+    (done * 100 / total.max(1)) as u8
+}
+
+/// Same as [`encode`], calling `progress` with a percentage after each chunk.
+pub fn encode_with_progress(input: &str, mut progress: impl FnMut(u8)) -> String {
+    let bytes = input.as_bytes();
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    let mut done = 0;
+    for chunk in bytes.chunks(3 * CHUNK_GROUPS) {
+        STANDARD.encode_string(chunk, &mut encoded);
+        done += chunk.len();
+        progress(percent(done, bytes.len()));
+    }
+    encoded
+}
+
+/// Same as [`decode`], calling `progress` with a percentage after each chunk.
+pub fn decode_with_progress(input: &str, mut progress: impl FnMut(u8)) -> Result<String, DecodeError> {
+    let bytes = input.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len() / 4 * 3);
+    let mut done = 0;
+    for chunk in bytes.chunks(4 * CHUNK_GROUPS) {
+        if STANDARD.decode_vec(chunk, &mut decoded).is_err() {
+            // Re-run on the whole input so the error reports the offset in the input
+            return decode(input);
+        }
+        done += chunk.len();
+        progress(percent(done, bytes.len()));
+    }
+    String::from_utf8(decoded).map_err(DecodeError::Utf8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_utf8_text() {
+        let encoded = encode("hello, 世界");
+        assert_eq!(encoded, "aGVsbG8sIOS4lueVjA==");
+        assert_eq!(decode(&encoded).unwrap(), "hello, 世界");
+    }
+
+    #[test]
+    fn rejects_invalid_base64() {
+        assert!(matches!(decode("not base64!"), Err(DecodeError::Base64(_))));
+    }
+
+    #[test]
+    fn chunked_matches_whole_input() {
+        let input = "ab€".repeat(CHUNK_GROUPS + 7);
+        let mut reports = Vec::new();
+        let encoded = encode_with_progress(&input, |p| reports.push(p));
+        assert_eq!(encoded, encode(&input));
+        assert_eq!(reports.last(), Some(&100));
+        assert!(reports.len() > 1);
+        assert_eq!(decode_with_progress(&encoded, |_| ()).unwrap(), input);
+    }
+
+    #[test]
+    fn chunked_decode_reports_whole_input_offsets() {
+        let mut input = encode(&"x".repeat(4 * CHUNK_GROUPS));
+        input.push_str("!!!!");
+        assert_eq!(
+            decode_with_progress(&input, |_| ()).unwrap_err().to_string(),
+            decode(&input).unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn rejects_non_utf8_payload() {
+        // 0xff 0xfe
+        assert!(matches!(decode("//4="), Err(DecodeError::Utf8(_))));
+    }
+}
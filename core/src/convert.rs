@@ -0,0 +1,537 @@
+//! Reading YAML, TOML, XML, CSV and NDJSON into JSON values, and writing values
+//! back out in those formats through [`json::format_value`](crate::json::format_value).
+//!
+//! XML has no arrays or types, so it follows the usual convention: attributes
+//! become `@name` keys, text next to attributes or children becomes `#text`,
+//! repeated child elements become an array and every value is a string.
+
+use crate::json::{location_at, minify_value, ErrorLocation, FormatOptions, JsonError};
+use crate::path::JsonPath;
+use crate::table::{self, TableOptions};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Number, Value};
+use std::fmt;
+
+/// Attribute keys in converted XML start with this.
+const ATTRIBUTE_PREFIX: char = '@';
+
+/// Key holding an element's text when it also has attributes or children.
+const TEXT_KEY: &str = "#text";
+
+/// Element wrapped around documents that are not a single object with one key.
+const XML_ROOT: &str = "root";
+
+/// Element used for each item of an array with no name of its own.
+const XML_ITEM: &str = "item";
+
+/// The format of the input.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SourceFormat {
+    #[default]
+    Json,
+    /// One or more documents; several become an array.
+    Yaml,
+    Toml,
+    Xml,
+    /// A header row followed by records; each record becomes an object.
+    Csv,
+    /// One JSON document per line, read as an array.
+    Ndjson,
+}
+
+impl SourceFormat {
+    pub const ALL: [SourceFormat; 6] = [
+        SourceFormat::Json,
+        SourceFormat::Yaml,
+        SourceFormat::Toml,
+        SourceFormat::Xml,
+        SourceFormat::Csv,
+        SourceFormat::Ndjson,
+    ];
+
+    pub fn as_param(&self) -> &'static str {
+        match self {
+            SourceFormat::Json => "json",
+            SourceFormat::Yaml => "yaml",
+            SourceFormat::Toml => "toml",
+            SourceFormat::Xml => "xml",
+            SourceFormat::Csv => "csv",
+            SourceFormat::Ndjson => "ndjson",
+        }
+    }
+
+    pub fn from_param(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|format| format.as_param() == value)
+    }
+}
+
+/// Why the input could not be read.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConvertError {
+    pub message: String,
+    pub location: Option<ErrorLocation>,
+}
+
+impl fmt::Display for ConvertError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.location {
+            Some(location) => write!(f, "{} at line {} column {}", self.message, location.line, location.column),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+impl std::error::Error for ConvertError {}
+
+impl ConvertError {
+    pub(crate) fn at(input: &str, offset: usize, message: impl fmt::Display) -> Self {
+        ConvertError {
+            message: message.to_string(),
+            location: Some(location_at(input, offset)),
+        }
+    }
+}
+
+/// Reads `input` in the given format. JSON is read strictly; lenient JSON is
+/// handled by [`json::parse_lenient`](crate::json::parse_lenient). `table`
+/// only applies to CSV.
+pub fn parse(input: &str, format: SourceFormat, table: &TableOptions) -> Result<Value, ConvertError> {
+    match format {
+        SourceFormat::Json => crate::json::parse(input).map_err(|e| ConvertError {
+            location: e.location(input),
+            message: strip_position(&e.to_string()),
+        }),
+        SourceFormat::Yaml => parse_yaml(input),
+        SourceFormat::Toml => parse_toml(input),
+        SourceFormat::Xml => parse_xml(input),
+        SourceFormat::Csv => table::parse(input, table),
+        SourceFormat::Ndjson => parse_ndjson(input),
+    }
+}
+
+/// Drops the ` at line 1 column 2` that `serde_json` appends, since the location
+/// is reported separately.
+fn strip_position(message: &str) -> String {
+    message.rsplit_once(" at line ").map_or(message, |(message, _)| message).to_string()
+}
+
+fn parse_yaml(input: &str) -> Result<Value, ConvertError> {
+    let mut documents = Vec::new();
+    for document in serde_yaml::Deserializer::from_str(input) {
+        let value = Value::deserialize(document).map_err(|e| match e.location() {
+            Some(location) => ConvertError::at(input, location.index(), strip_position(&e.to_string())),
+            None => ConvertError {
+                message: e.to_string(),
+                location: None,
+            },
+        })?;
+        documents.push(value);
+    }
+    Ok(match documents.len() {
+        0 => Value::Null,
+        1 => documents.remove(0),
+        _ => Value::Array(documents),
+    })
+}
+
+fn parse_toml(input: &str) -> Result<Value, ConvertError> {
+    let table: toml::Table = input.parse().map_err(|e: toml::de::Error| match e.span() {
+        Some(span) => ConvertError::at(input, span.start, e.message()),
+        None => ConvertError {
+            message: e.message().to_string(),
+            location: None,
+        },
+    })?;
+    Ok(from_toml(toml::Value::Table(table)))
+}
+
+fn from_toml(value: toml::Value) -> Value {
+    match value {
+        toml::Value::String(text) => Value::String(text),
+        toml::Value::Integer(number) => Value::from(number),
+        // JSON has no NaN or infinity, so those keep their TOML spelling as strings
+        toml::Value::Float(number) => Number::from_f64(number).map_or_else(|| Value::String(number.to_string()), Value::Number),
+        toml::Value::Boolean(flag) => Value::Bool(flag),
+        toml::Value::Datetime(datetime) => Value::String(datetime.to_string()),
+        toml::Value::Array(items) => Value::Array(items.into_iter().map(from_toml).collect()),
+        toml::Value::Table(table) => Value::Object(table.into_iter().map(|(key, value)| (key, from_toml(value))).collect()),
+    }
+}
+
+/// An XML element being read: its name, attributes and children so far, and its text.
+struct Element {
+    name: String,
+    fields: Map<String, Value>,
+    text: String,
+}
+
+fn parse_xml(input: &str) -> Result<Value, ConvertError> {
+    let mut reader = Reader::from_str(input);
+    let mut stack: Vec<Element> = Vec::new();
+    let mut root = None;
+    loop {
+        let offset = reader.buffer_position() as usize;
+        let event = reader
+            .read_event()
+            .map_err(|e| ConvertError::at(input, reader.error_position() as usize, e))?;
+        let error = |message: &str| ConvertError::at(input, offset, message);
+        match event {
+            Event::Start(start) => stack.push(element(&start).map_err(|e| error(&e))?),
+            Event::Empty(start) => {
+                let element = element(&start).map_err(|e| error(&e))?;
+                close(element, &mut stack, &mut root).map_err(|e| error(&e))?;
+            }
+            Event::End(_) => {
+                // quick-xml has already checked that the closing tag matches
+                let element = stack.pop().ok_or_else(|| error("unexpected closing tag"))?;
+                close(element, &mut stack, &mut root).map_err(|e| error(&e))?;
+            }
+            Event::Text(text) => {
+                let text = text.unescape().map_err(|e| error(&e.to_string()))?;
+                match stack.last_mut() {
+                    Some(element) => element.text.push_str(&text),
+                    None if text.trim().is_empty() => {}
+                    None => return Err(error("text outside the root element")),
+                }
+            }
+            Event::CData(data) => {
+                let text = data.decode().map_err(|e| error(&e.to_string()))?;
+                match stack.last_mut() {
+                    Some(element) => element.text.push_str(&text),
+                    None => return Err(error("text outside the root element")),
+                }
+            }
+            Event::Eof => break,
+            Event::Decl(_) | Event::PI(_) | Event::DocType(_) | Event::Comment(_) => {}
+        }
+    }
+    if let Some(element) = stack.last() {
+        return Err(ConvertError::at(input, input.len(), format!("unclosed element <{}>", element.name)));
+    }
+    let (name, value) = root.ok_or_else(|| ConvertError {
+        message: "no root element".to_string(),
+        location: None,
+    })?;
+    Ok(Value::Object(Map::from_iter([(name, value)])))
+}
+
+fn element(start: &BytesStart) -> Result<Element, String> {
+    let mut fields = Map::new();
+    for attribute in start.attributes() {
+        let attribute = attribute.map_err(|e| e.to_string())?;
+        let key = String::from_utf8_lossy(attribute.key.as_ref());
+        let value = attribute.unescape_value().map_err(|e| e.to_string())?;
+        fields.insert(format!("{}{}", ATTRIBUTE_PREFIX, key), Value::String(value.into_owned()));
+    }
+    Ok(Element {
+        name: String::from_utf8_lossy(start.name().as_ref()).into_owned(),
+        fields,
+        text: String::new(),
+    })
+}
+
+/// Adds a finished element to its parent, or makes it the root.
+fn close(element: Element, stack: &mut [Element], root: &mut Option<(String, Value)>) -> Result<(), String> {
+    let Element { name, mut fields, text } = element;
+    let text = text.trim();
+    let value = if fields.is_empty() {
+        if text.is_empty() { Value::Null } else { Value::String(text.to_string()) }
+    } else {
+        if !text.is_empty() {
+            fields.insert(TEXT_KEY.to_string(), Value::String(text.to_string()));
+        }
+        Value::Object(fields)
+    };
+    let Some(parent) = stack.last_mut() else {
+        if root.is_some() {
+            return Err("more than one root element".to_string());
+        }
+        *root = Some((name, value));
+        return Ok(());
+    };
+    match parent.fields.get_mut(&name) {
+        Some(Value::Array(items)) => items.push(value),
+        Some(existing) => *existing = Value::Array(vec![existing.take(), value]),
+        None => {
+            parent.fields.insert(name, value);
+        }
+    }
+    Ok(())
+}
+
+fn parse_ndjson(input: &str) -> Result<Value, ConvertError> {
+    serde_json::Deserializer::from_str(input)
+        .into_iter::<Value>()
+        .collect::<Result<Vec<_>, _>>()
+        .map(Value::Array)
+        .map_err(|e| {
+            let e = JsonError::Parse(e);
+            ConvertError {
+                location: e.location(input),
+                message: strip_position(&e.to_string()),
+            }
+        })
+}
+
+/// Writes a document as TOML, which needs a table at the top and has no `null`.
+pub(crate) fn to_toml(json: &Value) -> Result<String, JsonError> {
+    let toml::Value::Table(table) = toml_value(json, &JsonPath::root())? else {
+        return Err(JsonError::Write("TOML needs an object at the top level".to_string()));
+    };
+    toml::to_string(&table).map_err(|e| JsonError::Write(e.to_string()))
+}
+
+fn toml_value(json: &Value, path: &JsonPath) -> Result<toml::Value, JsonError> {
+    Ok(match json {
+        Value::Null => return Err(JsonError::Write(format!("TOML has no null value, found at {}", path))),
+        Value::Bool(flag) => toml::Value::Boolean(*flag),
+        Value::Number(number) => {
+            let text = number.to_string();
+            match text.parse::<i64>() {
+                Ok(integer) => toml::Value::Integer(integer),
+                Err(_) if text.contains(['.', 'e', 'E']) => toml::Value::Float(text.parse().unwrap_or(f64::NAN)),
+                Err(_) => {
+                    return Err(JsonError::Write(format!("{} at {} does not fit a TOML integer", text, path)));
+                }
+            }
+        }
+        Value::String(text) => toml::Value::String(text.clone()),
+        Value::Array(items) => toml::Value::Array(
+            items
+                .iter()
+                .enumerate()
+                .map(|(index, item)| toml_value(item, &path.index(index)))
+                .collect::<Result<_, _>>()?,
+        ),
+        Value::Object(map) => toml::Value::Table(
+            map.iter()
+                .map(|(key, value)| Ok((key.clone(), toml_value(value, &path.key(key))?)))
+                .collect::<Result<_, JsonError>>()?,
+        ),
+    })
+}
+
+/// Writes a document as XML, reversing the mapping [`parse`] uses. An object with
+/// a single key names the root element; anything else is wrapped in `<root>`.
+pub(crate) fn to_xml(json: &Value, options: &FormatOptions) -> Result<String, JsonError> {
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let writer = XmlWriter { indent: options.indent.as_str() };
+    match json {
+        Value::Object(map) if map.len() == 1 => {
+            let (name, value) = map.iter().next().expect("one entry");
+            if value.is_array() || name.starts_with(ATTRIBUTE_PREFIX) || name == TEXT_KEY {
+                writer.element(&mut out, XML_ROOT, json, 0)?;
+            } else {
+                writer.element(&mut out, name, value, 0)?;
+            }
+        }
+        Value::Array(_) => {
+            out.push_str(&format!("<{}>\n", XML_ROOT));
+            // 与对象中的数组一样，写成多个 <item>
+            writer.element(&mut out, XML_ITEM, json, 1)?;
+            out.push_str(&format!("</{}>\n", XML_ROOT));
+        }
+        _ => writer.element(&mut out, XML_ROOT, json, 0)?,
+    }
+    Ok(out)
+}
+
+struct XmlWriter {
+    indent: &'static str,
+}
+
+impl XmlWriter {
+    fn element(&self, out: &mut String, name: &str, value: &Value, depth: usize) -> Result<(), JsonError> {
+        if !is_xml_name(name) {
+            return Err(JsonError::Write(format!("{:?} is not a valid XML element name", name)));
+        }
+        let indent = self.indent.repeat(depth);
+        match value {
+            // 数组写成同名的多个元素；嵌套数组会被展平，读回时无法还原
+            Value::Array(items) => {
+                for item in items {
+                    if item.is_array() {
+                        return Err(JsonError::Write(format!("<{}> holds an array of arrays, which XML cannot represent", name)));
+                    }
+                    self.element(out, name, item, depth)?;
+                }
+            }
+            Value::Null => out.push_str(&format!("{}<{}/>\n", indent, name)),
+            Value::Object(map) => {
+                let mut attributes = String::new();
+                let mut text = None;
+                let mut children = Vec::new();
+                for (key, value) in map {
+                    if let Some(attribute) = key.strip_prefix(ATTRIBUTE_PREFIX) {
+                        if !is_xml_name(attribute) {
+                            return Err(JsonError::Write(format!("{:?} is not a valid XML attribute name", attribute)));
+                        }
+                        attributes.push_str(&format!(" {}=\"{}\"", attribute, quick_xml::escape::escape(scalar_text(value))));
+                    } else if key == TEXT_KEY {
+                        text = Some(scalar_text(value));
+                    } else {
+                        children.push((key, value));
+                    }
+                }
+                match (text, children.is_empty()) {
+                    (None, true) => out.push_str(&format!("{}<{}{}/>\n", indent, name, attributes)),
+                    (Some(text), true) => out.push_str(&format!(
+                        "{}<{}{}>{}</{}>\n",
+                        indent,
+                        name,
+                        attributes,
+                        quick_xml::escape::escape(&text),
+                        name
+                    )),
+                    (text, false) => {
+                        out.push_str(&format!("{}<{}{}>\n", indent, name, attributes));
+                        if let Some(text) = text {
+                            out.push_str(&format!("{}{}{}\n", indent, self.indent, quick_xml::escape::escape(&text)));
+                        }
+                        for (key, value) in children {
+                            self.element(out, key, value, depth + 1)?;
+                        }
+                        out.push_str(&format!("{}</{}>\n", indent, name));
+                    }
+                }
+            }
+            scalar => out.push_str(&format!(
+                "{}<{}>{}</{}>\n",
+                indent,
+                name,
+                quick_xml::escape::escape(scalar_text(scalar)),
+                name
+            )),
+        }
+        Ok(())
+    }
+}
+
+/// Text of a scalar for an XML text node or a CSV field; arrays and objects are
+/// written as JSON.
+pub(crate) fn scalar_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Null => String::new(),
+        other => minify_value(other, &FormatOptions::default()).unwrap_or_default(),
+    }
+}
+
+fn is_xml_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_alphabetic() || c == '_' || c == ':')
+        && chars.all(|c| c.is_alphanumeric() || matches!(c, '_' | ':' | '-' | '.'))
+        && !name.to_ascii_lowercase().starts_with("xml")
+}
+
+/// Writes each item of an array on its own line; any other document is a single line.
+pub(crate) fn to_ndjson(json: &Value, options: &FormatOptions) -> Result<String, JsonError> {
+    let options = FormatOptions {
+        trailing_newline: false,
+        ..options.clone()
+    };
+    let mut out = String::new();
+    for item in json.as_array().map_or(std::slice::from_ref(json), Vec::as_slice) {
+        out.push_str(&minify_value(item, &options)?);
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json::{format_value, FormatType};
+    use serde_json::json;
+
+    #[test]
+    fn reads_multi_document_yaml() {
+        let input = "apiVersion: v1\nkind: Service\n---\nkind: Deployment\nspec:\n  replicas: 3\n  ratio: 0.5\n";
+        assert_eq!(
+            parse(input, SourceFormat::Yaml, &TableOptions::default()).unwrap(),
+            json!([{"apiVersion": "v1", "kind": "Service"}, {"kind": "Deployment", "spec": {"replicas": 3, "ratio": 0.5}}])
+        );
+        assert_eq!(parse("a: 1\n", SourceFormat::Yaml, &TableOptions::default()).unwrap(), json!({"a": 1}));
+        let error = parse("a: 1\n b: [\n", SourceFormat::Yaml, &TableOptions::default()).unwrap_err();
+        assert_eq!(error.location.map(|location| location.line), Some(2));
+    }
+
+    #[test]
+    fn round_trips_toml() {
+        let input = "[package]\nname = \"dev-tools\"\nedition = 2021\nreleased = 2024-01-02\n\n[dependencies]\nserde = { version = \"1\", features = [\"derive\"] }\n";
+        let value = parse(input, SourceFormat::Toml, &TableOptions::default()).unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "package": {"name": "dev-tools", "edition": 2021, "released": "2024-01-02"},
+                "dependencies": {"serde": {"version": "1", "features": ["derive"]}}
+            })
+        );
+        let written = format_value(&value, FormatType::Toml, &FormatOptions::default()).unwrap();
+        assert_eq!(parse(&written, SourceFormat::Toml, &TableOptions::default()).unwrap(), value);
+        assert!(format_value(&json!([1]), FormatType::Toml, &FormatOptions::default()).is_err());
+        let error = format_value(&json!({"a": {"b": null}}), FormatType::Toml, &FormatOptions::default()).unwrap_err();
+        assert_eq!(error.to_string(), "TOML has no null value, found at $.a.b");
+        let error = parse("a = \n", SourceFormat::Toml, &TableOptions::default()).unwrap_err();
+        assert_eq!(error.location.map(|location| location.line), Some(1));
+    }
+
+    #[test]
+    fn round_trips_xml() {
+        let input = r#"<?xml version="1.0"?>
+<catalog id="7">
+  <!-- books -->
+  <book lang="en">Dune &amp; more</book>
+  <book lang="fr"><title>Vendredi</title></book>
+  <empty/>
+  <note><![CDATA[<raw>]]></note>
+</catalog>"#;
+        let value = parse(input, SourceFormat::Xml, &TableOptions::default()).unwrap();
+        assert_eq!(
+            value,
+            json!({"catalog": {
+                "@id": "7",
+                "book": [{"@lang": "en", "#text": "Dune & more"}, {"@lang": "fr", "title": "Vendredi"}],
+                "empty": null,
+                "note": "<raw>"
+            }})
+        );
+        let written = format_value(&value, FormatType::Xml, &FormatOptions::default()).unwrap();
+        assert_eq!(
+            written,
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<catalog id=\"7\">\n  <book lang=\"en\">Dune &amp; more</book>\n  <book lang=\"fr\">\n    <title>Vendredi</title>\n  </book>\n  <empty/>\n  <note>&lt;raw&gt;</note>\n</catalog>\n"
+        );
+        assert_eq!(parse(&written, SourceFormat::Xml, &TableOptions::default()).unwrap(), value);
+        assert_eq!(parse("<a><b></a>", SourceFormat::Xml, &TableOptions::default()).unwrap_err().location.map(|l| l.column), Some(7));
+        assert!(format_value(&json!({"a b": 1}), FormatType::Xml, &FormatOptions::default()).is_err());
+
+        let value = json!({"table": {"row": [{"cell": ["a", "b"]}, {"cell": ["c", "d"]}]}});
+        let written = format_value(&value, FormatType::Xml, &FormatOptions::default()).unwrap();
+        assert_eq!(parse(&written, SourceFormat::Xml, &TableOptions::default()).unwrap(), value);
+        let error = format_value(&json!({"table": {"row": [["a", "b"], ["c"]]}}), FormatType::Xml, &FormatOptions::default()).unwrap_err();
+        assert_eq!(error.to_string(), "<row> holds an array of arrays, which XML cannot represent");
+        assert!(format_value(&json!([[1, 2]]), FormatType::Xml, &FormatOptions::default()).is_err());
+    }
+
+    #[test]
+    fn converts_csv_and_ndjson() {
+        let value = parse("id,name\n1,\"Smith, J\"\n2,Ann\n", SourceFormat::Csv, &TableOptions::default()).unwrap();
+        assert_eq!(value, json!([{"id": 1, "name": "Smith, J"}, {"id": 2, "name": "Ann"}]));
+        let rows = json!([{"id": 1, "tags": ["a"]}, {"id": 2, "extra": null}]);
+        assert_eq!(
+            format_value(&rows, FormatType::Csv, &FormatOptions::default()).unwrap(),
+            "id,tags,extra\n1,\"[\"\"a\"\"]\",\n2,,\n"
+        );
+        assert_eq!(parse("id\n1,2\n", SourceFormat::Csv, &TableOptions::default()).unwrap_err().location.map(|l| l.line), Some(2));
+
+        let value = parse("{\"a\":1}\n\n{\"a\":2.50}\n", SourceFormat::Ndjson, &TableOptions::default()).unwrap();
+        assert_eq!(value.to_string(), "[{\"a\":1},{\"a\":2.50}]");
+        assert_eq!(
+            format_value(&value, FormatType::Ndjson, &FormatOptions::default()).unwrap(),
+            "{\"a\":1}\n{\"a\":2.50}\n"
+        );
+        assert_eq!(parse("{}\n{\"a\" 1}\n", SourceFormat::Ndjson, &TableOptions::default()).unwrap_err().location.map(|l| l.line), Some(2));
+    }
+}
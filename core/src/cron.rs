@@ -0,0 +1,237 @@
+use chrono::{DateTime, Local, TimeZone};
+use cron::Schedule;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OutputFormat {
+    Default,     // 2024-01-01 12:00:00
+    ISO8601,     // 2024-01-01T12:00:00+08:00
+    Unix,        // 1704085200
+}
+
+impl OutputFormat {
+    /// Value used in query strings and on the command line, e.g. `/cron?output=iso8601`.
+    pub fn as_param(&self) -> &'static str {
+        match self {
+            OutputFormat::Default => "default",
+            OutputFormat::ISO8601 => "iso8601",
+            OutputFormat::Unix => "unix",
+        }
+    }
+
+    pub fn from_param(value: &str) -> Option<Self> {
+        match value {
+            "default" => Some(OutputFormat::Default),
+            "iso8601" => Some(OutputFormat::ISO8601),
+            "unix" => Some(OutputFormat::Unix),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum CronError {
+    /// The expression was rejected by the schedule parser.
+    Parse(String),
+    /// The expression does not have five fields (six with seconds).
+    FieldCount,
+}
+
+impl fmt::Display for CronError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CronError::Parse(message) => f.write_str(message),
+            CronError::FieldCount => f.write_str("expected 5 fields (6 with seconds)"),
+        }
+    }
+}
+
+impl std::error::Error for CronError {}
+
+/// The cron field a [`FieldDescription`] refers to.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Field {
+    Second,
+    Minute,
+    Hour,
+    Day,
+    Month,
+    Week,
+}
+
+/// What a single cron field matches, e.g. `*/5` is `Step("5")`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum FieldPattern {
+    Every,
+    Any,
+    Step(String),
+    Range(String, String),
+    List(String),
+    Value(String),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FieldDescription {
+    pub field: Field,
+    pub pattern: FieldPattern,
+}
+
+pub fn format_time<Tz: TimeZone>(time: &DateTime<Tz>, format: OutputFormat) -> String
+where
+    Tz::Offset: fmt::Display,
+{
+    match format {
+        OutputFormat::Default => time.format("%Y-%m-%d %H:%M:%S").to_string(),
+        OutputFormat::ISO8601 => time.to_rfc3339(),
+        OutputFormat::Unix => time.timestamp().to_string(),
+    }
+}
+
+pub fn parse_cron(expr: &str, include_seconds: bool) -> Result<Schedule, CronError> {
+    // 如果不包含秒，在表达式前添加 "0 "
+    let expr = if !include_seconds && !expr.trim().is_empty() {
+        format!("0 {}", expr)
+    } else {
+        expr.to_string()
+    };
+
+    Schedule::from_str(&expr).map_err(|e| CronError::Parse(e.to_string()))
+}
+
+/// The next `count` run times strictly after `after`.
+pub fn next_occurrences<Tz: TimeZone>(
+    schedule: &Schedule,
+    after: &DateTime<Tz>,
+    count: usize,
+) -> Vec<DateTime<Tz>> {
+    schedule.after(after).take(count).collect()
+}
+
+/// The next `count` run times from now in the local time zone, formatted.
+pub fn get_next_occurrences(schedule: &Schedule, count: usize, format: OutputFormat) -> Vec<String> {
+    next_occurrences(schedule, &Local::now(), count)
+        .iter()
+        .map(|time| format_time(time, format))
+        .collect()
+}
+
+pub fn describe_field(field: &str) -> FieldPattern {
+    match field {
+        "*" => FieldPattern::Every,
+        "?" => FieldPattern::Any,
+        field if field.contains('/') => {
+            let parts: Vec<&str> = field.split('/').collect();
+            if parts.len() == 2 {
+                FieldPattern::Step(parts[1].to_string())
+            } else {
+                FieldPattern::Value(field.to_string())
+            }
+        }
+        field if field.contains('-') => {
+            let parts: Vec<&str> = field.split('-').collect();
+            if parts.len() == 2 {
+                FieldPattern::Range(parts[0].to_string(), parts[1].to_string())
+            } else {
+                FieldPattern::Value(field.to_string())
+            }
+        }
+        field if field.contains(',') => FieldPattern::List(field.to_string()),
+        _ => FieldPattern::Value(field.to_string()),
+    }
+}
+
+pub fn describe_cron(expr: &str, include_seconds: bool) -> Result<Vec<FieldDescription>, CronError> {
+    let parts: Vec<&str> = expr.split_whitespace().collect();
+    let (seconds, rest) = if include_seconds {
+        match parts.split_first() {
+            Some((seconds, rest)) => (Some(*seconds), rest),
+            None => return Err(CronError::FieldCount),
+        }
+    } else {
+        (None, &parts[..])
+    };
+
+    if rest.len() != 5 {
+        return Err(CronError::FieldCount);
+    }
+
+    let fields = [Field::Minute, Field::Hour, Field::Day, Field::Month, Field::Week];
+    let desc = seconds
+        .map(|s| (Field::Second, s))
+        .into_iter()
+        .chain(fields.into_iter().zip(rest.iter().copied()))
+        .map(|(field, value)| FieldDescription {
+            field,
+            pattern: describe_field(value),
+        })
+        .collect();
+
+    Ok(desc)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    #[test]
+    fn parses_five_field_expressions() {
+        assert!(parse_cron("0 9 * * 1-5", false).is_ok());
+        assert!(parse_cron("0 0 9 * * 1-5", true).is_ok());
+    }
+
+    #[test]
+    fn rejects_invalid_expressions() {
+        assert!(matches!(parse_cron("61 * * * *", false), Err(CronError::Parse(_))));
+        assert!(parse_cron("", false).is_err());
+    }
+
+    #[test]
+    fn lists_next_occurrences_after_a_given_time() {
+        let schedule = parse_cron("0 9 * * *", false).unwrap();
+        let after = Utc.with_ymd_and_hms(2024, 1, 1, 10, 0, 0).unwrap();
+        let times: Vec<String> = next_occurrences(&schedule, &after, 2)
+            .iter()
+            .map(|time| format_time(time, OutputFormat::ISO8601))
+            .collect();
+        assert_eq!(times, ["2024-01-02T09:00:00+00:00", "2024-01-03T09:00:00+00:00"]);
+    }
+
+    #[test]
+    fn formats_times() {
+        let time = Utc.with_ymd_and_hms(2024, 1, 1, 4, 20, 0).unwrap();
+        assert_eq!(format_time(&time, OutputFormat::Default), "2024-01-01 04:20:00");
+        assert_eq!(format_time(&time, OutputFormat::Unix), "1704082800");
+    }
+
+    #[test]
+    fn describes_fields() {
+        let desc = describe_cron("*/5 9-17 ? 1,6 MON", false).unwrap();
+        let patterns: Vec<FieldPattern> = desc.into_iter().map(|d| d.pattern).collect();
+        assert_eq!(
+            patterns,
+            [
+                FieldPattern::Step("5".into()),
+                FieldPattern::Range("9".into(), "17".into()),
+                FieldPattern::Any,
+                FieldPattern::List("1,6".into()),
+                FieldPattern::Value("MON".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn describes_seconds_field() {
+        let desc = describe_cron("30 * * * * *", true).unwrap();
+        assert_eq!(desc[0].field, Field::Second);
+        assert_eq!(desc[0].pattern, FieldPattern::Value("30".into()));
+        assert_eq!(desc.len(), 6);
+    }
+
+    #[test]
+    fn describe_rejects_empty_input_with_seconds() {
+        assert_eq!(describe_cron("", true), Err(CronError::FieldCount));
+        assert_eq!(describe_cron("   ", false), Err(CronError::FieldCount));
+    }
+}
@@ -0,0 +1,317 @@
+//! Structural comparison of two JSON documents.
+//!
+//! Object keys are matched by name, so key order never counts as a change. Arrays are
+//! aligned on their longest common subsequence, or matched as multisets when
+//! [`DiffOptions::ignore_array_order`] is set. The changes come out in an order that
+//! is valid as an RFC 6902 JSON Patch, see [`to_patch`].
+
+use crate::path::JsonPath;
+use serde_json::{json, Value};
+
+/// Arrays whose differing middle parts would need a larger alignment table than
+/// this are compared element by element instead.
+const ALIGN_LIMIT: usize = 1_000_000;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DiffOptions {
+    pub ignore_array_order: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Change {
+    Added { path: JsonPath, value: Value },
+    Removed { path: JsonPath, value: Value },
+    Changed { path: JsonPath, old: Value, new: Value },
+}
+
+impl Change {
+    pub fn path(&self) -> &JsonPath {
+        match self {
+            Change::Added { path, .. } | Change::Removed { path, .. } | Change::Changed { path, .. } => path,
+        }
+    }
+
+    /// The value on the left side, if the path exists there.
+    pub fn left(&self) -> Option<&Value> {
+        match self {
+            Change::Added { .. } => None,
+            Change::Removed { value, .. } => Some(value),
+            Change::Changed { old, .. } => Some(old),
+        }
+    }
+
+    /// The value on the right side, if the path exists there.
+    pub fn right(&self) -> Option<&Value> {
+        match self {
+            Change::Added { value, .. } => Some(value),
+            Change::Removed { .. } => None,
+            Change::Changed { new, .. } => Some(new),
+        }
+    }
+}
+
+/// Changes that turn `left` into `right`.
+///
+/// Changed and removed array elements are addressed by their index in `left`, added
+/// ones by their index in `right`, which is what applying the changes in order needs.
+pub fn diff(left: &Value, right: &Value, options: &DiffOptions) -> Vec<Change> {
+    let mut changes = Vec::new();
+    walk(&JsonPath::root(), left, right, options, &mut changes);
+    changes
+}
+
+/// The changes as an RFC 6902 JSON Patch document.
+pub fn to_patch(changes: &[Change]) -> Value {
+    Value::Array(
+        changes
+            .iter()
+            .map(|change| match change {
+                Change::Added { path, value } => json!({"op": "add", "path": path.to_pointer(), "value": value}),
+                Change::Removed { path, .. } => json!({"op": "remove", "path": path.to_pointer()}),
+                Change::Changed { path, new, .. } => {
+                    json!({"op": "replace", "path": path.to_pointer(), "value": new})
+                }
+            })
+            .collect(),
+    )
+}
+
+fn walk(path: &JsonPath, left: &Value, right: &Value, options: &DiffOptions, out: &mut Vec<Change>) {
+    if left == right {
+        return;
+    }
+    match (left, right) {
+        (Value::Object(a), Value::Object(b)) => {
+            for (key, old) in a {
+                match b.get(key) {
+                    Some(new) => walk(&path.key(key), old, new, options, out),
+                    None => out.push(Change::Removed {
+                        path: path.key(key),
+                        value: old.clone(),
+                    }),
+                }
+            }
+            for (key, new) in b.iter().filter(|(key, _)| !a.contains_key(*key)) {
+                out.push(Change::Added {
+                    path: path.key(key),
+                    value: new.clone(),
+                });
+            }
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            let (pairs, removed, added) = if options.ignore_array_order {
+                match_unordered(a, b)
+            } else {
+                match_ordered(a, b)
+            };
+            // 先处理成对的元素，再从后往前删除，最后从前往后插入，这样下标始终有效
+            for (i, j) in pairs {
+                walk(&path.index(i), &a[i], &b[j], options, out);
+            }
+            for &i in removed.iter().rev() {
+                out.push(Change::Removed {
+                    path: path.index(i),
+                    value: a[i].clone(),
+                });
+            }
+            for (index, j) in added {
+                out.push(Change::Added {
+                    path: path.index(index),
+                    value: b[j].clone(),
+                });
+            }
+        }
+        _ => out.push(Change::Changed {
+            path: path.clone(),
+            old: left.clone(),
+            new: right.clone(),
+        }),
+    }
+}
+
+/// How two arrays line up: `(left, right)` index pairs to compare, ascending left
+/// indices to remove, and `(target index, right index)` elements to insert.
+type Alignment = (Vec<(usize, usize)>, Vec<usize>, Vec<(usize, usize)>);
+
+/// Aligns the arrays on their longest common subsequence. Within each gap between
+/// equal elements, removed and inserted elements are paired up and compared, so an
+/// edited object shows as changed fields rather than a removal and an insertion.
+fn match_ordered(a: &[Value], b: &[Value]) -> Alignment {
+    let prefix = a.iter().zip(b).take_while(|(x, y)| x == y).count();
+    let suffix = a[prefix..]
+        .iter()
+        .rev()
+        .zip(b[prefix..].iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+    let (a_mid, b_mid) = (&a[prefix..a.len() - suffix], &b[prefix..b.len() - suffix]);
+
+    let mut pairs = Vec::new();
+    let mut removed = Vec::new();
+    let mut added = Vec::new();
+    let mut gap = |deleted: &[usize], inserted: &[usize]| {
+        for (&i, &j) in deleted.iter().zip(inserted) {
+            pairs.push((prefix + i, prefix + j));
+        }
+        removed.extend(deleted.iter().skip(inserted.len()).map(|i| prefix + i));
+        added.extend(inserted.iter().skip(deleted.len()).map(|j| (prefix + j, prefix + j)));
+    };
+
+    let (mut deleted, mut inserted) = (Vec::new(), Vec::new());
+    for step in align(a_mid, b_mid) {
+        match step {
+            Step::Keep => {
+                gap(&deleted, &inserted);
+                deleted.clear();
+                inserted.clear();
+            }
+            Step::Delete(i) => deleted.push(i),
+            Step::Insert(j) => inserted.push(j),
+        }
+    }
+    gap(&deleted, &inserted);
+    (pairs, removed, added)
+}
+
+enum Step {
+    Keep,
+    Delete(usize),
+    Insert(usize),
+}
+
+fn align(a: &[Value], b: &[Value]) -> Vec<Step> {
+    let (n, m) = (a.len(), b.len());
+    if n.saturating_mul(m) > ALIGN_LIMIT {
+        return (0..n).map(Step::Delete).chain((0..m).map(Step::Insert)).collect();
+    }
+    // lengths[i][j]：a[i..] 与 b[j..] 的最长公共子序列长度
+    let mut lengths = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lengths[i][j] = if a[i] == b[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+    let (mut i, mut j) = (0, 0);
+    let mut steps = Vec::new();
+    while i < n || j < m {
+        if i < n && j < m && a[i] == b[j] {
+            steps.push(Step::Keep);
+            i += 1;
+            j += 1;
+        } else if j == m || (i < n && lengths[i + 1][j] >= lengths[i][j + 1]) {
+            steps.push(Step::Delete(i));
+            i += 1;
+        } else {
+            steps.push(Step::Insert(j));
+            j += 1;
+        }
+    }
+    steps
+}
+
+/// Matches equal elements regardless of position; the leftovers are paired up in
+/// order and the rest appended after the surviving elements.
+fn match_unordered(a: &[Value], b: &[Value]) -> Alignment {
+    let mut used = vec![false; b.len()];
+    let mut unmatched = Vec::new();
+    for (i, value) in a.iter().enumerate() {
+        match (0..b.len()).find(|&j| !used[j] && b[j] == *value) {
+            Some(j) => used[j] = true,
+            None => unmatched.push(i),
+        }
+    }
+    let extra: Vec<usize> = (0..b.len()).filter(|&j| !used[j]).collect();
+    let pairs: Vec<(usize, usize)> = unmatched.iter().copied().zip(extra.iter().copied()).collect();
+    let removed: Vec<usize> = unmatched.iter().skip(extra.len()).copied().collect();
+    let kept = a.len() - removed.len();
+    let added = extra
+        .iter()
+        .skip(unmatched.len())
+        .enumerate()
+        .map(|(offset, &j)| (kept + offset, j))
+        .collect();
+    (pairs, removed, added)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paths(changes: &[Change]) -> Vec<String> {
+        changes.iter().map(|change| change.path().to_string()).collect()
+    }
+
+    #[test]
+    fn ignores_key_order_and_reports_paths() {
+        let left = json!({"a": 1, "b": {"c": true, "d": [1]}, "gone": null});
+        let right = json!({"b": {"d": [1], "c": false}, "a": 1, "new": "x"});
+        let changes = diff(&left, &right, &DiffOptions::default());
+        assert_eq!(paths(&changes), ["$.b.c", "$.gone", "$.new"]);
+        assert_eq!(
+            changes[0],
+            Change::Changed {
+                path: JsonPath::root().key("b").key("c"),
+                old: json!(true),
+                new: json!(false),
+            }
+        );
+        assert_eq!(changes[1].right(), None);
+        assert_eq!(changes[2].left(), None);
+        assert!(diff(&left, &left, &DiffOptions::default()).is_empty());
+    }
+
+    #[test]
+    fn aligns_arrays() {
+        let options = DiffOptions::default();
+        let changes = diff(&json!([1, 2, 3]), &json!([0, 1, 2, 3]), &options);
+        assert_eq!(
+            changes,
+            [Change::Added {
+                path: JsonPath::root().index(0),
+                value: json!(0),
+            }]
+        );
+        let changes = diff(&json!([1, 2, 3, 4]), &json!([1, 4]), &options);
+        assert_eq!(paths(&changes), ["$[2]", "$[1]"]);
+        let left = json!([{"id": 1, "v": "a"}, {"id": 2, "v": "b"}]);
+        let right = json!([{"id": 1, "v": "a"}, {"id": 2, "v": "c"}]);
+        assert_eq!(paths(&diff(&left, &right, &options)), ["$[1].v"]);
+    }
+
+    #[test]
+    fn can_ignore_array_order() {
+        let options = DiffOptions {
+            ignore_array_order: true,
+        };
+        assert!(diff(&json!([1, 2, 3]), &json!([3, 1, 2]), &options).is_empty());
+        let changes = diff(&json!([1, 2, 2]), &json!([2, 1, 4, 5]), &options);
+        assert_eq!(paths(&changes), ["$[2]", "$[3]"]);
+        assert_eq!(changes[0].right(), Some(&json!(4)));
+        assert_eq!(changes[1].right(), Some(&json!(5)));
+    }
+
+    #[test]
+    fn exports_json_patch_in_applicable_order() {
+        let left = json!({"list": ["a", "b", "c", "d"], "x": 1, "~/k": 0});
+        let right = json!({"list": ["a", "B", "e"], "x": 2});
+        let changes = diff(&left, &right, &DiffOptions::default());
+        assert_eq!(
+            to_patch(&changes),
+            json!([
+                {"op": "replace", "path": "/list/1", "value": "B"},
+                {"op": "replace", "path": "/list/2", "value": "e"},
+                {"op": "remove", "path": "/list/3"},
+                {"op": "replace", "path": "/x", "value": 2},
+                {"op": "remove", "path": "/~0~1k"}
+            ])
+        );
+        assert_eq!(
+            to_patch(&diff(&json!(1), &json!("1"), &DiffOptions::default())),
+            json!([{"op": "replace", "path": "", "value": "1"}])
+        );
+    }
+}
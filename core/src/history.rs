@@ -0,0 +1,136 @@
+use crate::share::SharedState;
+use serde::{Deserialize, Serialize};
+
+/// Number of unpinned entries kept per tool.
+pub const DEFAULT_LIMIT: usize = 20;
+
+/// Size of one tool's serialized history, in bytes, that [`serialize_within`]
+/// keeps to. Browsers allow about 5 MB of storage for all tools together.
+pub const MAX_BYTES: usize = 1024 * 1024;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub state: SharedState,
+    /// Milliseconds since the Unix epoch.
+    pub timestamp: i64,
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+/// Adds `state` as the newest entry (entries are kept newest first).
+///
+/// An identical earlier entry is moved to the top, keeping its pin, instead of
+/// being duplicated. Unpinned entries beyond `limit` are dropped, oldest first.
+pub fn record(entries: &mut Vec<HistoryEntry>, state: SharedState, timestamp: i64, limit: usize) {
+    let pinned = match entries.iter().position(|entry| entry.state == state) {
+        Some(index) => entries.remove(index).pinned,
+        None => false,
+    };
+    entries.insert(0, HistoryEntry { state, timestamp, pinned });
+
+    let mut unpinned = 0;
+    entries.retain(|entry| {
+        if entry.pinned {
+            return true;
+        }
+        unpinned += 1;
+        unpinned <= limit
+    });
+}
+
+/// Serializes the entries as a JSON array, first dropping unpinned entries,
+/// oldest first, until it is at most `max_bytes` long. Pinned entries are
+/// always kept, so the result can still be longer.
+pub fn serialize_within(entries: &mut Vec<HistoryEntry>, max_bytes: usize) -> String {
+    let sizes: Vec<usize> = entries
+        .iter()
+        .map(|entry| serde_json::to_string(entry).map_or(0, |json| json.len()))
+        .collect();
+    // The brackets and the commas between entries
+    let mut total = 2 + sizes.iter().sum::<usize>() + sizes.len().saturating_sub(1);
+    let mut keep = vec![true; entries.len()];
+    for index in (0..entries.len()).rev() {
+        if total <= max_bytes {
+            break;
+        }
+        if !entries[index].pinned {
+            keep[index] = false;
+            total -= sizes[index] + 1;
+        }
+    }
+    let mut keep = keep.into_iter();
+    entries.retain(|_| keep.next().unwrap_or(true));
+    serde_json::to_string(entries).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(input: &str) -> SharedState {
+        SharedState::new(input.to_string())
+    }
+
+    fn inputs(entries: &[HistoryEntry]) -> Vec<&str> {
+        entries.iter().map(|entry| entry.state.input.as_str()).collect()
+    }
+
+    #[test]
+    fn keeps_newest_first() {
+        let mut entries = Vec::new();
+        record(&mut entries, state("a"), 1, 10);
+        record(&mut entries, state("b"), 2, 10);
+        assert_eq!(inputs(&entries), ["b", "a"]);
+    }
+
+    #[test]
+    fn moves_duplicates_to_the_top() {
+        let mut entries = Vec::new();
+        record(&mut entries, state("a"), 1, 10);
+        record(&mut entries, state("b"), 2, 10);
+        entries[1].pinned = true;
+        record(&mut entries, state("a"), 3, 10);
+        assert_eq!(inputs(&entries), ["a", "b"]);
+        assert!(entries[0].pinned);
+        assert_eq!(entries[0].timestamp, 3);
+    }
+
+    #[test]
+    fn options_distinguish_entries() {
+        let mut entries = Vec::new();
+        record(&mut entries, state("a").with_option("format", "yaml"), 1, 10);
+        record(&mut entries, state("a"), 2, 10);
+        assert_eq!(entries.len(), 2);
+    }
+
+    #[test]
+    fn drops_oldest_unpinned_beyond_limit() {
+        let mut entries = Vec::new();
+        record(&mut entries, state("pinned"), 0, 2);
+        entries[0].pinned = true;
+        for (timestamp, input) in ["a", "b", "c"].into_iter().enumerate() {
+            record(&mut entries, state(input), timestamp as i64 + 1, 2);
+        }
+        assert_eq!(inputs(&entries), ["c", "b", "pinned"]);
+    }
+
+    #[test]
+    fn drops_oldest_unpinned_to_fit_the_size() {
+        let mut entries = Vec::new();
+        for (timestamp, input) in ["old", "pinned", "middle", "new"].into_iter().enumerate() {
+            record(&mut entries, state(&input.repeat(100)), timestamp as i64, 10);
+        }
+        entries[2].pinned = true;
+        let whole = serde_json::to_string(&entries).unwrap();
+        assert_eq!(serialize_within(&mut entries, whole.len()), whole);
+        assert_eq!(entries.len(), 4);
+
+        let json = serialize_within(&mut entries, whole.len() - 1);
+        assert_eq!(inputs(&entries), ["new".repeat(100), "middle".repeat(100), "pinned".repeat(100)]);
+        assert!(json.len() < whole.len());
+        assert_eq!(serde_json::from_str::<Vec<HistoryEntry>>(&json).unwrap(), entries);
+
+        serialize_within(&mut entries, 0);
+        assert_eq!(inputs(&entries), ["pinned".repeat(100)]);
+    }
+}
@@ -0,0 +1,71 @@
+//! A draft 2020-12 JSON Schema. Nested objects are described inline.
+
+use super::{InferOptions, Kind, Shape};
+use crate::json::{format_value, FormatOptions, FormatType};
+use serde_json::{json, Map, Value};
+
+pub(super) fn generate(shape: &Shape, options: &InferOptions) -> String {
+    let mut schema = Map::new();
+    schema.insert("$schema".to_string(), json!("https://json-schema.org/draft/2020-12/schema"));
+    schema.insert("title".to_string(), json!(options.root_name));
+    schema.extend(describe(shape, options));
+    format_value(&Value::Object(schema), FormatType::Standard, &FormatOptions::default()).unwrap_or_default()
+}
+
+fn describe(shape: &Shape, options: &InferOptions) -> Map<String, Value> {
+    let mut schema = Map::new();
+    if let Some(values) = shape.enum_values(options) {
+        let mut values: Vec<Value> = values.iter().map(|value| json!(value)).collect();
+        if shape.null {
+            values.push(Value::Null);
+        }
+        schema.insert("enum".to_string(), Value::Array(values));
+        return schema;
+    }
+    let kinds = shape.kinds();
+    let mut types: Vec<&str> = kinds
+        .iter()
+        .map(|kind| match kind {
+            Kind::Boolean => "boolean",
+            Kind::Integer => "integer",
+            Kind::Number => "number",
+            Kind::String => "string",
+            Kind::Array => "array",
+            Kind::Object => "object",
+        })
+        .collect();
+    if shape.null {
+        types.push("null");
+    }
+    match types.as_slice() {
+        [] => {}
+        [single] => {
+            schema.insert("type".to_string(), json!(single));
+        }
+        _ => {
+            schema.insert("type".to_string(), json!(types));
+        }
+    }
+    // Keywords for one type are ignored by the others, so a mixed shape can carry them all
+    if let Some(element) = shape.array.as_deref().filter(|element| element.count > 0) {
+        schema.insert("items".to_string(), Value::Object(describe(element, options)));
+    }
+    if kinds.contains(&Kind::Object) {
+        let properties = shape
+            .fields
+            .iter()
+            .map(|(key, field)| (key.clone(), Value::Object(describe(field, options))))
+            .collect();
+        schema.insert("properties".to_string(), Value::Object(properties));
+        let required: Vec<Value> = shape
+            .fields
+            .iter()
+            .filter(|(_, field)| !shape.is_optional(field))
+            .map(|(key, _)| json!(key))
+            .collect();
+        if !required.is_empty() {
+            schema.insert("required".to_string(), Value::Array(required));
+        }
+    }
+    schema
+}
@@ -0,0 +1,439 @@
+//! Type inference from sample JSON documents, written out as JSON Schema, Rust
+//! serde structs or TypeScript interfaces.
+//!
+//! All samples are merged into one shape: a field missing from some objects is
+//! optional, a field that is sometimes `null` is nullable, and short strings that
+//! repeat across the samples become an enum.
+
+mod json_schema;
+mod rust;
+mod typescript;
+
+use crate::json::JsonError;
+use serde_json::Value;
+use std::collections::HashSet;
+
+/// A string field with more distinct values than this is never an enum.
+const ENUM_LIMIT: usize = 10;
+
+/// Strings longer than this, in characters, are never enum values.
+const ENUM_VALUE_LENGTH: usize = 32;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Target {
+    JsonSchema,
+    Rust,
+    TypeScript,
+}
+
+impl Target {
+    pub const ALL: [Target; 3] = [Target::JsonSchema, Target::Rust, Target::TypeScript];
+
+    pub fn as_param(&self) -> &'static str {
+        match self {
+            Target::JsonSchema => "json-schema",
+            Target::Rust => "rust",
+            Target::TypeScript => "typescript",
+        }
+    }
+
+    pub fn from_param(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|target| target.as_param() == value)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InferOptions {
+    /// Name of the top-level type; nested types are named after their keys.
+    pub root_name: String,
+    /// Turn strings that repeat across the samples into enums.
+    pub detect_enums: bool,
+}
+
+impl Default for InferOptions {
+    fn default() -> Self {
+        InferOptions {
+            root_name: "Root".to_string(),
+            detect_enums: true,
+        }
+    }
+}
+
+/// Reads one or more samples: a single document, or several separated by
+/// whitespace such as NDJSON.
+pub fn parse_samples(input: &str) -> Result<Vec<Value>, JsonError> {
+    serde_json::Deserializer::from_str(input)
+        .into_iter::<Value>()
+        .collect::<Result<_, _>>()
+        .map_err(JsonError::Parse)
+}
+
+/// Infers the type of the samples and writes it for `target`.
+pub fn generate(samples: &[Value], target: Target, options: &InferOptions) -> String {
+    let mut shape = Shape::default();
+    for sample in samples {
+        shape.add(sample);
+    }
+    let options = InferOptions {
+        root_name: Some(pascal_case(&options.root_name))
+            .filter(|name| is_type_name(name))
+            .unwrap_or_else(|| "Root".to_string()),
+        ..options.clone()
+    };
+    match target {
+        Target::JsonSchema => json_schema::generate(&shape, &options),
+        Target::Rust => rust::generate(&shape, &options),
+        Target::TypeScript => typescript::generate(&shape, &options),
+    }
+}
+
+/// The JSON types a shape has seen, not counting `null`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Kind {
+    Boolean,
+    Integer,
+    Number,
+    String,
+    Array,
+    Object,
+}
+
+/// Everything seen at one position across the samples.
+#[derive(Clone, Debug, Default, PartialEq)]
+struct Shape {
+    /// How many values were merged into this shape.
+    count: usize,
+    null: bool,
+    boolean: bool,
+    integer: bool,
+    /// Numbers written with a fraction or an exponent.
+    float: bool,
+    /// How many of the values were strings.
+    strings: usize,
+    /// The distinct strings, or `None` once they can no longer form an enum.
+    string_values: Option<Vec<String>>,
+    /// The merged shape of every array element.
+    array: Option<Box<Shape>>,
+    /// How many of the values were objects.
+    objects: usize,
+    /// Every key of every object, in the order first seen.
+    fields: Vec<(String, Shape)>,
+}
+
+impl Shape {
+    fn add(&mut self, value: &Value) {
+        self.count += 1;
+        match value {
+            Value::Null => self.null = true,
+            Value::Bool(_) => self.boolean = true,
+            // Judged by the number text so integers beyond i64 still count
+            Value::Number(number) => {
+                if number.to_string().contains(['.', 'e', 'E']) {
+                    self.float = true;
+                } else {
+                    self.integer = true;
+                }
+            }
+            Value::String(text) => {
+                self.strings += 1;
+                if self.strings == 1 {
+                    self.string_values = Some(Vec::new());
+                }
+                if let Some(values) = &mut self.string_values {
+                    if text.chars().count() > ENUM_VALUE_LENGTH || (values.len() == ENUM_LIMIT && !values.contains(text)) {
+                        self.string_values = None;
+                    } else if !values.contains(text) {
+                        values.push(text.clone());
+                    }
+                }
+            }
+            Value::Array(items) => {
+                let shape = self.array.get_or_insert_with(Default::default);
+                for item in items {
+                    shape.add(item);
+                }
+            }
+            Value::Object(map) => {
+                self.objects += 1;
+                for (key, value) in map {
+                    match self.fields.iter_mut().find(|(name, _)| name == key) {
+                        Some((_, shape)) => shape.add(value),
+                        None => {
+                            let mut shape = Shape::default();
+                            shape.add(value);
+                            self.fields.push((key.clone(), shape));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn kinds(&self) -> Vec<Kind> {
+        let mut kinds = Vec::new();
+        if self.boolean {
+            kinds.push(Kind::Boolean);
+        }
+        if self.float {
+            kinds.push(Kind::Number);
+        } else if self.integer {
+            kinds.push(Kind::Integer);
+        }
+        if self.strings > 0 {
+            kinds.push(Kind::String);
+        }
+        if self.array.is_some() {
+            kinds.push(Kind::Array);
+        }
+        if self.objects > 0 {
+            kinds.push(Kind::Object);
+        }
+        kinds
+    }
+
+    /// The values of a string-only shape whose strings repeat, so that they look
+    /// like a fixed set rather than free text.
+    fn enum_values(&self, options: &InferOptions) -> Option<&[String]> {
+        let values = self.string_values.as_deref()?;
+        (options.detect_enums && self.kinds() == [Kind::String] && self.strings >= 2 && values.len() < self.strings)
+            .then_some(values)
+    }
+
+    /// Whether a field was missing from some of the objects.
+    fn is_optional(&self, field: &Shape) -> bool {
+        field.count < self.objects
+    }
+}
+
+/// Splits a key into lowercase words at punctuation and case changes, so
+/// `firstName`, `first_name` and `First Name` all give `first`, `name`.
+fn words(text: &str) -> Vec<String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut words = Vec::new();
+    let mut current = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            if !current.is_empty() {
+                words.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+        if c.is_uppercase() && !current.is_empty() {
+            let previous = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).is_some_and(|next| next.is_lowercase());
+            // `userID` breaks before `ID`, `HTTPServer` before `Server`
+            if previous.is_lowercase() || previous.is_numeric() || (previous.is_uppercase() && next_is_lower) {
+                words.push(std::mem::take(&mut current));
+            }
+        }
+        current.extend(c.to_lowercase());
+    }
+    if !current.is_empty() {
+        words.push(current);
+    }
+    words
+}
+
+fn pascal_case(text: &str) -> String {
+    words(text)
+        .iter()
+        .map(|word| {
+            let mut chars = word.chars();
+            chars.next().map_or(String::new(), |first| first.to_uppercase().chain(chars).collect())
+        })
+        .collect()
+}
+
+fn snake_case(text: &str) -> String {
+    words(text).join("_")
+}
+
+fn is_type_name(name: &str) -> bool {
+    name.chars().next().is_some_and(char::is_alphabetic)
+}
+
+/// Name for the elements of an array type: `Categories` gives `Category`,
+/// `Data` gives `DataItem`.
+fn singular(name: &str) -> String {
+    if let Some(stem) = name.strip_suffix("ies").filter(|stem| !stem.is_empty()) {
+        return format!("{}y", stem);
+    }
+    if ["sses", "xes", "ches", "shes"].iter().any(|suffix| name.ends_with(suffix)) {
+        return name[..name.len() - 2].to_string();
+    }
+    match name.strip_suffix('s') {
+        Some(stem) if !stem.is_empty() && !stem.ends_with('s') => stem.to_string(),
+        _ => format!("{}Item", name),
+    }
+}
+
+/// Hands out type names, numbering repeats: `Item`, `Item2`, ...
+#[derive(Default)]
+struct TypeNames {
+    used: HashSet<String>,
+}
+
+impl TypeNames {
+    /// Names that generated types must not shadow, such as those the output refers to.
+    fn reserving(names: &[&str]) -> Self {
+        TypeNames { used: names.iter().map(ToString::to_string).collect() }
+    }
+
+    fn claim(&mut self, name: &str) -> String {
+        let name = if is_type_name(name) { name.to_string() } else { format!("Type{}", name) };
+        let mut candidate = name.clone();
+        let mut number = 2;
+        while !self.used.insert(candidate.clone()) {
+            candidate = format!("{}{}", name, number);
+            number += 1;
+        }
+        candidate
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn samples() -> Vec<Value> {
+        parse_samples(
+            r#"
+            {"id": 1, "userName": "ada", "status": "active", "score": 9.5, "tags": ["a"], "address": {"city": "London"}}
+            {"id": 2, "userName": "bob", "status": "inactive", "score": 7, "tags": [], "address": null, "type": "x"}
+            {"id": 3, "userName": "cy", "status": "active", "score": 8, "tags": ["b", "c"], "address": {"city": "Paris", "zip": "75001"}}
+            "#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn splits_names_into_words() {
+        assert_eq!(words("userID"), ["user", "id"]);
+        assert_eq!(words("HTTPServer_port-2"), ["http", "server", "port", "2"]);
+        assert_eq!(pascal_case("first name"), "FirstName");
+        assert_eq!(snake_case("firstName"), "first_name");
+        assert_eq!(singular("Categories"), "Category");
+        assert_eq!(singular("Boxes"), "Box");
+        assert_eq!(singular("Items"), "Item");
+        assert_eq!(singular("Address"), "AddressItem");
+        let mut names = TypeNames::default();
+        assert_eq!([names.claim("Item"), names.claim("Item"), names.claim("2")], ["Item", "Item2", "Type2"]);
+    }
+
+    #[test]
+    fn merges_samples() {
+        let mut shape = Shape::default();
+        for sample in samples() {
+            shape.add(&sample);
+        }
+        let field = |name: &str| &shape.fields.iter().find(|(key, _)| key == name).unwrap().1;
+        assert!(!shape.is_optional(field("id")));
+        assert!(shape.is_optional(field("type")));
+        assert!(field("address").null);
+        assert_eq!(field("score").kinds(), [Kind::Number]);
+        let options = InferOptions::default();
+        assert_eq!(field("status").enum_values(&options), Some(&["active".to_string(), "inactive".to_string()][..]));
+        assert_eq!(field("userName").enum_values(&options), None);
+    }
+
+    #[test]
+    fn generates_rust() {
+        let expected = r#"use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Order {
+    pub id: i64,
+    #[serde(rename = "userName")]
+    pub user_name: String,
+    pub status: Status,
+    pub score: f64,
+    pub tags: Vec<String>,
+    pub address: Option<Address>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub r#type: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Status {
+    #[serde(rename = "active")]
+    Active,
+    #[serde(rename = "inactive")]
+    Inactive,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Address {
+    pub city: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub zip: Option<String>,
+}
+"#;
+        let options = InferOptions {
+            root_name: "order".to_string(),
+            ..InferOptions::default()
+        };
+        assert_eq!(generate(&samples(), Target::Rust, &options), expected);
+        assert_eq!(
+            generate(&[json!([1, 2])], Target::Rust, &InferOptions::default()),
+            "pub type Root = Vec<i64>;\n"
+        );
+        let expected = r#"use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Root {
+    pub string: String2,
+    pub value: Value2,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct String2 {
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Value2 {
+    pub items: Vec<String>,
+}
+"#;
+        let sample = json!({"string": {"text": "a"}, "value": {"items": ["b"]}});
+        assert_eq!(generate(&[sample], Target::Rust, &InferOptions::default()), expected);
+    }
+
+    #[test]
+    fn generates_typescript() {
+        let expected = r#"export interface Root {
+  id: number;
+  userName: string;
+  status: "active" | "inactive";
+  score: number;
+  tags: string[];
+  address: Address | null;
+  type?: string;
+}
+
+export interface Address {
+  city: string;
+  zip?: string;
+}
+"#;
+        assert_eq!(generate(&samples(), Target::TypeScript, &InferOptions::default()), expected);
+        assert_eq!(
+            generate(&[json!([{"a-b": 1}, {"a-b": "x"}])], Target::TypeScript, &InferOptions::default()),
+            "export type Root = RootItem[];\n\nexport interface RootItem {\n  \"a-b\": number | string;\n}\n"
+        );
+    }
+
+    #[test]
+    fn generates_a_schema_the_samples_pass() {
+        let samples = samples();
+        let schema = generate(&samples, Target::JsonSchema, &InferOptions::default());
+        let schema: Value = serde_json::from_str(&schema).unwrap();
+        assert_eq!(schema["properties"]["address"]["type"], json!(["object", "null"]));
+        assert_eq!(schema["properties"]["status"]["enum"], json!(["active", "inactive"]));
+        assert_eq!(schema["required"], json!(["id", "userName", "status", "score", "tags", "address"]));
+        for sample in &samples {
+            assert_eq!(crate::schema::validate(&schema, sample, None).unwrap(), []);
+        }
+    }
+}
@@ -0,0 +1,161 @@
+//! Rust structs and enums deriving serde's `Serialize` and `Deserialize`.
+
+use super::{pascal_case, singular, snake_case, InferOptions, Kind, Shape, TypeNames};
+
+/// Keywords that can be used as raw identifiers, e.g. `r#type`.
+const KEYWORDS: [&str; 47] = [
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn", "else",
+    "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in", "let", "loop", "macro",
+    "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return", "static", "struct", "trait",
+    "true", "try", "type", "typeof", "unsafe", "unsized", "use", "virtual", "where", "while",
+];
+
+/// Keywords that cannot be raw identifiers either.
+const RESERVED: [&str; 4] = ["crate", "self", "Self", "super"];
+
+const VALUE: &str = "serde_json::Value";
+
+/// Types the generated code uses by their short names, which a struct named
+/// after a field such as `string` would otherwise shadow.
+const USED_TYPES: [&str; 6] = ["String", "Option", "Vec", "Value", "Serialize", "Deserialize"];
+
+pub(super) fn generate(shape: &Shape, options: &InferOptions) -> String {
+    let mut generator = Generator {
+        options,
+        names: TypeNames::reserving(&USED_TYPES),
+        items: Vec::new(),
+    };
+    let root = generator.names.claim(&options.root_name);
+    if shape.kinds() == [Kind::Object] && !shape.null {
+        generator.structure(shape, root);
+    } else {
+        // The alias comes first, ahead of the types it refers to
+        generator.items.push(String::new());
+        let ty = generator.type_of(shape, &root, false);
+        generator.items[0] = format!("pub type {} = {};\n", root, ty);
+    }
+    let mut output = String::new();
+    if generator.items.iter().any(|item| item.starts_with("#[derive")) {
+        output.push_str("use serde::{Deserialize, Serialize};\n\n");
+    }
+    output.push_str(&generator.items.join("\n"));
+    output
+}
+
+struct Generator<'a> {
+    options: &'a InferOptions,
+    names: TypeNames,
+    /// Type definitions, outer types before the types they use.
+    items: Vec<String>,
+}
+
+impl Generator<'_> {
+    /// The Rust type for `shape`, defining structs and enums named after `name`
+    /// as needed. Optional and nullable values become `Option`.
+    fn type_of(&mut self, shape: &Shape, name: &str, optional: bool) -> String {
+        let ty = match shape.kinds().as_slice() {
+            [] => VALUE.to_string(),
+            [Kind::Boolean] => "bool".to_string(),
+            [Kind::Integer] => "i64".to_string(),
+            [Kind::Number] => "f64".to_string(),
+            [Kind::String] => match shape.enum_values(self.options) {
+                Some(values) => {
+                    let name = self.names.claim(name);
+                    self.enumeration(values, name)
+                }
+                None => "String".to_string(),
+            },
+            [Kind::Array] => {
+                let element = shape.array.as_deref().expect("arrays have an element shape");
+                format!("Vec<{}>", self.type_of(element, &singular(name), false))
+            }
+            [Kind::Object] => {
+                let name = self.names.claim(name);
+                self.structure(shape, name)
+            }
+            _ => VALUE.to_string(),
+        };
+        // `Value` holds `null` itself, but a missing field still needs `Option`
+        if optional || (shape.null && ty != VALUE) {
+            format!("Option<{}>", ty)
+        } else {
+            ty
+        }
+    }
+
+    fn structure(&mut self, shape: &Shape, name: String) -> String {
+        let index = self.items.len();
+        self.items.push(String::new());
+        let mut item = format!("#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]\npub struct {} {{\n", name);
+        let mut used = Vec::new();
+        for (key, field) in &shape.fields {
+            let optional = shape.is_optional(field);
+            let ty = self.type_of(field, &pascal_case(key), optional);
+            let ident = unique(field_name(key), &mut used);
+            let mut attributes = Vec::new();
+            if ident.trim_start_matches("r#") != key {
+                attributes.push(format!("rename = {:?}", key));
+            }
+            if optional {
+                attributes.push("skip_serializing_if = \"Option::is_none\"".to_string());
+            }
+            if !attributes.is_empty() {
+                item.push_str(&format!("    #[serde({})]\n", attributes.join(", ")));
+            }
+            item.push_str(&format!("    pub {}: {},\n", ident, ty));
+        }
+        item.push_str("}\n");
+        self.items[index] = item;
+        name
+    }
+
+    fn enumeration(&mut self, values: &[String], name: String) -> String {
+        let mut item = format!(
+            "#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]\npub enum {} {{\n",
+            name
+        );
+        let mut used = Vec::new();
+        for value in values {
+            let variant = match pascal_case(value) {
+                variant if variant.is_empty() => "Empty".to_string(),
+                variant if variant.starts_with(|c: char| c.is_numeric()) => format!("V{}", variant),
+                variant if RESERVED.contains(&variant.as_str()) => format!("{}Value", variant),
+                variant => variant,
+            };
+            let variant = unique(variant, &mut used);
+            if &variant != value {
+                item.push_str(&format!("    #[serde(rename = {:?})]\n", value));
+            }
+            item.push_str(&format!("    {},\n", variant));
+        }
+        item.push_str("}\n");
+        self.items.push(item);
+        name
+    }
+}
+
+/// A snake_case field name for `key`, made into a valid identifier.
+fn field_name(key: &str) -> String {
+    let name = snake_case(key);
+    if name.is_empty() || name.starts_with(|c: char| c.is_numeric()) {
+        format!("field_{}", name).trim_end_matches('_').to_string()
+    } else if RESERVED.contains(&name.as_str()) {
+        format!("{}_", name)
+    } else if KEYWORDS.contains(&name.as_str()) {
+        format!("r#{}", name)
+    } else {
+        name
+    }
+}
+
+/// Numbers `name` if it is already in `used`, then records it.
+fn unique(name: String, used: &mut Vec<String>) -> String {
+    let mut candidate = name.clone();
+    let mut number = 2;
+    while used.contains(&candidate) {
+        candidate = format!("{}{}", name, number);
+        number += 1;
+    }
+    used.push(candidate.clone());
+    candidate
+}
@@ -0,0 +1,84 @@
+//! TypeScript interfaces. Enums are written inline as unions of string literals.
+
+use super::{pascal_case, singular, InferOptions, Kind, Shape, TypeNames};
+
+pub(super) fn generate(shape: &Shape, options: &InferOptions) -> String {
+    let mut generator = Generator {
+        options,
+        names: TypeNames::default(),
+        items: Vec::new(),
+    };
+    let root = generator.names.claim(&options.root_name);
+    if shape.kinds() == [Kind::Object] && !shape.null {
+        generator.interface(shape, root);
+    } else {
+        generator.items.push(String::new());
+        let ty = generator.type_of(shape, &root);
+        generator.items[0] = format!("export type {} = {};\n", root, ty);
+    }
+    generator.items.join("\n")
+}
+
+struct Generator<'a> {
+    options: &'a InferOptions,
+    names: TypeNames,
+    /// Declarations, outer types before the types they use.
+    items: Vec<String>,
+}
+
+impl Generator<'_> {
+    fn type_of(&mut self, shape: &Shape, name: &str) -> String {
+        let mut types = Vec::new();
+        for kind in shape.kinds() {
+            match kind {
+                Kind::Boolean => types.push("boolean".to_string()),
+                Kind::Integer | Kind::Number => types.push("number".to_string()),
+                Kind::String => match shape.enum_values(self.options) {
+                    Some(values) => types.extend(values.iter().map(|value| serde_json::Value::from(value.as_str()).to_string())),
+                    None => types.push("string".to_string()),
+                },
+                Kind::Array => {
+                    let element = shape.array.as_deref().expect("arrays have an element shape");
+                    let element = self.type_of(element, &singular(name));
+                    types.push(if element.contains(' ') { format!("({})[]", element) } else { format!("{}[]", element) });
+                }
+                Kind::Object => {
+                    let name = self.names.claim(name);
+                    types.push(self.interface(shape, name));
+                }
+            }
+        }
+        if shape.null {
+            types.push("null".to_string());
+        }
+        if types.is_empty() {
+            return "unknown".to_string();
+        }
+        types.join(" | ")
+    }
+
+    fn interface(&mut self, shape: &Shape, name: String) -> String {
+        let index = self.items.len();
+        self.items.push(String::new());
+        let mut item = format!("export interface {} {{\n", name);
+        for (key, field) in &shape.fields {
+            let ty = self.type_of(field, &pascal_case(key));
+            let optional = if shape.is_optional(field) { "?" } else { "" };
+            item.push_str(&format!("  {}{}: {};\n", property_name(key), optional, ty));
+        }
+        item.push_str("}\n");
+        self.items[index] = item;
+        name
+    }
+}
+
+/// The key as written in an interface, quoted unless it is an identifier.
+fn property_name(key: &str) -> String {
+    let identifier = key.starts_with(|c: char| c.is_alphabetic() || c == '_' || c == '$')
+        && key.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '$');
+    if identifier {
+        key.to_string()
+    } else {
+        serde_json::Value::from(key).to_string()
+    }
+}
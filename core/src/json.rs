@@ -0,0 +1,1182 @@
+use crate::convert;
+use crate::path::{JsonPath, Segment};
+use crate::table::{self, TableOptions};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::borrow::Cow;
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum FormatType {
+    Standard,
+    SingleQuote,
+    NoQuote,
+    /// A Python literal: single quotes, `None`, `True` and `False`.
+    Python,
+    Yaml,
+    Toml,
+    Xml,
+    Csv,
+    /// One line of JSON per array item.
+    Ndjson,
+}
+
+impl FormatType {
+    pub const ALL: [FormatType; 9] = [
+        FormatType::Standard,
+        FormatType::SingleQuote,
+        FormatType::NoQuote,
+        FormatType::Python,
+        FormatType::Yaml,
+        FormatType::Toml,
+        FormatType::Xml,
+        FormatType::Csv,
+        FormatType::Ndjson,
+    ];
+
+    /// Value used in query strings and on the command line, e.g. `/json?format=yaml`.
+    pub fn as_param(&self) -> &'static str {
+        match self {
+            FormatType::Standard => "standard",
+            FormatType::SingleQuote => "single_quote",
+            FormatType::NoQuote => "no_quote",
+            FormatType::Python => "python",
+            FormatType::Yaml => "yaml",
+            FormatType::Toml => "toml",
+            FormatType::Xml => "xml",
+            FormatType::Csv => "csv",
+            FormatType::Ndjson => "ndjson",
+        }
+    }
+
+    pub fn from_param(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|format_type| format_type.as_param() == value)
+    }
+}
+
+/// A departure from strict JSON accepted by [`parse_lenient`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Relaxation {
+    /// `// line` or `/* block */` comments.
+    Comments,
+    /// A comma before a closing `]` or `}`.
+    TrailingCommas,
+    /// Strings in single quotes, e.g. `'value'`.
+    SingleQuotes,
+    /// Object keys without quotes, e.g. `{key: 1}`.
+    UnquotedKeys,
+}
+
+impl Relaxation {
+    pub fn as_param(&self) -> &'static str {
+        match self {
+            Relaxation::Comments => "comments",
+            Relaxation::TrailingCommas => "trailing_commas",
+            Relaxation::SingleQuotes => "single_quotes",
+            Relaxation::UnquotedKeys => "unquoted_keys",
+        }
+    }
+}
+
+/// Where parsing failed: 1-based line and column, the column counted in characters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErrorLocation {
+    pub line: usize,
+    pub column: usize,
+}
+
+/// One line of input shown around a parse error.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ContextLine {
+    /// 1-based line number.
+    pub number: usize,
+    pub text: String,
+    /// Whether this is the line the error points at.
+    pub is_error: bool,
+}
+
+#[derive(Debug)]
+pub enum JsonError {
+    /// The input is not valid JSON.
+    Parse(serde_json::Error),
+    /// The input is not valid even in lenient mode. The location refers to the
+    /// original input rather than the rewritten text that was parsed.
+    Lenient(serde_json_lenient::Error, Option<ErrorLocation>),
+    /// The parsed document could not be written back as JSON.
+    Serialize(serde_json::Error),
+    /// The parsed document could not be written as YAML.
+    Yaml(serde_yaml::Error),
+    /// The parsed document cannot be expressed in the target format, e.g. TOML
+    /// without a table at the top.
+    Write(String),
+}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JsonError::Parse(e) | JsonError::Serialize(e) => e.fmt(f),
+            JsonError::Lenient(e, location) => {
+                let message = e.to_string();
+                match (location, message.rsplit_once(" at line ")) {
+                    (Some(location), Some((message, _))) => {
+                        write!(f, "{} at line {} column {}", message, location.line, location.column)
+                    }
+                    _ => f.write_str(&message),
+                }
+            }
+            JsonError::Yaml(e) => e.fmt(f),
+            JsonError::Write(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for JsonError {}
+
+impl JsonError {
+    /// Location of a parse error within `input`, the text that was parsed.
+    pub fn location(&self, input: &str) -> Option<ErrorLocation> {
+        match self {
+            JsonError::Parse(e) if e.line() > 0 => {
+                Some(location_at(input, offset_of(input, e.line(), e.column())))
+            }
+            JsonError::Lenient(_, location) => *location,
+            _ => None,
+        }
+    }
+}
+
+/// Byte offset of a 1-based line and byte column as reported by `serde_json`.
+fn offset_of(input: &str, line: usize, column: usize) -> usize {
+    let line_start = if line <= 1 {
+        0
+    } else {
+        input
+            .match_indices('\n')
+            .nth(line - 2)
+            .map_or(input.len(), |(index, _)| index + 1)
+    };
+    let line_end = input[line_start..].find('\n').map_or(input.len(), |end| line_start + end);
+    (line_start + column.saturating_sub(1)).min(line_end)
+}
+
+pub(crate) fn location_at(input: &str, mut offset: usize) -> ErrorLocation {
+    offset = offset.min(input.len());
+    while !input.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    let before = &input[..offset];
+    let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+    ErrorLocation {
+        line: before.matches('\n').count() + 1,
+        column: before[line_start..].chars().count() + 1,
+    }
+}
+
+/// The error line and up to `radius` lines on either side of it.
+pub fn error_context(input: &str, location: ErrorLocation, radius: usize) -> Vec<ContextLine> {
+    let first = location.line.saturating_sub(radius).max(1);
+    input
+        .lines()
+        .enumerate()
+        .map(|(index, text)| (index + 1, text))
+        .skip(first - 1)
+        .take_while(|(number, _)| *number <= location.line + radius)
+        .map(|(number, text)| ContextLine {
+            number,
+            text: text.to_string(),
+            is_error: number == location.line,
+        })
+        .collect()
+}
+
+/// Where the value at `path` starts in `input`, found by scanning the text rather
+/// than parsing it, so comments, single quotes and unquoted keys are tolerated.
+pub fn locate(input: &str, path: &JsonPath) -> Option<ErrorLocation> {
+    let bytes = input.as_bytes();
+    let mut i = skip_insignificant(bytes, 0);
+    for segment in path.segments() {
+        match (bytes.get(i)?, segment) {
+            (b'{', Segment::Key(wanted)) => loop {
+                i = skip_insignificant(bytes, i + 1);
+                let key_end = match bytes.get(i)? {
+                    b'}' => return None,
+                    quote @ (b'"' | b'\'') => string_end(bytes, i, *quote),
+                    _ => i + bytes[i..].iter().position(|&b| b == b':' || b.is_ascii_whitespace())?,
+                };
+                let raw = &input[i..key_end];
+                let key = match bytes[i] {
+                    b'"' => serde_json::from_str::<String>(raw).unwrap_or_default(),
+                    b'\'' => raw[1..raw.len().saturating_sub(1).max(1)].to_string(),
+                    _ => raw.to_string(),
+                };
+                i = skip_insignificant(bytes, key_end);
+                if bytes.get(i) != Some(&b':') {
+                    return None;
+                }
+                i = skip_insignificant(bytes, i + 1);
+                if key == *wanted {
+                    break;
+                }
+                i = skip_insignificant(bytes, value_end(bytes, i));
+                if bytes.get(i) != Some(&b',') {
+                    return None;
+                }
+            },
+            (b'[', Segment::Index(wanted)) => {
+                i = skip_insignificant(bytes, i + 1);
+                for _ in 0..*wanted {
+                    if bytes.get(i) == Some(&b']') {
+                        return None;
+                    }
+                    i = skip_insignificant(bytes, value_end(bytes, i));
+                    if bytes.get(i) != Some(&b',') {
+                        return None;
+                    }
+                    i = skip_insignificant(bytes, i + 1);
+                }
+                if bytes.get(i) == Some(&b']') {
+                    return None;
+                }
+            }
+            _ => return None,
+        }
+    }
+    (i < bytes.len()).then(|| location_at(input, i))
+}
+
+/// Index just past the value starting at `start`.
+fn value_end(bytes: &[u8], start: usize) -> usize {
+    let mut depth = 0usize;
+    let mut i = start;
+    while i < bytes.len() {
+        match bytes[i] {
+            quote @ (b'"' | b'\'') => {
+                i = string_end(bytes, i, quote);
+                if depth == 0 {
+                    return i;
+                }
+                continue;
+            }
+            b'/' if matches!(bytes.get(i + 1), Some(b'/' | b'*')) => {
+                if depth == 0 {
+                    return i;
+                }
+                i = comment_end(bytes, i);
+                continue;
+            }
+            b'{' | b'[' => depth += 1,
+            b'}' | b']' if depth == 0 => return i,
+            b'}' | b']' => {
+                depth -= 1;
+                if depth == 0 {
+                    return i + 1;
+                }
+            }
+            b',' | b' ' | b'\n' | b'\t' | b'\r' if depth == 0 => return i,
+            _ => {}
+        }
+        i += 1;
+    }
+    bytes.len()
+}
+
+pub fn parse(input: &str) -> Result<Value, JsonError> {
+    serde_json::from_str(input).map_err(JsonError::Parse)
+}
+
+/// Parses JSON that may contain comments, trailing commas, single-quoted strings
+/// and unquoted keys, returning the relaxations the input actually relied on.
+pub fn parse_lenient(input: &str) -> Result<(Value, Vec<Relaxation>), JsonError> {
+    let relaxed = relax(input);
+    let mut deserializer = serde_json_lenient::Deserializer::from_str(&relaxed.text);
+    deserializer.set_allow_comments(true);
+    deserializer.set_ignore_trailing_commas(true);
+    // 先解析成 serde_json_lenient 的 Value 再转换，保留键的顺序和数字的原文
+    let value = serde_json_lenient::Value::deserialize(&mut deserializer)
+        .and_then(|value| deserializer.end().map(|()| value))
+        .and_then(|value| serde_json_lenient::to_string(&value))
+        .map_err(|e| {
+            let location = (e.line() > 0).then(|| {
+                let offset = offset_of(&relaxed.text, e.line(), e.column());
+                location_at(input, relaxed.original_offset(offset))
+            });
+            JsonError::Lenient(e, location)
+        })?;
+    let value = serde_json::from_str(&value).map_err(JsonError::Parse)?;
+    Ok((value, relaxed.relaxations))
+}
+
+struct Relaxed {
+    text: String,
+    relaxations: Vec<Relaxation>,
+    /// Pairs of `(offset in text, offset in input)` around each rewritten token;
+    /// the two agree byte for byte between anchors.
+    anchors: Vec<(usize, usize)>,
+}
+
+impl Relaxed {
+    fn original_offset(&self, offset: usize) -> usize {
+        let index = self.anchors.partition_point(|&(text, _)| text <= offset);
+        match index.checked_sub(1).map(|index| self.anchors[index]) {
+            Some((text, input)) => {
+                let next = self.anchors.get(index).map_or(usize::MAX, |&(_, input)| input);
+                (input + (offset - text)).min(next)
+            }
+            None => offset,
+        }
+    }
+}
+
+/// Rewrites single-quoted strings and unquoted keys as standard JSON and records
+/// every relaxation found. Comments and trailing commas are left for
+/// `serde_json_lenient` to skip.
+fn relax(input: &str) -> Relaxed {
+    let mut output = String::with_capacity(input.len());
+    let mut relaxations = Vec::new();
+    let mut anchors = Vec::new();
+    let mut note = |relaxation: Relaxation| {
+        if !relaxations.contains(&relaxation) {
+            relaxations.push(relaxation);
+        }
+    };
+    let bytes = input.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' => {
+                let end = string_end(bytes, i, b'"');
+                output.push_str(&input[i..end]);
+                i = end;
+            }
+            b'\'' => {
+                note(Relaxation::SingleQuotes);
+                anchors.push((output.len(), i));
+                let end = string_end(bytes, i, b'\'');
+                let closed = end > i + 1 && bytes[end - 1] == b'\'';
+                let body = &input[i + 1..if closed { end - 1 } else { end }];
+                output.push('"');
+                let mut chars = body.chars();
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => output.push_str("\\\""),
+                        '\\' => match chars.next() {
+                            Some('\'') => output.push('\''),
+                            Some(escaped) => {
+                                output.push('\\');
+                                output.push(escaped);
+                            }
+                            None => output.push('\\'),
+                        },
+                        c => output.push(c),
+                    }
+                }
+                if closed {
+                    output.push('"');
+                }
+                anchors.push((output.len(), end));
+                i = end;
+            }
+            b'/' if matches!(bytes.get(i + 1), Some(b'/' | b'*')) => {
+                note(Relaxation::Comments);
+                let end = comment_end(bytes, i);
+                output.push_str(&input[i..end]);
+                i = end;
+            }
+            b',' => {
+                if matches!(bytes.get(skip_insignificant(bytes, i + 1)), Some(b']' | b'}')) {
+                    note(Relaxation::TrailingCommas);
+                }
+                output.push(',');
+                i += 1;
+            }
+            b if b.is_ascii_alphabetic() || b == b'_' || b == b'$' => {
+                let mut end = i + 1;
+                while end < bytes.len()
+                    && (bytes[end].is_ascii_alphanumeric() || bytes[end] == b'_' || bytes[end] == b'$')
+                {
+                    end += 1;
+                }
+                let word = &input[i..end];
+                let is_key = !matches!(word, "true" | "false" | "null")
+                    && bytes.get(skip_insignificant(bytes, end)) == Some(&b':');
+                if is_key {
+                    note(Relaxation::UnquotedKeys);
+                    anchors.push((output.len(), i));
+                    output.push('"');
+                    output.push_str(word);
+                    output.push('"');
+                    anchors.push((output.len(), end));
+                } else {
+                    output.push_str(word);
+                }
+                i = end;
+            }
+            _ => {
+                // 原样复制，包括多字节字符
+                let len = input[i..].chars().next().map_or(1, char::len_utf8);
+                output.push_str(&input[i..i + len]);
+                i += len;
+            }
+        }
+    }
+    relaxations.sort();
+    Relaxed {
+        text: output,
+        relaxations,
+        anchors,
+    }
+}
+
+/// Index just past the string starting at `start`, or the end of input if unterminated.
+fn string_end(bytes: &[u8], start: usize, quote: u8) -> usize {
+    let mut i = start + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b if b == quote => return i + 1,
+            _ => i += 1,
+        }
+    }
+    bytes.len()
+}
+
+/// Index just past the comment starting at `start`.
+fn comment_end(bytes: &[u8], start: usize) -> usize {
+    if bytes[start + 1] == b'/' {
+        bytes[start..]
+            .iter()
+            .position(|&b| b == b'\n')
+            .map_or(bytes.len(), |offset| start + offset + 1)
+    } else {
+        bytes[start + 2..]
+            .windows(2)
+            .position(|pair| pair == b"*/")
+            .map_or(bytes.len(), |offset| start + 2 + offset + 2)
+    }
+}
+
+/// Skips whitespace and comments, returning the index of the next significant byte.
+fn skip_insignificant(bytes: &[u8], mut i: usize) -> usize {
+    while i < bytes.len() {
+        match bytes[i] {
+            b' ' | b'\n' | b'\t' | b'\r' => i += 1,
+            b'/' if matches!(bytes.get(i + 1), Some(b'/' | b'*')) => i = comment_end(bytes, i),
+            _ => break,
+        }
+    }
+    i
+}
+
+/// Indentation of formatted output.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Indent {
+    #[default]
+    Two,
+    Four,
+    Eight,
+    Tab,
+}
+
+impl Indent {
+    pub const ALL: [Indent; 4] = [Indent::Two, Indent::Four, Indent::Eight, Indent::Tab];
+
+    pub fn as_param(&self) -> &'static str {
+        match self {
+            Indent::Two => "2",
+            Indent::Four => "4",
+            Indent::Eight => "8",
+            Indent::Tab => "tab",
+        }
+    }
+
+    pub fn from_param(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|indent| indent.as_param() == value)
+    }
+
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            Indent::Two => "  ",
+            Indent::Four => "    ",
+            Indent::Eight => "        ",
+            Indent::Tab => "\t",
+        }
+    }
+
+    fn width(&self) -> usize {
+        match self {
+            Indent::Tab => TAB_WIDTH,
+            indent => indent.as_str().len(),
+        }
+    }
+}
+
+/// Output options shared by every format.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct FormatOptions {
+    /// Sort object keys alphabetically, at every level, instead of keeping the input order.
+    pub sort_keys: bool,
+    pub indent: Indent,
+    /// Keep arrays and objects on one line when they fit within this many
+    /// columns; 0 always puts each element on its own line.
+    pub max_width: usize,
+    /// Write non-ASCII characters as `\uXXXX` escapes.
+    pub escape_non_ascii: bool,
+    /// End the output with a line break.
+    pub trailing_newline: bool,
+    /// How CSV is written and read.
+    #[serde(default)]
+    pub table: TableOptions,
+}
+
+impl FormatOptions {
+    /// The options as query-string parameters, e.g. `indent=tab&width=80`.
+    pub fn to_params(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("sort_keys", self.sort_keys.to_string()),
+            ("indent", self.indent.as_param().to_string()),
+            ("width", self.max_width.to_string()),
+            ("ascii", self.escape_non_ascii.to_string()),
+            ("newline", self.trailing_newline.to_string()),
+        ]
+        .into_iter()
+        .chain(self.table.to_params())
+        .collect()
+    }
+
+    /// Reads options written by [`to_params`](Self::to_params); missing or invalid
+    /// parameters keep their defaults.
+    pub fn from_params(param: impl Fn(&str) -> Option<String>) -> Self {
+        let flag = |name: &str| param(name).is_some_and(|value| value == "true");
+        FormatOptions {
+            sort_keys: flag("sort_keys"),
+            indent: param("indent")
+                .and_then(|value| Indent::from_param(&value))
+                .unwrap_or_default(),
+            max_width: param("width").and_then(|value| value.parse().ok()).unwrap_or(0),
+            escape_non_ascii: flag("ascii"),
+            trailing_newline: flag("newline"),
+            table: TableOptions::from_params(&param),
+        }
+    }
+}
+
+pub fn format(input: &str, format_type: FormatType) -> Result<String, JsonError> {
+    format_value(&parse(input)?, format_type, &FormatOptions::default())
+}
+
+/// Formats a parsed document. Keys keep their input order unless
+/// [`FormatOptions::sort_keys`] is set, and numbers keep their exact input text.
+/// YAML output only honours `sort_keys`; it always uses two-space indentation. TOML,
+/// XML, CSV and NDJSON are written as described in [`convert`](crate::convert).
+pub fn format_value(
+    json: &Value,
+    format_type: FormatType,
+    options: &FormatOptions,
+) -> Result<String, JsonError> {
+    let json = &*arrange(json, options);
+    match format_type {
+        FormatType::Standard => Ok(Emitter { quotes: Quotes::Double, options }.emit(json)),
+        FormatType::SingleQuote => Ok(Emitter { quotes: Quotes::Single, options }.emit(json)),
+        FormatType::NoQuote => Ok(Emitter { quotes: Quotes::BareKeys, options }.emit(json)),
+        FormatType::Python => Ok(Emitter { quotes: Quotes::Python, options }.emit(json)),
+        FormatType::Yaml => to_yaml(json),
+        FormatType::Toml => convert::to_toml(json),
+        FormatType::Xml => convert::to_xml(json, options),
+        FormatType::Csv => table::to_csv(json, &options.table),
+        FormatType::Ndjson => convert::to_ndjson(json, options),
+    }
+}
+
+pub fn minify(input: &str) -> Result<String, JsonError> {
+    minify_value(&parse(input)?, &FormatOptions::default())
+}
+
+/// Writes a parsed document without whitespace, honouring `sort_keys`,
+/// `escape_non_ascii` and `trailing_newline`.
+pub fn minify_value(json: &Value, options: &FormatOptions) -> Result<String, JsonError> {
+    let mut minified = serde_json::to_string(&*arrange(json, options)).map_err(JsonError::Serialize)?;
+    if options.escape_non_ascii {
+        // 非 ASCII 字符只会出现在字符串中，可以直接替换
+        let mut escaped = String::with_capacity(minified.len());
+        for c in minified.chars() {
+            if c.is_ascii() {
+                escaped.push(c);
+            } else {
+                write_unicode_escape(&mut escaped, c);
+            }
+        }
+        minified = escaped;
+    }
+    if options.trailing_newline {
+        minified.push('\n');
+    }
+    Ok(minified)
+}
+
+/// Applies the options that change the document itself rather than its layout.
+fn arrange<'a>(json: &'a Value, options: &FormatOptions) -> Cow<'a, Value> {
+    if !options.sort_keys {
+        return Cow::Borrowed(json);
+    }
+    let mut sorted = json.clone();
+    sorted.sort_all_objects();
+    Cow::Owned(sorted)
+}
+
+/// Writes `json` as YAML in the layout `serde_yaml` uses. `serde_yaml` cannot
+/// serialize `serde_json` numbers with arbitrary precision, so the document is
+/// walked here and only string scalars are quoted by `serde_yaml`.
+fn to_yaml(json: &Value) -> Result<String, JsonError> {
+    let mut out = String::new();
+    match json {
+        Value::Object(map) if !map.is_empty() => write_yaml_mapping(&mut out, map, 0, false)?,
+        Value::Array(items) if !items.is_empty() => write_yaml_sequence(&mut out, items, 0, false)?,
+        scalar => {
+            out.push_str(&yaml_scalar(scalar, 0)?);
+            out.push('\n');
+        }
+    }
+    Ok(out)
+}
+
+/// Writes the entries of a mapping indented by `indent` spaces. With `inline`
+/// the first entry continues the current line, after a sequence dash.
+fn write_yaml_mapping(
+    out: &mut String,
+    map: &serde_json::Map<String, Value>,
+    indent: usize,
+    inline: bool,
+) -> Result<(), JsonError> {
+    for (index, (key, value)) in map.iter().enumerate() {
+        if index > 0 || !inline {
+            out.push_str(&" ".repeat(indent));
+        }
+        if key.contains('\n') {
+            // JSON 字符串同时也是合法的 YAML 双引号标量
+            out.push_str(&Value::from(key.as_str()).to_string());
+        } else {
+            out.push_str(&yaml_string(key, indent)?);
+        }
+        out.push(':');
+        match value {
+            Value::Object(map) if !map.is_empty() => {
+                out.push('\n');
+                write_yaml_mapping(out, map, indent + 2, false)?;
+            }
+            // 与 serde_yaml 一样，映射中的序列不额外缩进
+            Value::Array(items) if !items.is_empty() => {
+                out.push('\n');
+                write_yaml_sequence(out, items, indent, false)?;
+            }
+            scalar => {
+                out.push(' ');
+                out.push_str(&yaml_scalar(scalar, indent)?);
+                out.push('\n');
+            }
+        }
+    }
+    Ok(())
+}
+
+fn write_yaml_sequence(out: &mut String, items: &[Value], indent: usize, inline: bool) -> Result<(), JsonError> {
+    for (index, item) in items.iter().enumerate() {
+        if index > 0 || !inline {
+            out.push_str(&" ".repeat(indent));
+        }
+        out.push_str("- ");
+        match item {
+            Value::Object(map) if !map.is_empty() => write_yaml_mapping(out, map, indent + 2, true)?,
+            Value::Array(items) if !items.is_empty() => write_yaml_sequence(out, items, indent + 2, true)?,
+            scalar => {
+                out.push_str(&yaml_scalar(scalar, indent)?);
+                out.push('\n');
+            }
+        }
+    }
+    Ok(())
+}
+
+/// A scalar or empty container; `indent` is the indentation of the node that holds it.
+fn yaml_scalar(value: &Value, indent: usize) -> Result<String, JsonError> {
+    match value {
+        Value::String(text) => yaml_string(text, indent),
+        Value::Array(_) => Ok("[]".to_string()),
+        Value::Object(_) => Ok("{}".to_string()),
+        other => Ok(other.to_string()),
+    }
+}
+
+fn yaml_string(text: &str, indent: usize) -> Result<String, JsonError> {
+    let yaml = serde_yaml::to_string(text).map_err(JsonError::Yaml)?;
+    let yaml = yaml.strip_suffix('\n').unwrap_or(&yaml);
+    // 多行字符串会写成块标量，其内容行需要随所在节点一起缩进
+    Ok(yaml.replace('\n', &format!("\n{}", " ".repeat(indent))))
+}
+
+/// How [`Emitter`] quotes keys and strings.
+#[derive(Clone, Copy, PartialEq)]
+enum Quotes {
+    /// Standard JSON.
+    Double,
+    /// Keys and strings in single quotes, as JavaScript accepts them.
+    Single,
+    /// Keys that are valid identifiers without quotes, everything else as in JSON.
+    BareKeys,
+    /// Single quotes as for JavaScript, with Python's `None`, `True` and `False`
+    /// and `\U` escapes outside the Basic Multilingual Plane.
+    Python,
+}
+
+/// Columns a tab is counted as when checking [`FormatOptions::max_width`].
+const TAB_WIDTH: usize = 4;
+
+/// Pretty-printer shared by the Standard, Single Quote, No Quote and Python formats.
+struct Emitter<'a> {
+    quotes: Quotes,
+    options: &'a FormatOptions,
+}
+
+impl Emitter<'_> {
+    fn emit(&self, json: &Value) -> String {
+        let mut out = String::new();
+        self.write(&mut out, json, 0, 0);
+        if self.options.trailing_newline {
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Writes `value` nested `depth` levels deep, starting at `column` on the current line.
+    fn write(&self, out: &mut String, value: &Value, depth: usize, column: usize) {
+        match value {
+            Value::Array(items) if !items.is_empty() => {
+                if let Some(flat) = self.flat(value, column) {
+                    out.push_str(&flat);
+                    return;
+                }
+                out.push('[');
+                for (index, item) in items.iter().enumerate() {
+                    if index > 0 {
+                        out.push(',');
+                    }
+                    self.write_newline(out, depth + 1);
+                    self.write(out, item, depth + 1, self.indent_width(depth + 1));
+                }
+                self.write_newline(out, depth);
+                out.push(']');
+            }
+            Value::Object(map) if !map.is_empty() => {
+                if let Some(flat) = self.flat(value, column) {
+                    out.push_str(&flat);
+                    return;
+                }
+                out.push('{');
+                for (index, (key, item)) in map.iter().enumerate() {
+                    if index > 0 {
+                        out.push(',');
+                    }
+                    self.write_newline(out, depth + 1);
+                    let key_start = out.len();
+                    self.write_key(out, key);
+                    out.push_str(": ");
+                    let key_width = out[key_start..].chars().count();
+                    self.write(out, item, depth + 1, self.indent_width(depth + 1) + key_width);
+                }
+                self.write_newline(out, depth);
+                out.push('}');
+            }
+            scalar => self.write_scalar(out, scalar),
+        }
+    }
+
+    /// The one-line form of a container, if it fits before `max_width` with room for a comma.
+    fn flat(&self, value: &Value, column: usize) -> Option<String> {
+        if self.options.max_width == 0 {
+            return None;
+        }
+        let budget = self.options.max_width.checked_sub(column + 1)?;
+        let mut out = String::new();
+        let fits = self.write_flat(&mut out, value, budget) && out.chars().count() <= budget;
+        fits.then_some(out)
+    }
+
+    /// Writes `[1, 2]` or `{ "a": 1 }`, giving up once the text can no longer fit in `budget`.
+    fn write_flat(&self, out: &mut String, value: &Value, budget: usize) -> bool {
+        // 按字节粗略判断，最终由调用方按字符数检查
+        let too_long = |out: &String| out.len() > budget * 4;
+        match value {
+            Value::Array(items) if !items.is_empty() => {
+                out.push('[');
+                for (index, item) in items.iter().enumerate() {
+                    if index > 0 {
+                        out.push_str(", ");
+                    }
+                    if !self.write_flat(out, item, budget) {
+                        return false;
+                    }
+                }
+                out.push(']');
+            }
+            Value::Object(map) if !map.is_empty() => {
+                out.push_str("{ ");
+                for (index, (key, item)) in map.iter().enumerate() {
+                    if index > 0 {
+                        out.push_str(", ");
+                    }
+                    self.write_key(out, key);
+                    out.push_str(": ");
+                    if !self.write_flat(out, item, budget) {
+                        return false;
+                    }
+                }
+                out.push_str(" }");
+            }
+            scalar => self.write_scalar(out, scalar),
+        }
+        !too_long(out)
+    }
+
+    /// Strings, numbers, booleans, null and empty containers.
+    fn write_scalar(&self, out: &mut String, value: &Value) {
+        match value {
+            Value::String(text) => self.write_string(out, text),
+            Value::Array(_) => out.push_str("[]"),
+            Value::Object(_) => out.push_str("{}"),
+            Value::Null if self.quotes == Quotes::Python => out.push_str("None"),
+            Value::Bool(flag) if self.quotes == Quotes::Python => out.push_str(if *flag { "True" } else { "False" }),
+            // 数字保留原文，JSON 数字也是合法的 Python 数字
+            other => out.push_str(&other.to_string()),
+        }
+    }
+
+    fn write_key(&self, out: &mut String, key: &str) {
+        if self.quotes == Quotes::BareKeys && is_identifier(key) {
+            out.push_str(key);
+        } else {
+            self.write_string(out, key);
+        }
+    }
+
+    fn write_string(&self, out: &mut String, text: &str) {
+        let quote = if matches!(self.quotes, Quotes::Single | Quotes::Python) { '\'' } else { '"' };
+        out.push(quote);
+        for c in text.chars() {
+            match c {
+                '\\' => out.push_str("\\\\"),
+                '\n' => out.push_str("\\n"),
+                '\r' => out.push_str("\\r"),
+                '\t' => out.push_str("\\t"),
+                '\u{8}' => out.push_str("\\b"),
+                '\u{c}' => out.push_str("\\f"),
+                c if c == quote => {
+                    out.push('\\');
+                    out.push(c);
+                }
+                c if c < ' ' => self.write_escape(out, c),
+                // 单引号输出用于 JavaScript 源码，U+2028/U+2029 在旧版 JavaScript 字符串中会被当作换行
+                c if quote == '\'' && (c.is_control() || c == '\u{2028}' || c == '\u{2029}') => self.write_escape(out, c),
+                c if self.options.escape_non_ascii && !c.is_ascii() => self.write_escape(out, c),
+                c => out.push(c),
+            }
+        }
+        out.push(quote);
+    }
+
+    /// Python reads `\uXXXX` surrogate pairs as two separate characters, so it gets one `\UXXXXXXXX` instead.
+    fn write_escape(&self, out: &mut String, c: char) {
+        if self.quotes == Quotes::Python && c > '\u{ffff}' {
+            out.push_str(&format!("\\U{:08x}", c as u32));
+        } else {
+            write_unicode_escape(out, c);
+        }
+    }
+
+    fn write_newline(&self, out: &mut String, depth: usize) {
+        out.push('\n');
+        for _ in 0..depth {
+            out.push_str(self.options.indent.as_str());
+        }
+    }
+
+    fn indent_width(&self, depth: usize) -> usize {
+        depth * self.options.indent.width()
+    }
+}
+
+/// Writes `c` as one `\uXXXX` escape, or a surrogate pair outside the Basic Multilingual Plane.
+fn write_unicode_escape(out: &mut String, c: char) {
+    for unit in c.encode_utf16(&mut [0; 2]) {
+        out.push_str(&format!("\\u{:04x}", unit));
+    }
+}
+
+/// Whether `key` can be written as a JavaScript identifier without quotes.
+pub(crate) fn is_identifier(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locates_values_by_path() {
+        let input = "{\n  \"a\": [1, {\"b\": \"x,]\"}, 3],\n  \"c\\\"d\": null\n}";
+        let at = |path: JsonPath| locate(input, &path).map(|location| (location.line, location.column));
+        assert_eq!(at(JsonPath::root()), Some((1, 1)));
+        assert_eq!(at(JsonPath::root().key("a").index(2)), Some((2, 26)));
+        assert_eq!(at(JsonPath::root().key("a").index(1).key("b")), Some((2, 18)));
+        assert_eq!(at(JsonPath::root().key("c\"d")), Some((3, 11)));
+        assert_eq!(at(JsonPath::root().key("a").index(3)), None);
+        assert_eq!(at(JsonPath::root().key("missing")), None);
+        let lenient = "{ // note\n  name: 'x', 'list': [/* first */ true] }";
+        assert_eq!(locate(lenient, &JsonPath::root().key("list").index(0)).map(|l| l.column), Some(35));
+    }
+
+    #[test]
+    fn formats_standard() {
+        assert_eq!(
+            format(r#"{"a":[1,true,null]}"#, FormatType::Standard).unwrap(),
+            "{\n  \"a\": [\n    1,\n    true,\n    null\n  ]\n}"
+        );
+    }
+
+    #[test]
+    fn single_quote_escapes_strings() {
+        let input = r#"{"it's": ["say \"hi\"", "a\\b\nc", {}, []], "n": 1.5}"#;
+        assert_eq!(
+            format(input, FormatType::SingleQuote).unwrap(),
+            "{\n  'it\\'s': [\n    'say \"hi\"',\n    'a\\\\b\\nc',\n    {},\n    []\n  ],\n  'n': 1.5\n}"
+        );
+    }
+
+    #[test]
+    fn no_quote_only_unquotes_identifier_keys() {
+        let input = r#"{"$id_2": null, "1st": true, "content-type": "a", "time": "12:30", "url": "http://x:80/a"}"#;
+        assert_eq!(
+            format(input, FormatType::NoQuote).unwrap(),
+            "{\n  $id_2: null,\n  \"1st\": true,\n  \"content-type\": \"a\",\n  time: \"12:30\",\n  url: \"http://x:80/a\"\n}"
+        );
+    }
+
+    #[test]
+    fn python_uses_its_own_literals() {
+        let input = r#"{"it's": [null, true, false, 1e3, -2], "s": "null\ttrue \ud83d\ude00"}"#;
+        assert_eq!(
+            format(input, FormatType::Python).unwrap(),
+            "{\n  'it\\'s': [\n    None,\n    True,\n    False,\n    1e3,\n    -2\n  ],\n  's': 'null\\ttrue 😀'\n}"
+        );
+        let options = FormatOptions { escape_non_ascii: true, max_width: 80, ..Default::default() };
+        assert_eq!(
+            format_value(&parse(r#"["é😀", {"k": null}]"#).unwrap(), FormatType::Python, &options).unwrap(),
+            r"['\u00e9\U0001f600', { 'k': None }]"
+        );
+    }
+
+    #[test]
+    fn source_output_matches_standard_layout() {
+        let input = r#"{"a": [1, {"b": [true, null]}], "c": {}}"#;
+        let standard = format(input, FormatType::Standard).unwrap();
+        assert_eq!(format(input, FormatType::SingleQuote).unwrap(), standard.replace('"', "'"));
+    }
+
+    #[test]
+    fn indents_with_spaces_or_tabs() {
+        let value = parse(r#"{"a": [1]}"#).unwrap();
+        let options = FormatOptions { indent: Indent::Four, ..Default::default() };
+        assert_eq!(
+            format_value(&value, FormatType::Standard, &options).unwrap(),
+            "{\n    \"a\": [\n        1\n    ]\n}"
+        );
+        let options = FormatOptions { indent: Indent::Tab, ..Default::default() };
+        assert_eq!(
+            format_value(&value, FormatType::NoQuote, &options).unwrap(),
+            "{\n\ta: [\n\t\t1\n\t]\n}"
+        );
+    }
+
+    #[test]
+    fn keeps_short_containers_on_one_line() {
+        let value = parse(r#"{"id": 1, "tags": ["a", "b"], "point": {"x": 1, "y": 2}, "long": [1000000, 2000000, 3000000]}"#).unwrap();
+        let options = FormatOptions { max_width: 30, ..Default::default() };
+        assert_eq!(
+            format_value(&value, FormatType::Standard, &options).unwrap(),
+            "{\n  \"id\": 1,\n  \"tags\": [\"a\", \"b\"],\n  \"point\": { \"x\": 1, \"y\": 2 },\n  \"long\": [\n    1000000,\n    2000000,\n    3000000\n  ]\n}"
+        );
+        let options = FormatOptions { max_width: 80, ..Default::default() };
+        assert_eq!(
+            format_value(&parse("[[1, 2], {}]").unwrap(), FormatType::SingleQuote, &options).unwrap(),
+            "[[1, 2], {}]"
+        );
+    }
+
+    #[test]
+    fn escapes_non_ascii_and_adds_trailing_newline() {
+        let value = parse(r#"{"名": "é😀"}"#).unwrap();
+        let options = FormatOptions {
+            escape_non_ascii: true,
+            trailing_newline: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            format_value(&value, FormatType::Standard, &options).unwrap(),
+            "{\n  \"\\u540d\": \"\\u00e9\\ud83d\\ude00\"\n}\n"
+        );
+        assert_eq!(
+            minify_value(&value, &options).unwrap(),
+            "{\"\\u540d\":\"\\u00e9\\ud83d\\ude00\"}\n"
+        );
+    }
+
+    #[test]
+    fn standard_output_matches_serde_json() {
+        let input = r#"{"s": "q\"\\\n\u0001\u007f\u2028", "a": [1, {"b": [true, null, {}, []]}]}"#;
+        let value = parse(input).unwrap();
+        assert_eq!(format(input, FormatType::Standard).unwrap(), serde_json::to_string_pretty(&value).unwrap());
+    }
+
+    #[test]
+    fn format_options_round_trip_as_params() {
+        let options = FormatOptions {
+            sort_keys: true,
+            indent: Indent::Tab,
+            max_width: 100,
+            escape_non_ascii: true,
+            trailing_newline: false,
+            table: TableOptions {
+                delimiter: table::Delimiter::Tab,
+                ..TableOptions::default()
+            },
+        };
+        let params = options.to_params();
+        let parsed = FormatOptions::from_params(|name| {
+            params.iter().find(|(key, _)| *key == name).map(|(_, value)| value.clone())
+        });
+        assert_eq!(parsed, options);
+        assert_eq!(FormatOptions::from_params(|_| None), FormatOptions::default());
+    }
+
+    #[test]
+    fn formats_yaml() {
+        assert_eq!(
+            format(r#"{"a":{"b":"c"}}"#, FormatType::Yaml).unwrap(),
+            "a:\n  b: c\n"
+        );
+    }
+
+    #[test]
+    fn formats_yaml_like_serde_yaml() {
+        let input = r##"{"a":[1,[2,3],{"b":1,"c":[]},{}],"d":{"e":[{"f":"x"}]},"s":["a\nb","1.0","","#c"],"m":{"k":"l1\nl2"}}"##;
+        assert_eq!(
+            format(input, FormatType::Yaml).unwrap(),
+            "a:\n- 1\n- - 2\n  - 3\n- b: 1\n  c: []\n- {}\nd:\n  e:\n  - f: x\ns:\n- |-\n  a\n  b\n- '1.0'\n- ''\n- '#c'\nm:\n  k: |-\n    l1\n    l2\n"
+        );
+        assert_eq!(format("\"top\"", FormatType::Yaml).unwrap(), "top\n");
+    }
+
+    #[test]
+    fn keeps_key_order_and_number_text() {
+        let input = r#"{"z": 12345678901234567890, "a": 0.10000000000000000001, "m": 1e400}"#;
+        assert_eq!(minify(input).unwrap(), r#"{"z":12345678901234567890,"a":0.10000000000000000001,"m":1e400}"#);
+        assert_eq!(
+            format(input, FormatType::Yaml).unwrap(),
+            "z: 12345678901234567890\na: 0.10000000000000000001\nm: 1e400\n"
+        );
+        let (value, _) = parse_lenient("{b: 9007199254740993, a: 1}").unwrap();
+        assert_eq!(minify_value(&value, &FormatOptions::default()).unwrap(), r#"{"b":9007199254740993,"a":1}"#);
+    }
+
+    #[test]
+    fn sorts_keys_when_asked() {
+        let value = parse(r#"{"b": {"d": 1, "c": 2}, "a": [{"f": 1, "e": 2}]}"#).unwrap();
+        let options = FormatOptions { sort_keys: true, ..Default::default() };
+        assert_eq!(
+            minify_value(&value, &options).unwrap(),
+            r#"{"a":[{"e":2,"f":1}],"b":{"c":2,"d":1}}"#
+        );
+        assert_eq!(
+            format_value(&value, FormatType::NoQuote, &options).unwrap(),
+            "{\n  a: [\n    {\n      e: 2,\n      f: 1\n    }\n  ],\n  b: {\n    c: 2,\n    d: 1\n  }\n}"
+        );
+    }
+
+    #[test]
+    fn minifies() {
+        assert_eq!(minify("{ \"a\" : [ 1 , 2 ] }").unwrap(), r#"{"a":[1,2]}"#);
+    }
+
+    #[test]
+    fn reports_parse_errors() {
+        let err = format("{\"a\":}", FormatType::Standard).unwrap_err();
+        assert!(matches!(err, JsonError::Parse(_)));
+        assert!(err.to_string().contains("line 1 column 6"));
+    }
+
+    #[test]
+    fn format_params_round_trip() {
+        for format_type in FormatType::ALL {
+            assert_eq!(FormatType::from_param(format_type.as_param()), Some(format_type));
+        }
+        assert_eq!(FormatType::from_param("html"), None);
+    }
+
+    #[test]
+    fn lenient_accepts_relaxed_syntax() {
+        let input = "{\n  // settings\n  name: 'it\\'s \"ok\"', /* inline */\n  \"list\": [1, 2,],\n}";
+        let (value, relaxations) = parse_lenient(input).unwrap();
+        assert_eq!(value, serde_json::json!({"name": "it's \"ok\"", "list": [1, 2]}));
+        assert_eq!(
+            relaxations,
+            [
+                Relaxation::Comments,
+                Relaxation::TrailingCommas,
+                Relaxation::SingleQuotes,
+                Relaxation::UnquotedKeys
+            ]
+        );
+    }
+
+    #[test]
+    fn lenient_reports_nothing_for_strict_json() {
+        let (value, relaxations) = parse_lenient(r#"{"url": "http://x/*y*/", "t": true}"#).unwrap();
+        assert_eq!(value["url"], "http://x/*y*/");
+        assert!(relaxations.is_empty());
+    }
+
+    #[test]
+    fn lenient_still_rejects_invalid_input() {
+        assert!(matches!(parse_lenient("{a: }"), Err(JsonError::Lenient(..))));
+        assert!(parse_lenient("[1] [2]").is_err());
+        // 未加引号的值不是键，不会被改写
+        assert!(parse_lenient("[abc]").is_err());
+    }
+
+    #[test]
+    fn locates_errors_in_characters() {
+        let input = "{\n  \"名字\": \"值\"\n  \"b\": 1\n}";
+        let err = parse(input).unwrap_err();
+        assert_eq!(err.location(input), Some(ErrorLocation { line: 3, column: 3 }));
+    }
+
+    #[test]
+    fn locates_lenient_errors_in_the_original_input() {
+        // 改写后的文本多了引号和转义，位置需要映射回原输入
+        let input = "{a: 'say \"hi\"', b: 1 c: 2}";
+        let err = parse_lenient(input).unwrap_err();
+        let location = err.location(input).unwrap();
+        assert_eq!(location, ErrorLocation { line: 1, column: 22 });
+        assert!(err.to_string().ends_with("at line 1 column 22"));
+    }
+
+    #[test]
+    fn shows_context_around_errors() {
+        let input = "1\n2\n3\n4\n5";
+        let lines = error_context(input, ErrorLocation { line: 2, column: 1 }, 2);
+        assert_eq!(lines.iter().map(|line| line.number).collect::<Vec<_>>(), [1, 2, 3, 4]);
+        assert!(lines[1].is_error && !lines[0].is_error);
+    }
+}
@@ -0,0 +1,24 @@
+//! Headless conversion logic shared by the web UI and other front ends.
+//!
+//! Every function here is pure and returns a typed result; localisation and
+//! rendering are left to the caller.
+
+pub mod base64;
+pub mod convert;
+pub mod cron;
+pub mod detect;
+pub mod diff;
+pub mod history;
+pub mod infer;
+pub mod json;
+pub mod patch;
+pub mod path;
+pub mod pipeline;
+pub mod query;
+pub mod repair;
+pub mod schema;
+pub mod share;
+pub mod stringified;
+pub mod table;
+pub mod task;
+pub mod url;
@@ -0,0 +1,402 @@
+//! Applying and generating RFC 6902 JSON Patch and RFC 7386 JSON Merge Patch
+//! documents.
+//!
+//! A JSON Patch is applied atomically: when an operation fails, the error names
+//! it and the document is left as it was.
+
+use crate::diff::{self, DiffOptions};
+use crate::path::JsonPath;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PatchFormat {
+    /// RFC 6902: an array of `add`, `remove`, `replace`, `move`, `copy` and `test` operations.
+    #[default]
+    JsonPatch,
+    /// RFC 7386: a partial document whose `null` members remove keys.
+    MergePatch,
+}
+
+impl PatchFormat {
+    pub const ALL: [PatchFormat; 2] = [PatchFormat::JsonPatch, PatchFormat::MergePatch];
+
+    pub fn as_param(&self) -> &'static str {
+        match self {
+            PatchFormat::JsonPatch => "json-patch",
+            PatchFormat::MergePatch => "merge-patch",
+        }
+    }
+
+    pub fn from_param(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|format| format.as_param() == value)
+    }
+}
+
+/// Why a patch could not be applied or generated.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PatchError {
+    /// The zero-based index of the failed JSON Patch operation, if one failed.
+    pub operation: Option<usize>,
+    pub message: String,
+}
+
+impl fmt::Display for PatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.operation {
+            Some(index) => write!(f, "operation {}: {}", index, self.message),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+impl std::error::Error for PatchError {}
+
+impl PatchError {
+    fn new(message: impl Into<String>) -> Self {
+        PatchError {
+            operation: None,
+            message: message.into(),
+        }
+    }
+}
+
+/// Applies `patch` in the given format to a copy of `document`.
+pub fn apply(document: &Value, patch: &Value, format: PatchFormat) -> Result<Value, PatchError> {
+    match format {
+        PatchFormat::JsonPatch => apply_json_patch(document, patch),
+        PatchFormat::MergePatch => Ok(apply_merge_patch(document, patch)),
+    }
+}
+
+/// A patch in the given format that turns `left` into `right`.
+pub fn generate(left: &Value, right: &Value, format: PatchFormat) -> Result<Value, PatchError> {
+    match format {
+        PatchFormat::JsonPatch => Ok(diff::to_patch(&diff::diff(left, right, &DiffOptions::default()))),
+        PatchFormat::MergePatch => merge_patch(left, right),
+    }
+}
+
+/// Applies the operations in order, stopping at the first that fails.
+pub fn apply_json_patch(document: &Value, patch: &Value) -> Result<Value, PatchError> {
+    let operations = patch.as_array().ok_or_else(|| PatchError::new("a JSON Patch must be an array of operations"))?;
+    let mut document = document.clone();
+    for (index, operation) in operations.iter().enumerate() {
+        apply_operation(&mut document, operation).map_err(|message| PatchError {
+            operation: Some(index),
+            message,
+        })?;
+    }
+    Ok(document)
+}
+
+fn apply_operation(document: &mut Value, operation: &Value) -> Result<(), String> {
+    let member = |name: &str| operation.get(name);
+    let pointer = |name: &str| -> Result<Vec<String>, String> {
+        match member(name) {
+            Some(Value::String(pointer)) => parse_pointer(pointer),
+            Some(_) => Err(format!("\"{}\" must be a string", name)),
+            None => Err(format!("missing \"{}\"", name)),
+        }
+    };
+    let value = || member("value").cloned().ok_or_else(|| "missing \"value\"".to_string());
+    if !operation.is_object() {
+        return Err("an operation must be an object".to_string());
+    }
+    let op = match member("op") {
+        Some(Value::String(op)) => op.as_str(),
+        Some(_) => return Err("\"op\" must be a string".to_string()),
+        None => return Err("missing \"op\"".to_string()),
+    };
+    match op {
+        "add" => add(document, &pointer("path")?, value()?),
+        "remove" => remove(document, &pointer("path")?).map(drop),
+        "replace" => {
+            let path = pointer("path")?;
+            let value = value()?;
+            let target = lookup_mut(document, &path).ok_or_else(|| missing(&path))?;
+            *target = value;
+            Ok(())
+        }
+        "move" => {
+            let (from, path) = (pointer("from")?, pointer("path")?);
+            if path.len() > from.len() && path.starts_with(&from) {
+                return Err(format!("cannot move {} into itself at {}", display(&from), display(&path)));
+            }
+            let value = remove(document, &from)?;
+            add(document, &path, value)
+        }
+        "copy" => {
+            let (from, path) = (pointer("from")?, pointer("path")?);
+            let value = lookup(document, &from).ok_or_else(|| missing(&from))?.clone();
+            add(document, &path, value)
+        }
+        "test" => {
+            let path = pointer("path")?;
+            let expected = value()?;
+            let actual = lookup(document, &path).ok_or_else(|| missing(&path))?;
+            if equal(actual, &expected) {
+                Ok(())
+            } else {
+                Err(format!("test failed: {} is {}, expected {}", display(&path), actual, expected))
+            }
+        }
+        op => Err(format!("unknown op \"{}\"", op)),
+    }
+}
+
+/// The reference tokens of a JSON Pointer (RFC 6901), `~1` and `~0` unescaped.
+fn parse_pointer(pointer: &str) -> Result<Vec<String>, String> {
+    if pointer.is_empty() {
+        return Ok(Vec::new());
+    }
+    let Some(rest) = pointer.strip_prefix('/') else {
+        return Err(format!("\"{}\" is not a JSON Pointer; it must be empty or start with /", pointer));
+    };
+    Ok(rest.split('/').map(|token| token.replace("~1", "/").replace("~0", "~")).collect())
+}
+
+/// A path for messages, written back as the quoted pointer the patch used.
+fn display(tokens: &[String]) -> String {
+    let pointer: String = tokens
+        .iter()
+        .map(|token| format!("/{}", token.replace('~', "~0").replace('/', "~1")))
+        .collect();
+    format!("\"{}\"", pointer)
+}
+
+fn missing(tokens: &[String]) -> String {
+    format!("{} does not exist", display(tokens))
+}
+
+/// An array index token: digits without leading zeros.
+fn index(token: &str, len: usize, allow_end: bool) -> Result<usize, String> {
+    let digits = !token.is_empty() && token.bytes().all(|b| b.is_ascii_digit());
+    let valid = digits && (token == "0" || !token.starts_with('0'));
+    let index: usize = valid
+        .then(|| token.parse().ok())
+        .flatten()
+        .ok_or_else(|| format!("\"{}\" is not an array index", token))?;
+    if index > len || (index == len && !allow_end) {
+        return Err(format!("index {} is out of bounds for an array of {}", index, len));
+    }
+    Ok(index)
+}
+
+fn lookup<'a>(document: &'a Value, tokens: &[String]) -> Option<&'a Value> {
+    tokens.iter().try_fold(document, |value, token| match value {
+        Value::Object(map) => map.get(token),
+        Value::Array(items) => items.get(index(token, items.len(), false).ok()?),
+        _ => None,
+    })
+}
+
+fn lookup_mut<'a>(document: &'a mut Value, tokens: &[String]) -> Option<&'a mut Value> {
+    tokens.iter().try_fold(document, |value, token| match value {
+        Value::Object(map) => map.get_mut(token),
+        Value::Array(items) => {
+            let index = index(token, items.len(), false).ok()?;
+            items.get_mut(index)
+        }
+        _ => None,
+    })
+}
+
+fn add(document: &mut Value, tokens: &[String], value: Value) -> Result<(), String> {
+    let Some((last, parent)) = tokens.split_last() else {
+        *document = value;
+        return Ok(());
+    };
+    match lookup_mut(document, parent) {
+        Some(Value::Object(map)) => {
+            map.insert(last.clone(), value);
+            Ok(())
+        }
+        Some(Value::Array(items)) if last == "-" => {
+            items.push(value);
+            Ok(())
+        }
+        Some(Value::Array(items)) => {
+            let index = index(last, items.len(), true)?;
+            items.insert(index, value);
+            Ok(())
+        }
+        Some(_) => Err(format!("cannot add to {}, which is neither an object nor an array", display(parent))),
+        None => Err(missing(parent)),
+    }
+}
+
+fn remove(document: &mut Value, tokens: &[String]) -> Result<Value, String> {
+    let Some((last, parent)) = tokens.split_last() else {
+        return Err("cannot remove the whole document".to_string());
+    };
+    match lookup_mut(document, parent) {
+        Some(Value::Object(map)) => map.shift_remove(last).ok_or_else(|| missing(tokens)),
+        Some(Value::Array(items)) => {
+            let index = index(last, items.len(), false)?;
+            Ok(items.remove(index))
+        }
+        _ => Err(missing(tokens)),
+    }
+}
+
+/// JSON equality for `test`: numbers compare by value, so `1` equals `1.0`, and
+/// object members in any order.
+fn equal(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => a == b || a.as_f64().is_some_and(|a| b.as_f64() == Some(a)),
+        (Value::Array(a), Value::Array(b)) => a.len() == b.len() && a.iter().zip(b).all(|(a, b)| equal(a, b)),
+        (Value::Object(a), Value::Object(b)) => {
+            a.len() == b.len() && a.iter().all(|(key, a)| b.get(key).is_some_and(|b| equal(a, b)))
+        }
+        (a, b) => a == b,
+    }
+}
+
+/// Applies a merge patch (RFC 7386) to a copy of `document`.
+pub fn apply_merge_patch(document: &Value, patch: &Value) -> Value {
+    let mut document = document.clone();
+    merge(&mut document, patch);
+    document
+}
+
+fn merge(target: &mut Value, patch: &Value) {
+    let Value::Object(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(Map::new());
+    }
+    let map = target.as_object_mut().expect("just made an object");
+    for (key, value) in patch {
+        if value.is_null() {
+            map.shift_remove(key);
+        } else {
+            merge(map.entry(key.clone()).or_insert(Value::Null), value);
+        }
+    }
+}
+
+/// A merge patch that turns `left` into `right`. Fails when `right` sets a
+/// member to `null`, which a merge patch can only express as removing it.
+pub fn merge_patch(left: &Value, right: &Value) -> Result<Value, PatchError> {
+    merge_diff(&JsonPath::root(), left, right)
+}
+
+fn merge_diff(path: &JsonPath, left: &Value, right: &Value) -> Result<Value, PatchError> {
+    let (Value::Object(left), Value::Object(right)) = (left, right) else {
+        return match right {
+            Value::Object(_) => no_nulls(path, right).map(|()| right.clone()),
+            _ => Ok(right.clone()),
+        };
+    };
+    let mut patch = Map::new();
+    for key in left.keys().filter(|key| !right.contains_key(*key)) {
+        patch.insert(key.clone(), Value::Null);
+    }
+    for (key, value) in right {
+        let path = path.key(key);
+        match left.get(key) {
+            Some(old) if equal(old, value) => {}
+            _ if value.is_null() => return Err(null_member(&path)),
+            Some(old) => {
+                patch.insert(key.clone(), merge_diff(&path, old, value)?);
+            }
+            None => {
+                no_nulls(&path, value)?;
+                patch.insert(key.clone(), value.clone());
+            }
+        }
+    }
+    Ok(Value::Object(patch))
+}
+
+/// Checks that a new object has no `null` members, which would be dropped when merged.
+fn no_nulls(path: &JsonPath, value: &Value) -> Result<(), PatchError> {
+    let Value::Object(map) = value else {
+        return Ok(());
+    };
+    for (key, value) in map {
+        let path = path.key(key);
+        if value.is_null() {
+            return Err(null_member(&path));
+        }
+        no_nulls(&path, value)?;
+    }
+    Ok(())
+}
+
+fn null_member(path: &JsonPath) -> PatchError {
+    PatchError::new(format!(
+        "a Merge Patch cannot set {} to null, since null removes a member; use a JSON Patch instead",
+        path
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn applies_json_patch_operations() {
+        let document = json!({"a": {"b": [1, 2]}, "c": "x"});
+        let patch = json!([
+            {"op": "add", "path": "/a/b/1", "value": 9},
+            {"op": "add", "path": "/a/b/-", "value": 3},
+            {"op": "test", "path": "/a/b/0", "value": 1.0},
+            {"op": "remove", "path": "/c"},
+            {"op": "copy", "from": "/a/b", "path": "/d"},
+            {"op": "move", "from": "/d/0", "path": "/e~1f"},
+            {"op": "replace", "path": "/a/b", "value": {}}
+        ]);
+        assert_eq!(apply_json_patch(&document, &patch).unwrap(), json!({"a": {"b": {}}, "d": [9, 2, 3], "e/f": 1}));
+        assert_eq!(apply_json_patch(&document, &json!([{"op": "replace", "path": "", "value": 7}])).unwrap(), json!(7));
+    }
+
+    #[test]
+    fn reports_the_failing_operation() {
+        let document = json!({"a": [1]});
+        let error = |patch: Value| apply_json_patch(&document, &patch).unwrap_err().to_string();
+        assert_eq!(
+            error(json!([{"op": "add", "path": "/b", "value": 1}, {"op": "test", "path": "/a/0", "value": 2}])),
+            r#"operation 1: test failed: "/a/0" is 1, expected 2"#
+        );
+        assert_eq!(error(json!([{"op": "remove", "path": "/x/y"}])), r#"operation 0: "/x/y" does not exist"#);
+        assert_eq!(error(json!([{"op": "add", "path": "/a/2", "value": 0}])), "operation 0: index 2 is out of bounds for an array of 1");
+        assert_eq!(error(json!([{"op": "add", "path": "/a/01", "value": 0}])), "operation 0: \"01\" is not an array index");
+        assert_eq!(error(json!([{"op": "move", "from": "/a", "path": "/a/0"}])), r#"operation 0: cannot move "/a" into itself at "/a/0""#);
+        assert_eq!(error(json!([{"op": "add", "path": "a"}])), "operation 0: \"a\" is not a JSON Pointer; it must be empty or start with /");
+        assert_eq!(error(json!([{"op": "add", "path": "/b"}])), "operation 0: missing \"value\"");
+        assert_eq!(error(json!([{"op": "rename"}])), "operation 0: unknown op \"rename\"");
+        assert_eq!(error(json!({"op": "add"})), "a JSON Patch must be an array of operations");
+    }
+
+    #[test]
+    fn applies_and_generates_merge_patches() {
+        let document = json!({"title": "Hi", "author": {"name": "A", "email": "a@x"}, "tags": ["a"]});
+        let patch = json!({"title": "Hello", "author": {"email": null}, "tags": ["b"], "new": {"k": 1}});
+        let merged = apply_merge_patch(&document, &patch);
+        assert_eq!(merged, json!({"title": "Hello", "author": {"name": "A"}, "tags": ["b"], "new": {"k": 1}}));
+        assert_eq!(apply_merge_patch(&json!([1]), &json!({"a": null})), json!({}));
+
+        assert_eq!(merge_patch(&document, &merged).unwrap(), patch);
+        assert_eq!(
+            merge_patch(&json!({"a": 1}), &json!({"a": null})).unwrap_err().to_string(),
+            "a Merge Patch cannot set $.a to null, since null removes a member; use a JSON Patch instead"
+        );
+        assert!(merge_patch(&json!({}), &json!({"a": {"b": null}})).is_err());
+    }
+
+    #[test]
+    fn generated_patches_apply() {
+        let left = json!({"list": ["a", "b", "c"], "x": {"y": 1}, "gone": true});
+        let right = json!({"list": ["b", "c", "d"], "x": {"y": 2, "z": [1]}});
+        for format in PatchFormat::ALL {
+            let patch = generate(&left, &right, format).unwrap();
+            assert_eq!(apply(&left, &patch, format).unwrap(), right, "{}", format.as_param());
+        }
+    }
+}
@@ -0,0 +1,114 @@
+//! Locations inside a JSON document.
+//!
+//! A [`JsonPath`] is written as JSONPath (`$.items[3].id`) for display and as a
+//! JSON Pointer (`/items/3/id`, RFC 6901) where a standard requires one.
+
+use crate::json::is_identifier;
+use serde_json::Value;
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct JsonPath(Vec<Segment>);
+
+impl JsonPath {
+    /// The whole document, `$`.
+    pub fn root() -> Self {
+        JsonPath(Vec::new())
+    }
+
+    pub fn key(&self, key: &str) -> Self {
+        self.child(Segment::Key(key.to_string()))
+    }
+
+    pub fn index(&self, index: usize) -> Self {
+        self.child(Segment::Index(index))
+    }
+
+    pub fn child(&self, segment: Segment) -> Self {
+        let mut segments = self.0.clone();
+        segments.push(segment);
+        JsonPath(segments)
+    }
+
+    pub fn segments(&self) -> &[Segment] {
+        &self.0
+    }
+
+    pub fn is_root(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The value at this path within `document`.
+    pub fn lookup<'a>(&self, document: &'a Value) -> Option<&'a Value> {
+        self.0.iter().try_fold(document, |value, segment| match segment {
+            Segment::Key(key) => value.as_object()?.get(key),
+            Segment::Index(index) => value.as_array()?.get(*index),
+        })
+    }
+
+    /// The path as a JSON Pointer, e.g. `/items/3/id`; the root is the empty string.
+    pub fn to_pointer(&self) -> String {
+        self.0
+            .iter()
+            .map(|segment| match segment {
+                Segment::Key(key) => format!("/{}", key.replace('~', "~0").replace('/', "~1")),
+                Segment::Index(index) => format!("/{}", index),
+            })
+            .collect()
+    }
+}
+
+impl From<Vec<Segment>> for JsonPath {
+    fn from(segments: Vec<Segment>) -> Self {
+        JsonPath(segments)
+    }
+}
+
+/// JSONPath notation: identifiers as `.key`, other keys as `['key']`.
+impl fmt::Display for JsonPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("$")?;
+        for segment in &self.0 {
+            match segment {
+                Segment::Key(key) if is_identifier(key) => write!(f, ".{}", key)?,
+                Segment::Key(key) => write!(f, "['{}']", key.replace('\\', "\\\\").replace('\'', "\\'"))?,
+                Segment::Index(index) => write!(f, "[{}]", index)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn displays_as_jsonpath() {
+        let path = JsonPath::root().key("items").index(3).key("id");
+        assert_eq!(path.to_string(), "$.items[3].id");
+        assert_eq!(JsonPath::root().to_string(), "$");
+        assert_eq!(JsonPath::root().key("content-type").key("it's").to_string(), "$['content-type']['it\\'s']");
+    }
+
+    #[test]
+    fn converts_to_json_pointer() {
+        let path = JsonPath::root().key("a/b").key("m~n").index(0);
+        assert_eq!(path.to_pointer(), "/a~1b/m~0n/0");
+        assert_eq!(JsonPath::root().to_pointer(), "");
+    }
+
+    #[test]
+    fn looks_up_values() {
+        let document = json!({"items": [{"id": 7}]});
+        assert_eq!(JsonPath::root().key("items").index(0).key("id").lookup(&document), Some(&json!(7)));
+        assert_eq!(JsonPath::root().key("items").key("id").lookup(&document), None);
+    }
+}
@@ -0,0 +1,152 @@
+use crate::json::{self, FormatType, JsonError};
+use crate::{base64, url};
+use std::fmt;
+use std::string::FromUtf8Error;
+
+/// A single conversion that can be chained with others.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Step {
+    UrlEncode,
+    UrlDecode,
+    Base64Encode,
+    Base64Decode,
+    JsonFormat,
+    JsonMinify,
+    JsonYaml,
+}
+
+impl Step {
+    pub const ALL: [Step; 7] = [
+        Step::UrlEncode,
+        Step::UrlDecode,
+        Step::Base64Encode,
+        Step::Base64Decode,
+        Step::JsonFormat,
+        Step::JsonMinify,
+        Step::JsonYaml,
+    ];
+
+    /// Value used in share links and saved pipelines, e.g. `base64_decode`.
+    pub fn as_param(&self) -> &'static str {
+        match self {
+            Step::UrlEncode => "url_encode",
+            Step::UrlDecode => "url_decode",
+            Step::Base64Encode => "base64_encode",
+            Step::Base64Decode => "base64_decode",
+            Step::JsonFormat => "json_format",
+            Step::JsonMinify => "json_minify",
+            Step::JsonYaml => "json_yaml",
+        }
+    }
+
+    pub fn from_param(value: &str) -> Option<Self> {
+        Step::ALL.into_iter().find(|step| step.as_param() == value)
+    }
+
+    pub fn apply(&self, input: &str) -> Result<String, StepError> {
+        match self {
+            Step::UrlEncode => Ok(url::encode(input)),
+            Step::UrlDecode => url::decode(input).map_err(StepError::Url),
+            Step::Base64Encode => Ok(base64::encode(input)),
+            Step::Base64Decode => base64::decode(input).map_err(StepError::Base64),
+            Step::JsonFormat => json::format(input, FormatType::Standard).map_err(StepError::Json),
+            Step::JsonMinify => json::minify(input).map_err(StepError::Json),
+            Step::JsonYaml => json::format(input, FormatType::Yaml).map_err(StepError::Json),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum StepError {
+    Url(FromUtf8Error),
+    Base64(base64::DecodeError),
+    Json(JsonError),
+}
+
+impl fmt::Display for StepError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StepError::Url(e) => e.fmt(f),
+            StepError::Base64(e) => e.fmt(f),
+            StepError::Json(e) => e.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for StepError {}
+
+#[derive(Debug)]
+pub struct StepResult {
+    pub step: Step,
+    pub output: Result<String, StepError>,
+}
+
+/// Runs `steps` in order, feeding each output into the next step.
+///
+/// Evaluation stops at the first failing step, which is the last result returned.
+pub fn run(input: &str, steps: &[Step]) -> Vec<StepResult> {
+    let mut results = Vec::with_capacity(steps.len());
+    let mut current = input.to_string();
+    for &step in steps {
+        let output = step.apply(&current);
+        let failed = output.is_err();
+        if let Ok(text) = &output {
+            current = text.clone();
+        }
+        results.push(StepResult { step, output });
+        if failed {
+            break;
+        }
+    }
+    results
+}
+
+/// Serializes steps as a comma-separated list, e.g. `url_decode,base64_decode`.
+pub fn steps_to_param(steps: &[Step]) -> String {
+    steps.iter().map(Step::as_param).collect::<Vec<_>>().join(",")
+}
+
+/// Parses a comma-separated list of steps, skipping unknown names.
+pub fn steps_from_param(value: &str) -> Vec<Step> {
+    value.split(',').filter_map(|name| Step::from_param(name.trim())).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chains_outputs() {
+        // {"a":1} Base64-encoded, then percent-encoded
+        let results = run(
+            "eyJhIjoxfQ%3D%3D",
+            &[Step::UrlDecode, Step::Base64Decode, Step::JsonFormat],
+        );
+        let outputs: Vec<&str> = results
+            .iter()
+            .map(|result| result.output.as_ref().unwrap().as_str())
+            .collect();
+        assert_eq!(outputs, ["eyJhIjoxfQ==", "{\"a\":1}", "{\n  \"a\": 1\n}"]);
+    }
+
+    #[test]
+    fn stops_at_the_failing_step() {
+        let results = run("{\"a\": 1}", &[Step::Base64Decode, Step::JsonFormat]);
+        assert_eq!(results.len(), 1);
+        assert!(matches!(results[0].output, Err(StepError::Base64(_))));
+    }
+
+    #[test]
+    fn minify_then_encode() {
+        let results = run("{ \"a\": 1 }", &[Step::JsonMinify, Step::Base64Encode]);
+        assert_eq!(results[1].output.as_ref().unwrap(), "eyJhIjoxfQ==");
+    }
+
+    #[test]
+    fn steps_param_round_trip() {
+        let steps = [Step::UrlDecode, Step::JsonYaml];
+        assert_eq!(steps_to_param(&steps), "url_decode,json_yaml");
+        assert_eq!(steps_from_param("url_decode, json_yaml,bogus"), steps);
+        assert!(steps_from_param("").is_empty());
+    }
+}
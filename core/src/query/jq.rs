@@ -0,0 +1,437 @@
+//! A practical jq subset: paths, `[]`, slices, `?`, pipes, commas, array
+//! construction, comparisons, `and`/`or`, and `keys`, `length`, `map`,
+//! `select`, `not` and `type`.
+
+use super::lexer::{CmpOp, Parser, Token};
+use super::{compare, number, type_name, Match, QueryError};
+use serde_json::Value;
+use std::cmp::Ordering;
+
+#[derive(Debug)]
+enum Expr {
+    Identity,
+    /// `..`: the input and everything below it.
+    Recurse,
+    Literal(Value),
+    Field(Box<Expr>, String),
+    Index(Box<Expr>, Box<Expr>),
+    Slice(Box<Expr>, Option<Box<Expr>>, Option<Box<Expr>>),
+    Iterate(Box<Expr>),
+    /// `expr?`: errors produce no output instead.
+    Try(Box<Expr>),
+    Collect(Option<Box<Expr>>),
+    Pipe(Box<Expr>, Box<Expr>),
+    Comma(Box<Expr>, Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Cmp(Box<Expr>, CmpOp, Box<Expr>),
+    Call(Builtin),
+}
+
+#[derive(Debug)]
+enum Builtin {
+    Keys,
+    Length,
+    Not,
+    Type,
+    Map(Box<Expr>),
+    Select(Box<Expr>),
+}
+
+pub(super) fn evaluate(query: &str, document: &Value) -> Result<Vec<Match>, QueryError> {
+    let mut parser = Parser::new(query)?;
+    let expr = pipe(&mut parser)?;
+    if !parser.at_end() {
+        return Err(parser.error("unexpected token".to_string()));
+    }
+    Ok(eval(&expr, document)?
+        .into_iter()
+        .map(|value| Match { path: None, value })
+        .collect())
+}
+
+fn pipe(parser: &mut Parser) -> Result<Expr, QueryError> {
+    let expr = comma(parser)?;
+    if parser.eat(&Token::Pipe) {
+        return Ok(Expr::Pipe(Box::new(expr), Box::new(pipe(parser)?)));
+    }
+    Ok(expr)
+}
+
+fn comma(parser: &mut Parser) -> Result<Expr, QueryError> {
+    let mut expr = or(parser)?;
+    while parser.eat(&Token::Comma) {
+        expr = Expr::Comma(Box::new(expr), Box::new(or(parser)?));
+    }
+    Ok(expr)
+}
+
+fn keyword(parser: &mut Parser, word: &str) -> bool {
+    if matches!(parser.peek(), Some(Token::Ident(ident)) if ident == word) {
+        parser.next();
+        true
+    } else {
+        false
+    }
+}
+
+fn or(parser: &mut Parser) -> Result<Expr, QueryError> {
+    let mut expr = and(parser)?;
+    while keyword(parser, "or") {
+        expr = Expr::Or(Box::new(expr), Box::new(and(parser)?));
+    }
+    Ok(expr)
+}
+
+fn and(parser: &mut Parser) -> Result<Expr, QueryError> {
+    let mut expr = comparison(parser)?;
+    while keyword(parser, "and") {
+        expr = Expr::And(Box::new(expr), Box::new(comparison(parser)?));
+    }
+    Ok(expr)
+}
+
+fn comparison(parser: &mut Parser) -> Result<Expr, QueryError> {
+    let left = postfix(parser)?;
+    if let Some(Token::Cmp(op)) = parser.peek().cloned() {
+        parser.next();
+        return Ok(Expr::Cmp(Box::new(left), op, Box::new(postfix(parser)?)));
+    }
+    Ok(left)
+}
+
+fn postfix(parser: &mut Parser) -> Result<Expr, QueryError> {
+    let mut expr = primary(parser)?;
+    loop {
+        expr = match parser.peek() {
+            Some(Token::Dot) => match parser.peek_at(1).cloned() {
+                Some(Token::Ident(name) | Token::Str(name)) => {
+                    parser.next();
+                    parser.next();
+                    Expr::Field(Box::new(expr), name)
+                }
+                // jq 1.7 的 `.a.[0]` 写法
+                Some(Token::LBracket) => {
+                    parser.next();
+                    parser.next();
+                    bracket(parser, expr)?
+                }
+                _ => return Err(parser.error("expected a field name".to_string())),
+            },
+            Some(Token::LBracket) => {
+                parser.next();
+                bracket(parser, expr)?
+            }
+            Some(Token::Question) => {
+                parser.next();
+                Expr::Try(Box::new(expr))
+            }
+            _ => return Ok(expr),
+        };
+    }
+}
+
+/// `[]`, `[i]`, `["key"]` or a slice applied to `base`, the `[` already consumed.
+fn bracket(parser: &mut Parser, base: Expr) -> Result<Expr, QueryError> {
+    let base = Box::new(base);
+    if parser.eat(&Token::RBracket) {
+        return Ok(Expr::Iterate(base));
+    }
+    let start = if parser.peek() == Some(&Token::Colon) { None } else { Some(Box::new(pipe(parser)?)) };
+    if parser.eat(&Token::Colon) {
+        let end = if parser.peek() == Some(&Token::RBracket) { None } else { Some(Box::new(pipe(parser)?)) };
+        parser.expect(&Token::RBracket, "`]`")?;
+        return Ok(Expr::Slice(base, start, end));
+    }
+    parser.expect(&Token::RBracket, "`]`")?;
+    match start {
+        Some(index) => Ok(Expr::Index(base, index)),
+        None => Err(parser.error("expected an index".to_string())),
+    }
+}
+
+fn primary(parser: &mut Parser) -> Result<Expr, QueryError> {
+    let Some(token) = parser.peek().cloned() else {
+        return Err(parser.error("unexpected end of query".to_string()));
+    };
+    match token {
+        Token::Dot => {
+            parser.next();
+            match parser.peek().cloned() {
+                Some(Token::Ident(name) | Token::Str(name)) => {
+                    parser.next();
+                    Ok(Expr::Field(Box::new(Expr::Identity), name))
+                }
+                _ => Ok(Expr::Identity),
+            }
+        }
+        Token::DotDot => {
+            parser.next();
+            Ok(Expr::Recurse)
+        }
+        Token::Str(text) => {
+            parser.next();
+            Ok(Expr::Literal(Value::String(text)))
+        }
+        Token::Num(text) => {
+            let value = number(&text).ok_or_else(|| parser.error(format!("invalid number `{}`", text)))?;
+            parser.next();
+            Ok(Expr::Literal(value))
+        }
+        Token::LParen => {
+            parser.next();
+            let expr = pipe(parser)?;
+            parser.expect(&Token::RParen, "`)`")?;
+            Ok(expr)
+        }
+        Token::LBracket => {
+            parser.next();
+            if parser.eat(&Token::RBracket) {
+                return Ok(Expr::Collect(None));
+            }
+            let expr = pipe(parser)?;
+            parser.expect(&Token::RBracket, "`]`")?;
+            Ok(Expr::Collect(Some(Box::new(expr))))
+        }
+        Token::Ident(name) => function(parser, &name),
+        _ => Err(parser.error("unexpected token".to_string())),
+    }
+}
+
+fn function(parser: &mut Parser, name: &str) -> Result<Expr, QueryError> {
+    let builtin = match name {
+        "true" => Expr::Literal(Value::Bool(true)),
+        "false" => Expr::Literal(Value::Bool(false)),
+        "null" => Expr::Literal(Value::Null),
+        "keys" => Expr::Call(Builtin::Keys),
+        "length" => Expr::Call(Builtin::Length),
+        "not" => Expr::Call(Builtin::Not),
+        "type" => Expr::Call(Builtin::Type),
+        "map" | "select" => {
+            parser.next();
+            parser.expect(&Token::LParen, "`(`")?;
+            let argument = Box::new(pipe(parser)?);
+            parser.expect(&Token::RParen, "`)`")?;
+            return Ok(Expr::Call(if name == "map" {
+                Builtin::Map(argument)
+            } else {
+                Builtin::Select(argument)
+            }));
+        }
+        _ => return Err(parser.error(format!("unknown function `{}`", name))),
+    };
+    parser.next();
+    Ok(builtin)
+}
+
+fn eval(expr: &Expr, input: &Value) -> Result<Vec<Value>, QueryError> {
+    match expr {
+        Expr::Identity => Ok(vec![input.clone()]),
+        Expr::Recurse => {
+            let mut out = Vec::new();
+            recurse(input, &mut out);
+            Ok(out)
+        }
+        Expr::Literal(value) => Ok(vec![value.clone()]),
+        Expr::Field(base, name) => eval(base, input)?
+            .iter()
+            .map(|value| match value {
+                Value::Object(map) => Ok(map.get(name).cloned().unwrap_or(Value::Null)),
+                Value::Null => Ok(Value::Null),
+                other => Err(QueryError::Eval(format!("cannot index {} with \"{}\"", type_name(other), name))),
+            })
+            .collect(),
+        Expr::Index(base, index) => {
+            let mut out = Vec::new();
+            for value in eval(base, input)? {
+                for index in eval(index, input)? {
+                    out.push(lookup(&value, &index)?);
+                }
+            }
+            Ok(out)
+        }
+        Expr::Slice(base, start, end) => {
+            let bound = |bound: &Option<Box<Expr>>| -> Result<Option<Value>, QueryError> {
+                match bound {
+                    Some(expr) => Ok(eval(expr, input)?.into_iter().next()),
+                    None => Ok(None),
+                }
+            };
+            let (start, end) = (bound(start)?, bound(end)?);
+            eval(base, input)?
+                .iter()
+                .map(|value| slice(value, start.as_ref(), end.as_ref()))
+                .collect()
+        }
+        Expr::Iterate(base) => {
+            let mut out = Vec::new();
+            for value in eval(base, input)? {
+                out.extend(iterate(&value)?);
+            }
+            Ok(out)
+        }
+        Expr::Try(expr) => Ok(eval(expr, input).unwrap_or_default()),
+        Expr::Collect(expr) => {
+            let items = match expr {
+                Some(expr) => eval(expr, input)?,
+                None => Vec::new(),
+            };
+            Ok(vec![Value::Array(items)])
+        }
+        Expr::Pipe(left, right) => {
+            let mut out = Vec::new();
+            for value in eval(left, input)? {
+                out.extend(eval(right, &value)?);
+            }
+            Ok(out)
+        }
+        Expr::Comma(left, right) => {
+            let mut out = eval(left, input)?;
+            out.extend(eval(right, input)?);
+            Ok(out)
+        }
+        Expr::And(left, right) | Expr::Or(left, right) => {
+            let is_and = matches!(expr, Expr::And(..));
+            let mut out = Vec::new();
+            for left in eval(left, input)? {
+                // 左边已经决定结果时不再计算右边
+                if truthy(&left) != is_and {
+                    out.push(Value::Bool(!is_and));
+                    continue;
+                }
+                for right in eval(right, input)? {
+                    out.push(Value::Bool(truthy(&right)));
+                }
+            }
+            Ok(out)
+        }
+        Expr::Cmp(left, op, right) => {
+            let mut out = Vec::new();
+            for right in eval(right, input)? {
+                for left in eval(left, input)? {
+                    let ordering = compare(&left, &right);
+                    let result = match op {
+                        CmpOp::Eq => ordering == Ordering::Equal,
+                        CmpOp::Ne => ordering != Ordering::Equal,
+                        CmpOp::Lt => ordering == Ordering::Less,
+                        CmpOp::Le => ordering != Ordering::Greater,
+                        CmpOp::Gt => ordering == Ordering::Greater,
+                        CmpOp::Ge => ordering != Ordering::Less,
+                    };
+                    out.push(Value::Bool(result));
+                }
+            }
+            Ok(out)
+        }
+        Expr::Call(builtin) => call(builtin, input),
+    }
+}
+
+fn call(builtin: &Builtin, input: &Value) -> Result<Vec<Value>, QueryError> {
+    let value = match builtin {
+        Builtin::Keys => match input {
+            Value::Object(map) => {
+                let mut keys: Vec<&String> = map.keys().collect();
+                keys.sort();
+                Value::from(keys.into_iter().cloned().collect::<Vec<_>>())
+            }
+            Value::Array(items) => Value::from((0..items.len()).collect::<Vec<_>>()),
+            other => return Err(QueryError::Eval(format!("{} has no keys", type_name(other)))),
+        },
+        Builtin::Length => match input {
+            Value::Null => Value::from(0),
+            Value::Bool(_) => return Err(QueryError::Eval("boolean has no length".to_string())),
+            Value::Number(n) => match n.as_i64() {
+                Some(n) => Value::from(n.unsigned_abs()),
+                None => Value::from(n.as_f64().unwrap_or_default().abs()),
+            },
+            Value::String(text) => Value::from(text.chars().count()),
+            Value::Array(items) => Value::from(items.len()),
+            Value::Object(map) => Value::from(map.len()),
+        },
+        Builtin::Not => Value::Bool(!truthy(input)),
+        Builtin::Type => Value::from(type_name(input)),
+        Builtin::Map(f) => {
+            let mut out = Vec::new();
+            for item in iterate(input)? {
+                out.extend(eval(f, &item)?);
+            }
+            Value::Array(out)
+        }
+        Builtin::Select(f) => {
+            let selected = eval(f, input)?.iter().filter(|value| truthy(value)).count();
+            return Ok(vec![input.clone(); selected]);
+        }
+    };
+    Ok(vec![value])
+}
+
+fn truthy(value: &Value) -> bool {
+    !matches!(value, Value::Null | Value::Bool(false))
+}
+
+fn recurse(value: &Value, out: &mut Vec<Value>) {
+    out.push(value.clone());
+    match value {
+        Value::Array(items) => items.iter().for_each(|item| recurse(item, out)),
+        Value::Object(map) => map.values().for_each(|item| recurse(item, out)),
+        _ => {}
+    }
+}
+
+fn iterate(value: &Value) -> Result<Vec<Value>, QueryError> {
+    match value {
+        Value::Array(items) => Ok(items.clone()),
+        Value::Object(map) => Ok(map.values().cloned().collect()),
+        other => Err(QueryError::Eval(format!("cannot iterate over {}", type_name(other)))),
+    }
+}
+
+fn lookup(value: &Value, index: &Value) -> Result<Value, QueryError> {
+    match (value, index) {
+        (Value::Null, _) => Ok(Value::Null),
+        (Value::Object(map), Value::String(key)) => Ok(map.get(key).cloned().unwrap_or(Value::Null)),
+        (Value::Array(items), Value::Number(n)) => {
+            let index = n.as_f64().unwrap_or_default().floor() as i64;
+            let index = if index < 0 { items.len() as i64 + index } else { index };
+            Ok(usize::try_from(index)
+                .ok()
+                .and_then(|index| items.get(index))
+                .cloned()
+                .unwrap_or(Value::Null))
+        }
+        (value, index) => Err(QueryError::Eval(format!(
+            "cannot index {} with {}",
+            type_name(value),
+            type_name(index)
+        ))),
+    }
+}
+
+fn slice(value: &Value, start: Option<&Value>, end: Option<&Value>) -> Result<Value, QueryError> {
+    let range = |len: usize| -> Result<(usize, usize), QueryError> {
+        let bound = |bound: Option<&Value>, default: usize| match bound {
+            None | Some(Value::Null) => Ok(default),
+            Some(Value::Number(n)) => {
+                let index = n.as_f64().unwrap_or_default().floor() as i64;
+                let index = if index < 0 { len as i64 + index } else { index };
+                Ok(index.clamp(0, len as i64) as usize)
+            }
+            Some(other) => Err(QueryError::Eval(format!("cannot slice with {}", type_name(other)))),
+        };
+        let start = bound(start, 0)?;
+        Ok((start, bound(end, len)?.max(start)))
+    };
+    match value {
+        Value::Null => Ok(Value::Null),
+        Value::Array(items) => {
+            let (start, end) = range(items.len())?;
+            Ok(Value::Array(items[start..end].to_vec()))
+        }
+        Value::String(text) => {
+            let (start, end) = range(text.chars().count())?;
+            Ok(Value::String(text.chars().skip(start).take(end - start).collect()))
+        }
+        other => Err(QueryError::Eval(format!("cannot slice {}", type_name(other)))),
+    }
+}
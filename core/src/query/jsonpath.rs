@@ -0,0 +1,355 @@
+//! JSONPath (RFC 9535): names, wildcards, indices, slices, unions, descendants
+//! and filters with comparisons, `&&`, `||` and `!`.
+
+use super::lexer::{CmpOp, Parser, Token};
+use super::{compare, number, Match, QueryError};
+use crate::path::JsonPath;
+use serde_json::Value;
+use std::cmp::Ordering;
+
+#[derive(Debug)]
+enum Selector {
+    Name(String),
+    Wildcard,
+    Index(i64),
+    Slice(Option<i64>, Option<i64>, Option<i64>),
+    Filter(Expr),
+}
+
+#[derive(Debug)]
+enum Segment {
+    Child(Vec<Selector>),
+    Descendant(Vec<Selector>),
+}
+
+#[derive(Debug)]
+struct Query {
+    /// Starts at `@`, the node being filtered, rather than at `$`.
+    relative: bool,
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug)]
+enum Expr {
+    Or(Box<Expr>, Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Cmp(Operand, CmpOp, Operand),
+    Exists(Query),
+}
+
+#[derive(Debug)]
+enum Operand {
+    Literal(Value),
+    Query(Query),
+}
+
+type Node<'a> = (JsonPath, &'a Value);
+
+pub(super) fn evaluate(query: &str, document: &Value) -> Result<Vec<Match>, QueryError> {
+    let mut parser = Parser::new(query)?;
+    parser.expect(&Token::Dollar, "`$`")?;
+    let query = Query {
+        relative: false,
+        segments: segments(&mut parser)?,
+    };
+    if !parser.at_end() {
+        return Err(parser.error("unexpected token".to_string()));
+    }
+    Ok(run(&query, document, document)
+        .into_iter()
+        .map(|(path, value)| Match {
+            path: Some(path),
+            value: value.clone(),
+        })
+        .collect())
+}
+
+fn segments(parser: &mut Parser) -> Result<Vec<Segment>, QueryError> {
+    let mut segments = Vec::new();
+    loop {
+        let segment = match parser.peek() {
+            Some(Token::Dot) => {
+                parser.next();
+                Segment::Child(vec![dotted(parser)?])
+            }
+            Some(Token::DotDot) => {
+                parser.next();
+                if parser.eat(&Token::LBracket) {
+                    Segment::Descendant(bracket(parser)?)
+                } else {
+                    Segment::Descendant(vec![dotted(parser)?])
+                }
+            }
+            Some(Token::LBracket) => {
+                parser.next();
+                Segment::Child(bracket(parser)?)
+            }
+            _ => return Ok(segments),
+        };
+        segments.push(segment);
+    }
+}
+
+/// The selector after `.` or `..`: a name or `*`.
+fn dotted(parser: &mut Parser) -> Result<Selector, QueryError> {
+    match parser.peek().cloned() {
+        Some(Token::Star) => {
+            parser.next();
+            Ok(Selector::Wildcard)
+        }
+        Some(Token::Ident(name)) => {
+            parser.next();
+            Ok(Selector::Name(name))
+        }
+        _ => Err(parser.error("expected a name or `*`".to_string())),
+    }
+}
+
+/// Selectors between `[` and `]`, the opening bracket already consumed.
+fn bracket(parser: &mut Parser) -> Result<Vec<Selector>, QueryError> {
+    let mut selectors = vec![selector(parser)?];
+    while parser.eat(&Token::Comma) {
+        selectors.push(selector(parser)?);
+    }
+    parser.expect(&Token::RBracket, "`]`")?;
+    Ok(selectors)
+}
+
+fn selector(parser: &mut Parser) -> Result<Selector, QueryError> {
+    match parser.peek().cloned() {
+        Some(Token::Str(name)) => {
+            parser.next();
+            Ok(Selector::Name(name))
+        }
+        Some(Token::Star) => {
+            parser.next();
+            Ok(Selector::Wildcard)
+        }
+        Some(Token::Question) => {
+            parser.next();
+            Ok(Selector::Filter(or(parser)?))
+        }
+        Some(Token::Num(_) | Token::Colon) => {
+            let start = integer(parser)?;
+            if !parser.eat(&Token::Colon) {
+                return start.map(Selector::Index).ok_or_else(|| parser.error("expected an index".to_string()));
+            }
+            let end = integer(parser)?;
+            let step = if parser.eat(&Token::Colon) { integer(parser)? } else { None };
+            Ok(Selector::Slice(start, end, step))
+        }
+        _ => Err(parser.error("expected a selector".to_string())),
+    }
+}
+
+fn integer(parser: &mut Parser) -> Result<Option<i64>, QueryError> {
+    let Some(Token::Num(text)) = parser.peek().cloned() else {
+        return Ok(None);
+    };
+    let value = text.parse().map_err(|_| parser.error(format!("`{}` is not an integer", text)))?;
+    parser.next();
+    Ok(Some(value))
+}
+
+fn or(parser: &mut Parser) -> Result<Expr, QueryError> {
+    let mut expr = and(parser)?;
+    while parser.eat(&Token::Or) {
+        expr = Expr::Or(Box::new(expr), Box::new(and(parser)?));
+    }
+    Ok(expr)
+}
+
+fn and(parser: &mut Parser) -> Result<Expr, QueryError> {
+    let mut expr = unary(parser)?;
+    while parser.eat(&Token::And) {
+        expr = Expr::And(Box::new(expr), Box::new(unary(parser)?));
+    }
+    Ok(expr)
+}
+
+fn unary(parser: &mut Parser) -> Result<Expr, QueryError> {
+    if parser.eat(&Token::Not) {
+        return Ok(Expr::Not(Box::new(unary(parser)?)));
+    }
+    if parser.eat(&Token::LParen) {
+        let expr = or(parser)?;
+        parser.expect(&Token::RParen, "`)`")?;
+        return Ok(expr);
+    }
+    let left = operand(parser)?;
+    if let Some(Token::Cmp(op)) = parser.peek().cloned() {
+        parser.next();
+        return Ok(Expr::Cmp(left, op, operand(parser)?));
+    }
+    match left {
+        Operand::Query(query) => Ok(Expr::Exists(query)),
+        Operand::Literal(_) => Err(parser.error("expected a comparison".to_string())),
+    }
+}
+
+fn operand(parser: &mut Parser) -> Result<Operand, QueryError> {
+    let literal = match parser.peek().cloned() {
+        Some(token @ (Token::At | Token::Dollar)) => {
+            parser.next();
+            return Ok(Operand::Query(Query {
+                relative: token == Token::At,
+                segments: segments(parser)?,
+            }));
+        }
+        Some(Token::Str(text)) => Some(Value::String(text)),
+        Some(Token::Num(text)) => number(&text),
+        Some(Token::Ident(word)) => match word.as_str() {
+            "true" => Some(Value::Bool(true)),
+            "false" => Some(Value::Bool(false)),
+            "null" => Some(Value::Null),
+            _ => None,
+        },
+        _ => None,
+    };
+    let literal = literal.ok_or_else(|| parser.error("expected `@`, `$` or a literal".to_string()))?;
+    parser.next();
+    Ok(Operand::Literal(literal))
+}
+
+fn run<'a>(query: &Query, current: &'a Value, root: &'a Value) -> Vec<Node<'a>> {
+    let start = if query.relative { current } else { root };
+    query.segments.iter().fold(vec![(JsonPath::root(), start)], |nodes, segment| {
+        let mut selected = Vec::new();
+        for node in nodes {
+            match segment {
+                Segment::Child(selectors) => {
+                    for selector in selectors {
+                        select(&node, selector, root, &mut selected);
+                    }
+                }
+                Segment::Descendant(selectors) => {
+                    for descendant in descendants(node) {
+                        for selector in selectors {
+                            select(&descendant, selector, root, &mut selected);
+                        }
+                    }
+                }
+            }
+        }
+        selected
+    })
+}
+
+/// The node and everything below it, parents before children.
+fn descendants(node: Node<'_>) -> Vec<Node<'_>> {
+    // Pre-order with an explicit stack; children are pushed in reverse so the
+    // first child is visited next
+    let mut nodes = Vec::new();
+    let mut stack = vec![node];
+    while let Some(node) = stack.pop() {
+        stack.extend(children(&node).into_iter().rev());
+        nodes.push(node);
+    }
+    nodes
+}
+
+fn children<'a>((path, value): &Node<'a>) -> Vec<Node<'a>> {
+    match value {
+        Value::Object(map) => map.iter().map(|(key, child)| (path.key(key), child)).collect(),
+        Value::Array(items) => items.iter().enumerate().map(|(index, child)| (path.index(index), child)).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn select<'a>(node: &Node<'a>, selector: &Selector, root: &'a Value, out: &mut Vec<Node<'a>>) {
+    let (path, value) = node;
+    match selector {
+        Selector::Name(name) => {
+            if let Some(child) = value.as_object().and_then(|map| map.get(name)) {
+                out.push((path.key(name), child));
+            }
+        }
+        Selector::Wildcard => out.extend(children(node)),
+        Selector::Index(index) => {
+            if let Some(items) = value.as_array() {
+                let index = if *index < 0 { items.len() as i64 + index } else { *index };
+                if let Some(child) = usize::try_from(index).ok().and_then(|index| items.get(index)) {
+                    out.push((path.index(index as usize), child));
+                }
+            }
+        }
+        Selector::Slice(start, end, step) => {
+            if let Some(items) = value.as_array() {
+                for index in slice_indices(items.len() as i64, *start, *end, step.unwrap_or(1)) {
+                    out.push((path.index(index), &items[index]));
+                }
+            }
+        }
+        Selector::Filter(expr) => {
+            out.extend(children(node).into_iter().filter(|(_, child)| test(expr, child, root)));
+        }
+    }
+}
+
+fn slice_indices(len: i64, start: Option<i64>, end: Option<i64>, step: i64) -> Vec<usize> {
+    let normalize = |index: i64| if index < 0 { len + index } else { index };
+    let mut indices = Vec::new();
+    if step > 0 {
+        let lower = start.map_or(0, normalize).clamp(0, len);
+        let upper = end.map_or(len, normalize).clamp(0, len);
+        let mut index = lower;
+        while index < upper {
+            indices.push(index as usize);
+            index += step;
+        }
+    } else if step < 0 {
+        let upper = start.map_or(len - 1, normalize).clamp(-1, len - 1);
+        let lower = end.map_or(-1, normalize).clamp(-1, len - 1);
+        let mut index = upper;
+        while lower < index {
+            indices.push(index as usize);
+            index += step;
+        }
+    }
+    indices
+}
+
+fn test(expr: &Expr, current: &Value, root: &Value) -> bool {
+    match expr {
+        Expr::Or(a, b) => test(a, current, root) || test(b, current, root),
+        Expr::And(a, b) => test(a, current, root) && test(b, current, root),
+        Expr::Not(expr) => !test(expr, current, root),
+        Expr::Exists(query) => !run(query, current, root).is_empty(),
+        Expr::Cmp(left, op, right) => {
+            let left = operand_value(left, current, root);
+            let right = operand_value(right, current, root);
+            match (left, right) {
+                // 两边都不存在时视为相等
+                (None, None) => matches!(op, CmpOp::Eq | CmpOp::Le | CmpOp::Ge),
+                (Some(left), Some(right)) => {
+                    let comparable = matches!(
+                        (&left, &right),
+                        (Value::Number(_), Value::Number(_)) | (Value::String(_), Value::String(_))
+                    );
+                    let ordering = compare(&left, &right);
+                    match op {
+                        CmpOp::Eq => ordering == Ordering::Equal,
+                        CmpOp::Ne => ordering != Ordering::Equal,
+                        CmpOp::Lt => comparable && ordering == Ordering::Less,
+                        CmpOp::Le => comparable && ordering != Ordering::Greater,
+                        CmpOp::Gt => comparable && ordering == Ordering::Greater,
+                        CmpOp::Ge => comparable && ordering != Ordering::Less,
+                    }
+                }
+                _ => matches!(op, CmpOp::Ne),
+            }
+        }
+    }
+}
+
+/// A literal, or the single value a query selects; `None` when it selects nothing or several.
+fn operand_value(operand: &Operand, current: &Value, root: &Value) -> Option<Value> {
+    match operand {
+        Operand::Literal(value) => Some(value.clone()),
+        Operand::Query(query) => match run(query, current, root).as_slice() {
+            [(_, value)] => Some((*value).clone()),
+            _ => None,
+        },
+    }
+}
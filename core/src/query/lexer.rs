@@ -0,0 +1,224 @@
+use super::QueryError;
+
+#[derive(Clone, Debug, PartialEq)]
+pub(super) enum Token {
+    Dollar,
+    At,
+    Dot,
+    DotDot,
+    Star,
+    Question,
+    Colon,
+    Comma,
+    Pipe,
+    LBracket,
+    RBracket,
+    LParen,
+    RParen,
+    Not,
+    And,
+    Or,
+    Cmp(CmpOp),
+    Ident(String),
+    Str(String),
+    /// Number literal, kept as written.
+    Num(String),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(super) enum CmpOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+/// A token and the byte offset where it starts.
+pub(super) type Spanned = (Token, usize);
+
+pub(super) fn tokenize(query: &str) -> Result<Vec<Spanned>, QueryError> {
+    let bytes = query.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let start = i;
+        let next = bytes.get(i + 1).copied();
+        let token = match bytes[i] {
+            b' ' | b'\t' | b'\n' | b'\r' => {
+                i += 1;
+                continue;
+            }
+            b'.' if next == Some(b'.') => {
+                i += 2;
+                Token::DotDot
+            }
+            b'&' if next == Some(b'&') => {
+                i += 2;
+                Token::And
+            }
+            b'|' if next == Some(b'|') => {
+                i += 2;
+                Token::Or
+            }
+            b'=' if next == Some(b'=') => {
+                i += 2;
+                Token::Cmp(CmpOp::Eq)
+            }
+            b'!' if next == Some(b'=') => {
+                i += 2;
+                Token::Cmp(CmpOp::Ne)
+            }
+            b'<' if next == Some(b'=') => {
+                i += 2;
+                Token::Cmp(CmpOp::Le)
+            }
+            b'>' if next == Some(b'=') => {
+                i += 2;
+                Token::Cmp(CmpOp::Ge)
+            }
+            b'"' | b'\'' => {
+                let (text, end) = string(query, i)?;
+                i = end;
+                Token::Str(text)
+            }
+            b'-' | b'0'..=b'9' if bytes[i] != b'-' || next.is_some_and(|b| b.is_ascii_digit()) => {
+                i += 1;
+                while i < bytes.len()
+                    && (bytes[i].is_ascii_digit()
+                        || matches!(bytes[i], b'.' | b'e' | b'E')
+                        || (matches!(bytes[i], b'+' | b'-') && matches!(bytes[i - 1], b'e' | b'E')))
+                {
+                    // `1..` 中的 `..` 不属于数字
+                    if bytes[i] == b'.' && !bytes.get(i + 1).is_some_and(u8::is_ascii_digit) {
+                        break;
+                    }
+                    i += 1;
+                }
+                Token::Num(query[start..i].to_string())
+            }
+            b if b.is_ascii_alphabetic() || b == b'_' => {
+                i += 1;
+                while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_') {
+                    i += 1;
+                }
+                Token::Ident(query[start..i].to_string())
+            }
+            other => {
+                i += 1;
+                match other {
+                    b'$' => Token::Dollar,
+                    b'@' => Token::At,
+                    b'.' => Token::Dot,
+                    b'*' => Token::Star,
+                    b'?' => Token::Question,
+                    b':' => Token::Colon,
+                    b',' => Token::Comma,
+                    b'|' => Token::Pipe,
+                    b'[' => Token::LBracket,
+                    b']' => Token::RBracket,
+                    b'(' => Token::LParen,
+                    b')' => Token::RParen,
+                    b'!' => Token::Not,
+                    b'<' => Token::Cmp(CmpOp::Lt),
+                    b'>' => Token::Cmp(CmpOp::Gt),
+                    _ => {
+                        let c = query[start..].chars().next().unwrap_or_default();
+                        return Err(QueryError::Syntax(start, format!("unexpected character `{}`", c)));
+                    }
+                }
+            }
+        };
+        tokens.push((token, start));
+    }
+    Ok(tokens)
+}
+
+/// Reads a quoted string starting at `start`, returning its value and the index after it.
+fn string(query: &str, start: usize) -> Result<(String, usize), QueryError> {
+    let quote = query.as_bytes()[start] as char;
+    let mut text = String::new();
+    let mut chars = query[start + 1..].char_indices();
+    while let Some((offset, c)) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some((_, 'n')) => text.push('\n'),
+                Some((_, 't')) => text.push('\t'),
+                Some((_, 'r')) => text.push('\r'),
+                Some((_, 'u')) => {
+                    let hex: String = chars.by_ref().take(4).map(|(_, c)| c).collect();
+                    let c = u32::from_str_radix(&hex, 16)
+                        .ok()
+                        .and_then(char::from_u32)
+                        .ok_or_else(|| QueryError::Syntax(start, "invalid \\u escape".to_string()))?;
+                    text.push(c);
+                }
+                Some((_, c)) => text.push(c),
+                None => break,
+            },
+            c if c == quote => return Ok((text, start + 1 + offset + 1)),
+            c => text.push(c),
+        }
+    }
+    Err(QueryError::Syntax(start, "unterminated string".to_string()))
+}
+
+/// A cursor over the tokens with helpers for the recursive-descent parsers.
+pub(super) struct Parser {
+    tokens: Vec<Spanned>,
+    pos: usize,
+    end: usize,
+}
+
+impl Parser {
+    pub(super) fn new(query: &str) -> Result<Self, QueryError> {
+        Ok(Parser {
+            tokens: tokenize(query)?,
+            pos: 0,
+            end: query.len(),
+        })
+    }
+
+    pub(super) fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(token, _)| token)
+    }
+
+    pub(super) fn peek_at(&self, offset: usize) -> Option<&Token> {
+        self.tokens.get(self.pos + offset).map(|(token, _)| token)
+    }
+
+    pub(super) fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).map(|(token, _)| token.clone());
+        self.pos += 1;
+        token
+    }
+
+    /// Consumes the next token if it equals `token`.
+    pub(super) fn eat(&mut self, token: &Token) -> bool {
+        if self.peek() == Some(token) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub(super) fn expect(&mut self, token: &Token, what: &str) -> Result<(), QueryError> {
+        if self.eat(token) {
+            Ok(())
+        } else {
+            Err(self.error(format!("expected {}", what)))
+        }
+    }
+
+    pub(super) fn at_end(&self) -> bool {
+        self.pos >= self.tokens.len()
+    }
+
+    /// A syntax error at the current token.
+    pub(super) fn error(&self, message: String) -> QueryError {
+        let position = self.tokens.get(self.pos).map_or(self.end, |(_, position)| *position);
+        QueryError::Syntax(position, message)
+    }
+}
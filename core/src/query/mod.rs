@@ -0,0 +1,223 @@
+//! Queries over a parsed JSON document.
+//!
+//! Queries starting with `$` are JSONPath (`$.items[?(@.price < 10)].id`); anything
+//! else is read as a practical subset of jq (`.items[] | select(.active) | .id`).
+
+mod jq;
+mod jsonpath;
+mod lexer;
+
+use crate::path::JsonPath;
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Language {
+    JsonPath,
+    Jq,
+}
+
+impl Language {
+    pub fn detect(query: &str) -> Self {
+        if query.trim_start().starts_with('$') {
+            Language::JsonPath
+        } else {
+            Language::Jq
+        }
+    }
+}
+
+/// One query result. JSONPath results know where they came from; jq results
+/// are computed values and have no path.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Match {
+    pub path: Option<JsonPath>,
+    pub value: Value,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum QueryError {
+    /// The query could not be parsed; the byte offset points into the query.
+    Syntax(usize, String),
+    /// The query is valid but cannot be applied to this document.
+    Eval(String),
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QueryError::Syntax(position, message) => write!(f, "{} at position {}", message, position + 1),
+            QueryError::Eval(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+pub fn evaluate(query: &str, document: &Value) -> Result<Vec<Match>, QueryError> {
+    match Language::detect(query) {
+        Language::JsonPath => jsonpath::evaluate(query, document),
+        Language::Jq => jq::evaluate(query, document),
+    }
+}
+
+/// jq's ordering: null < false < true < numbers < strings < arrays < objects.
+/// JSONPath filters use it too, so `1 == 1.0` holds in both.
+fn compare(a: &Value, b: &Value) -> Ordering {
+    fn rank(value: &Value) -> u8 {
+        match value {
+            Value::Null => 0,
+            Value::Bool(false) => 1,
+            Value::Bool(true) => 2,
+            Value::Number(_) => 3,
+            Value::String(_) => 4,
+            Value::Array(_) => 5,
+            Value::Object(_) => 6,
+        }
+    }
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => {
+            let (a, b) = (a.as_f64().unwrap_or(f64::NAN), b.as_f64().unwrap_or(f64::NAN));
+            a.partial_cmp(&b).unwrap_or(Ordering::Equal)
+        }
+        (Value::String(a), Value::String(b)) => a.cmp(b),
+        (Value::Array(a), Value::Array(b)) => a
+            .iter()
+            .zip(b)
+            .map(|(a, b)| compare(a, b))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or_else(|| a.len().cmp(&b.len())),
+        (Value::Object(a), Value::Object(b)) => {
+            let mut a_keys: Vec<_> = a.keys().collect();
+            let mut b_keys: Vec<_> = b.keys().collect();
+            a_keys.sort();
+            b_keys.sort();
+            a_keys.cmp(&b_keys).then_with(|| {
+                a_keys
+                    .iter()
+                    .map(|key| compare(&a[key.as_str()], &b[key.as_str()]))
+                    .find(|ordering| ordering.is_ne())
+                    .unwrap_or(Ordering::Equal)
+            })
+        }
+        _ => rank(a).cmp(&rank(b)),
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Parses a number literal into a `Value`, keeping its text.
+fn number(text: &str) -> Option<Value> {
+    serde_json::from_str::<serde_json::Number>(text).ok().map(Value::Number)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn values(query: &str, document: &Value) -> Vec<Value> {
+        evaluate(query, document).unwrap().into_iter().map(|m| m.value).collect()
+    }
+
+    fn store() -> Value {
+        json!({
+            "store": {
+                "book": [
+                    {"title": "Sayings", "price": 8.95, "tags": ["a"]},
+                    {"title": "Sword", "price": 12.99, "isbn": "0-553"},
+                    {"title": "Moby", "price": 8.99, "isbn": "0-395"}
+                ],
+                "bicycle": {"color": "red", "price": 19.95}
+            }
+        })
+    }
+
+    #[test]
+    fn jsonpath_selects_with_paths() {
+        let document = store();
+        let matches = evaluate("$.store.book[*].title", &document).unwrap();
+        assert_eq!(matches[1].path.as_ref().unwrap().to_string(), "$.store.book[1].title");
+        assert_eq!(
+            matches.into_iter().map(|m| m.value).collect::<Vec<_>>(),
+            [json!("Sayings"), json!("Sword"), json!("Moby")]
+        );
+        assert_eq!(values("$['store']['bicycle'].color", &document), [json!("red")]);
+        assert_eq!(values("$.store.book[-1].title", &document), [json!("Moby")]);
+        assert_eq!(values("$.store.book[0,2].price", &document), [json!(8.95), json!(8.99)]);
+        assert_eq!(values("$.store.book[1:].title", &document), [json!("Sword"), json!("Moby")]);
+        assert_eq!(values("$.store.book[::-2].title", &document), [json!("Moby"), json!("Sayings")]);
+    }
+
+    #[test]
+    fn jsonpath_descends_and_filters() {
+        let document = store();
+        assert_eq!(values("$..price", &document), [json!(8.95), json!(12.99), json!(8.99), json!(19.95)]);
+        assert_eq!(values("$..book[?(@.isbn)].title", &document), [json!("Sword"), json!("Moby")]);
+        assert_eq!(
+            values("$.store.book[?(@.price < 10 && @.title != 'Moby')].title", &document),
+            [json!("Sayings")]
+        );
+        assert_eq!(values("$.store.book[?@.price > $.store.bicycle.price]", &document), Vec::<Value>::new());
+        assert_eq!(values("$.store.*.color", &document), [json!("red")]);
+    }
+
+    #[test]
+    fn jq_navigates_and_iterates() {
+        let document = store();
+        assert_eq!(values(".store.bicycle.color", &document), [json!("red")]);
+        assert_eq!(values(".store.book[].title", &document), [json!("Sayings"), json!("Sword"), json!("Moby")]);
+        assert_eq!(values("[.store.book[] | .price]", &document), [json!([8.95, 12.99, 8.99])]);
+        assert_eq!(values(".store.book[1:2] | length", &document), [json!(1)]);
+        assert_eq!(values(".store.missing", &document), [Value::Null]);
+        assert_eq!(values(".", &json!(1)), [json!(1)]);
+        assert_eq!(values(".[\"a b\"], .c", &json!({"a b": 1, "c": 2})), [json!(1), json!(2)]);
+    }
+
+    #[test]
+    fn jq_supports_builtins() {
+        let document = store();
+        assert_eq!(
+            values(".store.book | map(select(.price < 10) | .title)", &document),
+            [json!(["Sayings", "Moby"])]
+        );
+        assert_eq!(values(".store.bicycle | keys", &document), [json!(["color", "price"])]);
+        assert_eq!(values(".store.book | length", &document), [json!(3)]);
+        assert_eq!(values(".store.book[0].title | length", &document), [json!(7)]);
+        assert_eq!(
+            values(".store.book[] | select(.isbn and (.price > 10 | not) | not) | .title", &document),
+            [json!("Sayings"), json!("Sword")]
+        );
+        assert_eq!(values(".store.book[0] | .tags[0], (.price | type)", &document), [json!("a"), json!("number")]);
+    }
+
+    #[test]
+    fn reports_errors() {
+        assert!(matches!(evaluate(".a |", &json!({})), Err(QueryError::Syntax(..))));
+        assert!(matches!(evaluate("$.a[", &json!({})), Err(QueryError::Syntax(..))));
+        assert_eq!(
+            evaluate(".[]", &json!(5)).unwrap_err(),
+            QueryError::Eval("cannot iterate over number".to_string())
+        );
+        assert_eq!(values(".[]?", &json!(5)), Vec::<Value>::new());
+        assert!(matches!(evaluate("frobnicate", &json!({})), Err(QueryError::Syntax(..))));
+    }
+
+    #[test]
+    fn compares_like_jq() {
+        assert_eq!(compare(&json!(1), &json!(1.0)), Ordering::Equal);
+        assert_eq!(compare(&json!(null), &json!(false)), Ordering::Less);
+        assert_eq!(compare(&json!("b"), &json!([])), Ordering::Less);
+        assert_eq!(compare(&json!([1, 2]), &json!([1, 3])), Ordering::Less);
+    }
+}
@@ -0,0 +1,653 @@
+//! Turning almost-JSON into JSON, noting every change made.
+//!
+//! Handles what debuggers and log viewers tend to produce: Python `repr` output
+//! (`True`, `None`, single quotes, tuples), JavaScript object literals
+//! (unquoted keys, `undefined`, comments), `NaN` and `Infinity`, raw control
+//! characters in strings, missing or extra commas and documents cut off part
+//! way through. The repaired text keeps the input's layout.
+
+use crate::json::{self, location_at, ErrorLocation};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// How deeply objects and arrays may nest: the most the JSON parser accepts.
+/// Deeper input is rejected rather than overflowing the stack.
+const MAX_DEPTH: usize = 127;
+
+/// One kind of change made by [`repair`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum RepairKind {
+    /// Python `True`, `False` or `None`.
+    PythonLiteral,
+    /// A Python tuple `(1, 2)`, written as an array.
+    PythonTuple,
+    /// A string in single quotes.
+    SingleQuotes,
+    /// An object key without quotes.
+    UnquotedKey,
+    /// JavaScript `undefined`, written as `null`.
+    Undefined,
+    /// `NaN`, `Infinity` or `-Infinity`, which JSON cannot express; written as `null`.
+    NonFinite,
+    /// A raw tab, line break or other control character inside a string.
+    ControlCharacter,
+    /// A backslash escape JSON lacks, such as `\'` or Python's `\x41`.
+    InvalidEscape,
+    /// A `//` or `/* */` comment, removed.
+    Comment,
+    MissingComma,
+    MissingColon,
+    /// A comma before a closing bracket or after another comma, removed.
+    ExtraComma,
+    /// A number cut off after its `.`, `e` or sign.
+    IncompleteNumber,
+    /// A string cut off before its closing quote.
+    UnclosedString,
+    /// A key or `:` with nothing after it at the end of the input, given `null`.
+    MissingValue,
+    /// An array or object never closed, or closed with the wrong bracket.
+    UnclosedBracket,
+}
+
+impl RepairKind {
+    pub fn as_param(&self) -> &'static str {
+        match self {
+            RepairKind::PythonLiteral => "python_literal",
+            RepairKind::PythonTuple => "python_tuple",
+            RepairKind::SingleQuotes => "single_quotes",
+            RepairKind::UnquotedKey => "unquoted_key",
+            RepairKind::Undefined => "undefined",
+            RepairKind::NonFinite => "non_finite",
+            RepairKind::ControlCharacter => "control_character",
+            RepairKind::InvalidEscape => "invalid_escape",
+            RepairKind::Comment => "comment",
+            RepairKind::MissingComma => "missing_comma",
+            RepairKind::MissingColon => "missing_colon",
+            RepairKind::ExtraComma => "extra_comma",
+            RepairKind::IncompleteNumber => "incomplete_number",
+            RepairKind::UnclosedString => "unclosed_string",
+            RepairKind::MissingValue => "missing_value",
+            RepairKind::UnclosedBracket => "unclosed_bracket",
+        }
+    }
+
+    /// What the repair did, for command-line output.
+    pub fn description(&self) -> &'static str {
+        match self {
+            RepairKind::PythonLiteral => "replaced Python True, False or None",
+            RepairKind::PythonTuple => "wrote a Python tuple as an array",
+            RepairKind::SingleQuotes => "replaced single quotes with double quotes",
+            RepairKind::UnquotedKey => "quoted a key",
+            RepairKind::Undefined => "replaced undefined with null",
+            RepairKind::NonFinite => "replaced NaN or Infinity with null",
+            RepairKind::ControlCharacter => "escaped control characters in a string",
+            RepairKind::InvalidEscape => "rewrote an escape sequence JSON does not have",
+            RepairKind::Comment => "removed a comment",
+            RepairKind::MissingComma => "inserted a missing comma",
+            RepairKind::MissingColon => "inserted a missing colon",
+            RepairKind::ExtraComma => "removed an extra comma",
+            RepairKind::IncompleteNumber => "completed a cut-off number",
+            RepairKind::UnclosedString => "closed an unterminated string",
+            RepairKind::MissingValue => "filled in a missing value with null",
+            RepairKind::UnclosedBracket => "closed an unclosed bracket",
+        }
+    }
+}
+
+/// A change and where in the input it was made.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Repair {
+    pub kind: RepairKind,
+    pub location: ErrorLocation,
+}
+
+/// Repairs of one kind: how many there were and where the first one is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RepairSummary {
+    pub kind: RepairKind,
+    pub count: usize,
+    pub first: ErrorLocation,
+}
+
+/// Groups repairs by kind, in the order each kind first occurs.
+pub fn summarize(repairs: &[Repair]) -> Vec<RepairSummary> {
+    let mut summaries: Vec<RepairSummary> = Vec::new();
+    for repair in repairs {
+        match summaries.iter_mut().find(|summary| summary.kind == repair.kind) {
+            Some(summary) => summary.count += 1,
+            None => summaries.push(RepairSummary {
+                kind: repair.kind,
+                count: 1,
+                first: repair.location,
+            }),
+        }
+    }
+    summaries
+}
+
+/// The input could not be repaired.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RepairError {
+    pub message: String,
+    pub location: Option<ErrorLocation>,
+}
+
+impl fmt::Display for RepairError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.location {
+            Some(location) => write!(f, "{} at line {} column {}", self.message, location.line, location.column),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+impl std::error::Error for RepairError {}
+
+/// The repaired text, valid JSON, and the changes that made it so.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Repaired {
+    pub text: String,
+    pub repairs: Vec<Repair>,
+}
+
+/// Rewrites `input` as valid JSON. Input that is already valid comes back
+/// unchanged with no repairs.
+pub fn repair(input: &str) -> Result<Repaired, RepairError> {
+    let mut repairer = Repairer {
+        input,
+        pos: 0,
+        out: String::with_capacity(input.len()),
+        notes: Vec::new(),
+        depth: 0,
+    };
+    repairer.value()?;
+    repairer.blank();
+    if let Some(c) = repairer.peek() {
+        return Err(repairer.error(format!("unexpected '{}' after the document", c)));
+    }
+    // Whatever slipped through, e.g. a malformed number, is reported here
+    json::parse(&repairer.out).map_err(|e| RepairError {
+        message: format!("could not repair the input: {}", e),
+        location: None,
+    })?;
+    let offsets: Vec<usize> = repairer.notes.iter().map(|&(offset, _)| offset).collect();
+    let repairs = repairer
+        .notes
+        .iter()
+        .zip(locate_all(input, &offsets))
+        .map(|(&(_, kind), location)| Repair { kind, location })
+        .collect();
+    Ok(Repaired {
+        text: repairer.out,
+        repairs,
+    })
+}
+
+/// Locations of many byte offsets, found in one pass over `input` rather than
+/// rescanning from the start for each.
+fn locate_all(input: &str, offsets: &[usize]) -> Vec<ErrorLocation> {
+    let mut order: Vec<usize> = (0..offsets.len()).collect();
+    order.sort_by_key(|&index| offsets[index]);
+    let mut locations = vec![ErrorLocation { line: 1, column: 1 }; offsets.len()];
+    let (mut pos, mut location) = (0, ErrorLocation { line: 1, column: 1 });
+    for index in order {
+        let mut offset = offsets[index].min(input.len());
+        while !input.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        for c in input[pos..offset].chars() {
+            if c == '\n' {
+                location = ErrorLocation { line: location.line + 1, column: 1 };
+            } else {
+                location.column += 1;
+            }
+        }
+        pos = offset;
+        locations[index] = location;
+    }
+    locations
+}
+
+struct Repairer<'a> {
+    input: &'a str,
+    /// Byte offset of the next character to read.
+    pos: usize,
+    out: String,
+    /// Byte offset and kind of each repair; turned into locations once at the end.
+    notes: Vec<(usize, RepairKind)>,
+    /// Objects and arrays currently open.
+    depth: usize,
+}
+
+impl Repairer<'_> {
+    fn peek(&self) -> Option<char> {
+        self.input[self.pos..].chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn rest(&self) -> &str {
+        &self.input[self.pos..]
+    }
+
+    fn note(&mut self, offset: usize, kind: RepairKind) {
+        self.notes.push((offset, kind));
+    }
+
+    fn error(&self, message: String) -> RepairError {
+        RepairError {
+            message,
+            location: Some(location_at(self.input, self.pos)),
+        }
+    }
+
+    /// Copies whitespace and drops comments.
+    fn blank(&mut self) {
+        loop {
+            let start = self.pos;
+            match self.peek() {
+                Some(c @ (' ' | '\t' | '\n' | '\r')) => {
+                    self.out.push(c);
+                    self.bump();
+                }
+                // Non-breaking and other Unicode spaces, common in pasted text
+                Some(c) if c.is_whitespace() || c == '\u{feff}' => {
+                    self.out.push(' ');
+                    self.bump();
+                }
+                Some('/') if self.rest().starts_with("//") => {
+                    self.note(start, RepairKind::Comment);
+                    let end = self.rest().find('\n').unwrap_or(self.rest().len());
+                    self.pos += end;
+                }
+                Some('/') if self.rest().starts_with("/*") => {
+                    self.note(start, RepairKind::Comment);
+                    let end = self.rest()[2..].find("*/").map_or(self.rest().len(), |end| end + 4);
+                    self.pos += end;
+                }
+                _ => return,
+            }
+        }
+    }
+
+    fn value(&mut self) -> Result<(), RepairError> {
+        self.blank();
+        match self.peek() {
+            None => {
+                self.note(self.pos, RepairKind::MissingValue);
+                self.out.push_str("null");
+                Ok(())
+            }
+            Some('{') => self.nested('}'),
+            Some('[') => self.nested(']'),
+            Some('(') => {
+                self.note(self.pos, RepairKind::PythonTuple);
+                self.nested(')')
+            }
+            Some('"' | '\'') => {
+                self.string();
+                Ok(())
+            }
+            Some(c) if c == '-' || c.is_ascii_digit() => {
+                self.number();
+                Ok(())
+            }
+            Some(c) if is_word_char(c) => self.word(),
+            Some(c) => Err(self.error(format!("unexpected '{}'", c))),
+        }
+    }
+
+    /// [`Self::members`] one level deeper, failing past [`MAX_DEPTH`].
+    fn nested(&mut self, close: char) -> Result<(), RepairError> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error(format!("nested more than {} levels deep", MAX_DEPTH)));
+        }
+        self.depth += 1;
+        let result = self.members(close);
+        self.depth -= 1;
+        result
+    }
+
+    /// An object, array or tuple up to its closing bracket, fixing commas on the way.
+    fn members(&mut self, close: char) -> Result<(), RepairError> {
+        let is_object = close == '}';
+        let (open, close_out) = if is_object { ('{', '}') } else { ('[', ']') };
+        self.bump();
+        self.out.push(open);
+        let mut expect_member = true;
+        // The comma after the last member: its position in the output and the input
+        let mut comma: Option<(usize, usize)> = None;
+        loop {
+            self.blank();
+            let start = self.pos;
+            match self.peek() {
+                None => {
+                    // Cut off after a comma: the missing member is dropped with it
+                    if let Some((out, _)) = comma {
+                        self.out.remove(out);
+                    }
+                    self.note(start, RepairKind::UnclosedBracket);
+                    self.out.push(close_out);
+                    return Ok(());
+                }
+                Some(c @ (']' | '}' | ')')) => {
+                    if let Some((out, input)) = comma {
+                        self.out.remove(out);
+                        self.note(input, RepairKind::ExtraComma);
+                    }
+                    // The closing bracket of an enclosing value closes this one too
+                    if c == close {
+                        self.bump();
+                    } else {
+                        self.note(start, RepairKind::UnclosedBracket);
+                    }
+                    self.out.push(close_out);
+                    return Ok(());
+                }
+                Some(',') => {
+                    self.bump();
+                    if expect_member {
+                        self.note(start, RepairKind::ExtraComma);
+                    } else {
+                        comma = Some((self.out.len(), start));
+                        self.out.push(',');
+                        expect_member = true;
+                    }
+                }
+                Some(_) => {
+                    if !expect_member {
+                        self.note(start, RepairKind::MissingComma);
+                        let end = self.out.trim_end().len();
+                        self.out.insert(end, ',');
+                    }
+                    if is_object {
+                        self.property()?;
+                    } else {
+                        self.value()?;
+                    }
+                    expect_member = false;
+                    comma = None;
+                }
+            }
+        }
+    }
+
+    fn property(&mut self) -> Result<(), RepairError> {
+        let start = self.pos;
+        match self.peek() {
+            Some('"' | '\'') => self.string(),
+            Some(c) if is_word_char(c) => {
+                while self.peek().is_some_and(is_word_char) {
+                    self.bump();
+                }
+                self.note(start, RepairKind::UnquotedKey);
+                let key = &self.input[start..self.pos];
+                self.out.push('"');
+                self.out.push_str(key);
+                self.out.push('"');
+            }
+            Some(c) => return Err(self.error(format!("expected a key, found '{}'", c))),
+            None => unreachable!("members checks for the end of the input"),
+        }
+        self.blank();
+        match self.peek() {
+            Some(':') => {
+                self.bump();
+                self.out.push(':');
+            }
+            None => {
+                self.note(self.pos, RepairKind::MissingValue);
+                self.out.push_str(":null");
+                return Ok(());
+            }
+            Some(_) => {
+                self.note(self.pos, RepairKind::MissingColon);
+                let end = self.out.trim_end().len();
+                self.out.insert(end, ':');
+            }
+        }
+        self.value()
+    }
+
+    fn string(&mut self) {
+        let start = self.pos;
+        let quote = self.bump().expect("called at a quote");
+        if quote == '\'' {
+            self.note(start, RepairKind::SingleQuotes);
+        }
+        self.out.push('"');
+        let mut control = false;
+        loop {
+            let at = self.pos;
+            match self.bump() {
+                None => {
+                    self.note(at, RepairKind::UnclosedString);
+                    self.out.push('"');
+                    return;
+                }
+                Some(c) if c == quote => {
+                    self.out.push('"');
+                    return;
+                }
+                Some('\\') => self.escape(at),
+                Some('"') => self.out.push_str("\\\""),
+                Some(c) if (c as u32) < 0x20 => {
+                    // Once per string: a multi-line value is one problem, not one per line
+                    if !control {
+                        self.note(at, RepairKind::ControlCharacter);
+                        control = true;
+                    }
+                    match c {
+                        '\n' => self.out.push_str("\\n"),
+                        '\r' => self.out.push_str("\\r"),
+                        '\t' => self.out.push_str("\\t"),
+                        c => self.out.push_str(&format!("\\u{:04x}", c as u32)),
+                    }
+                }
+                Some(c) => self.out.push(c),
+            }
+        }
+    }
+
+    /// The escape after a backslash at `at`.
+    fn escape(&mut self, at: usize) {
+        let hex = |text: &str, len: usize| text.as_bytes().get(..len).is_some_and(|bytes| bytes.iter().all(u8::is_ascii_hexdigit));
+        match self.peek() {
+            Some(c @ ('"' | '\\' | '/' | 'b' | 'f' | 'n' | 'r' | 't')) => {
+                self.bump();
+                self.out.push('\\');
+                self.out.push(c);
+            }
+            Some('u') if hex(&self.rest()[1..], 4) => {
+                self.bump();
+                self.out.push_str("\\u");
+            }
+            Some('\'') => {
+                self.bump();
+                self.note(at, RepairKind::InvalidEscape);
+                self.out.push('\'');
+            }
+            Some('x') if hex(&self.rest()[1..], 2) => {
+                self.bump();
+                self.note(at, RepairKind::InvalidEscape);
+                self.out.push_str("\\u00");
+                let input = self.input;
+                self.out.push_str(&input[self.pos..self.pos + 2]);
+                self.pos += 2;
+            }
+            // The string was cut off right after the backslash
+            None => {}
+            // Anything else keeps its backslash as a literal character
+            Some(_) => {
+                self.note(at, RepairKind::InvalidEscape);
+                self.out.push_str("\\\\");
+            }
+        }
+    }
+
+    fn number(&mut self) {
+        let start = self.pos;
+        if self.rest().starts_with("-Infinity") {
+            self.pos += "-Infinity".len();
+            self.note(start, RepairKind::NonFinite);
+            self.out.push_str("null");
+            return;
+        }
+        while self.peek().is_some_and(|c| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E')) {
+            self.bump();
+        }
+        let text = &self.input[start..self.pos];
+        self.out.push_str(text);
+        if text.ends_with(['-', '+', '.', 'e', 'E']) {
+            self.note(start, RepairKind::IncompleteNumber);
+            self.out.push('0');
+        }
+    }
+
+    fn word(&mut self) -> Result<(), RepairError> {
+        let start = self.pos;
+        while self.peek().is_some_and(is_word_char) {
+            self.bump();
+        }
+        let (replacement, kind) = match &self.input[start..self.pos] {
+            word @ ("true" | "false" | "null") => (word, None),
+            "True" => ("true", Some(RepairKind::PythonLiteral)),
+            "False" => ("false", Some(RepairKind::PythonLiteral)),
+            "None" => ("null", Some(RepairKind::PythonLiteral)),
+            "undefined" => ("null", Some(RepairKind::Undefined)),
+            "NaN" | "Infinity" => ("null", Some(RepairKind::NonFinite)),
+            word => {
+                self.pos = start;
+                return Err(self.error(format!("unexpected '{}'", word)));
+            }
+        };
+        if let Some(kind) = kind {
+            self.note(start, kind);
+        }
+        self.out.push_str(replacement);
+        Ok(())
+    }
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(input: &str) -> (String, Vec<RepairKind>) {
+        let repaired = repair(input).unwrap();
+        (repaired.text, repaired.repairs.iter().map(|repair| repair.kind).collect())
+    }
+
+    #[test]
+    fn leaves_valid_json_alone() {
+        let input = "{\n  \"a\": [1, -2.5e3, true, null],\n  \"b\": \"\\u00e9\\n\"\n}";
+        assert_eq!(kinds(input), (input.to_string(), vec![]));
+    }
+
+    #[test]
+    fn repairs_python_and_javascript() {
+        assert_eq!(
+            kinds("{'a': True, 'b': (1, None), 'c': 'it\\'s \"x\"'}"),
+            (
+                r#"{"a": true, "b": [1, null], "c": "it's \"x\""}"#.to_string(),
+                vec![
+                    RepairKind::SingleQuotes,
+                    RepairKind::PythonLiteral,
+                    RepairKind::SingleQuotes,
+                    RepairKind::PythonTuple,
+                    RepairKind::PythonLiteral,
+                    RepairKind::SingleQuotes,
+                    RepairKind::SingleQuotes,
+                    RepairKind::InvalidEscape,
+                ]
+            )
+        );
+        assert_eq!(
+            kinds("{a: undefined, // note\n $b: [NaN, -Infinity,],}"),
+            (
+                "{\"a\": null, \n \"$b\": [null, null]}".to_string(),
+                vec![
+                    RepairKind::UnquotedKey,
+                    RepairKind::Undefined,
+                    RepairKind::Comment,
+                    RepairKind::UnquotedKey,
+                    RepairKind::NonFinite,
+                    RepairKind::NonFinite,
+                    RepairKind::ExtraComma,
+                    RepairKind::ExtraComma,
+                ]
+            )
+        );
+    }
+
+    #[test]
+    fn repairs_strings_and_commas() {
+        assert_eq!(
+            kinds("[\"line 1\nline 2\t\\x41\" \"b\"\n{\"c\" 1}]"),
+            (
+                "[\"line 1\\nline 2\\t\\u0041\", \"b\",\n{\"c\": 1}]".to_string(),
+                vec![
+                    RepairKind::ControlCharacter,
+                    RepairKind::InvalidEscape,
+                    RepairKind::MissingComma,
+                    RepairKind::MissingComma,
+                    RepairKind::MissingColon,
+                ]
+            )
+        );
+        assert_eq!(kinds("[1,,2]").0, "[1,2]");
+    }
+
+    #[test]
+    fn closes_truncated_documents() {
+        assert_eq!(kinds(r#"{"a": [1, 2.5e"#).0, r#"{"a": [1, 2.5e0]}"#);
+        assert_eq!(kinds(r#"{"a": {"b": "tex"#).0, r#"{"a": {"b": "tex"}}"#);
+        assert_eq!(kinds(r#"{"a": 1, "b"#).0, r#"{"a": 1, "b":null}"#);
+        assert_eq!(kinds(r#"[{"a": 1}, "#).0, r#"[{"a": 1} ]"#);
+        assert_eq!(kinds(r#"{"a": [1}"#), (r#"{"a": [1]}"#.to_string(), vec![RepairKind::UnclosedBracket]));
+
+        let repaired = repair("{\n  \"a\": [1,\n  2").unwrap();
+        assert_eq!(
+            summarize(&repaired.repairs),
+            [RepairSummary {
+                kind: RepairKind::UnclosedBracket,
+                count: 2,
+                first: ErrorLocation { line: 3, column: 4 },
+            }]
+        );
+    }
+
+    #[test]
+    fn reports_what_it_cannot_repair() {
+        let error = repair("{\"a\": new Date()}").unwrap_err();
+        assert_eq!(error.to_string(), "unexpected 'new' at line 1 column 7");
+        let error = repair(&"[".repeat(100_000)).unwrap_err();
+        assert_eq!(error.to_string(), "nested more than 127 levels deep at line 1 column 128");
+        assert!(repair(&"[".repeat(MAX_DEPTH)).is_ok());
+        assert!(repair("[1] 2").is_err());
+        assert!(repair("[1.2.3]").is_err());
+    }
+
+    #[test]
+    fn locates_many_repairs_in_linear_time() {
+        let input = format!("[{}]", "'a'\n".repeat(100_000));
+        let start = std::time::Instant::now();
+        let repaired = repair(&input).unwrap();
+        assert!(start.elapsed() < std::time::Duration::from_secs(5));
+        // 每个字符串两处修复：单引号和缺少的逗号
+        assert_eq!(repaired.repairs.len(), 2 * 100_000 - 1);
+        assert_eq!(repaired.repairs[2].location, ErrorLocation { line: 2, column: 1 });
+        assert_eq!(repaired.repairs.last().unwrap().location, ErrorLocation { line: 100_000, column: 1 });
+        for (offset, location) in [0, 3, 5, 9].into_iter().zip(locate_all("ab\néx\n", &[0, 3, 5, 9])) {
+            assert_eq!(location, location_at("ab\néx\n", offset));
+        }
+    }
+}
@@ -0,0 +1,1042 @@
+//! JSON Schema validation for drafts 7, 2019-09 and 2020-12.
+//!
+//! Every failing keyword is reported with the instance path it applies to and the
+//! keyword's location in the schema, so callers can point at both. References are
+//! resolved within the schema (`#/$defs/x`, `#anchor`, or a nested `$id`); remote
+//! schemas are not fetched. `format` is always checked for the formats listed in
+//! [`check_format`], which is what most people expect from a validator even though
+//! 2019-09 and later only require it as an annotation.
+
+use crate::path::{JsonPath, Segment};
+use regex::Regex;
+use serde_json::{Map, Value};
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Draft {
+    Draft7,
+    Draft201909,
+    Draft202012,
+}
+
+impl Draft {
+    pub const ALL: [Draft; 3] = [Draft::Draft7, Draft::Draft201909, Draft::Draft202012];
+
+    pub fn as_param(&self) -> &'static str {
+        match self {
+            Draft::Draft7 => "draft7",
+            Draft::Draft201909 => "2019-09",
+            Draft::Draft202012 => "2020-12",
+        }
+    }
+
+    pub fn from_param(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|draft| draft.as_param() == value)
+    }
+
+    /// The draft named by the schema's `$schema`, if it names one. Drafts 4 and 6
+    /// are close enough to 7 to be validated as such.
+    pub fn detect(schema: &Value) -> Option<Self> {
+        let uri = schema.get("$schema")?.as_str()?;
+        if uri.contains("2020-12") {
+            Some(Draft::Draft202012)
+        } else if uri.contains("2019-09") {
+            Some(Draft::Draft201909)
+        } else if ["draft-07", "draft-06", "draft-04"].iter().any(|name| uri.contains(name)) {
+            Some(Draft::Draft7)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ValidationError {
+    /// Where in the document the failing value is.
+    pub instance_path: JsonPath,
+    /// Where in the schema the failing keyword is, e.g. `/properties/age/minimum`.
+    pub schema_path: JsonPath,
+    pub message: String,
+}
+
+impl ValidationError {
+    /// The failing keyword, e.g. `minimum`.
+    pub fn keyword(&self) -> Option<&str> {
+        match self.schema_path.segments().last()? {
+            Segment::Key(key) => Some(key),
+            Segment::Index(_) => None,
+        }
+    }
+}
+
+/// The schema itself cannot be used.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SchemaError {
+    /// A keyword has a value the validator cannot use, such as an invalid regex.
+    Invalid { path: JsonPath, message: String },
+    /// A `$ref` points outside the schema or at nothing.
+    UnresolvedRef(String),
+    /// Following `$ref`s leads back to the same schema without consuming input.
+    RefLoop(String),
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchemaError::Invalid { path, message } => write!(f, "invalid schema at #{}: {}", path.to_pointer(), message),
+            SchemaError::UnresolvedRef(reference) => write!(f, "cannot resolve $ref \"{}\"", reference),
+            SchemaError::RefLoop(reference) => write!(f, "$ref \"{}\" refers back to itself", reference),
+        }
+    }
+}
+
+impl std::error::Error for SchemaError {}
+
+/// Validates `instance` against `schema`. The draft defaults to the one named by
+/// `$schema`, or 2020-12 when there is none.
+pub fn validate(schema: &Value, instance: &Value, draft: Option<Draft>) -> Result<Vec<ValidationError>, SchemaError> {
+    let validator = Validator {
+        root: schema,
+        draft: draft.or_else(|| Draft::detect(schema)).unwrap_or(Draft::Draft202012),
+        regexes: RefCell::new(HashMap::new()),
+        refs: RefCell::new(Vec::new()),
+    };
+    let mut errors = Vec::new();
+    validator.check(schema, &JsonPath::root(), instance, &JsonPath::root(), &mut errors)?;
+    Ok(errors)
+}
+
+struct Validator<'a> {
+    root: &'a Value,
+    draft: Draft,
+    regexes: RefCell<HashMap<String, Regex>>,
+    /// `$ref` targets being expanded for each instance value, to detect loops.
+    refs: RefCell<Vec<(*const Value, *const Value)>>,
+}
+
+/// Which properties and items of an instance a schema looked at, for
+/// `unevaluatedProperties` and `unevaluatedItems`.
+#[derive(Default)]
+struct Evaluated {
+    properties: HashSet<String>,
+    all_properties: bool,
+    items: usize,
+    all_items: bool,
+}
+
+impl Evaluated {
+    fn merge(&mut self, other: Evaluated) {
+        self.properties.extend(other.properties);
+        self.all_properties |= other.all_properties;
+        self.items = self.items.max(other.items);
+        self.all_items |= other.all_items;
+    }
+}
+
+impl<'a> Validator<'a> {
+    fn check(
+        &self,
+        schema: &'a Value,
+        schema_path: &JsonPath,
+        instance: &Value,
+        path: &JsonPath,
+        out: &mut Vec<ValidationError>,
+    ) -> Result<(), SchemaError> {
+        let s = match schema {
+            Value::Bool(true) => return Ok(()),
+            Value::Bool(false) => {
+                out.push(ValidationError {
+                    instance_path: path.clone(),
+                    schema_path: schema_path.clone(),
+                    message: "no value is allowed here".to_string(),
+                });
+                return Ok(());
+            }
+            Value::Object(s) => s,
+            _ => return Err(invalid(schema_path, "a schema must be an object or a boolean")),
+        };
+        for keyword in ["$ref", "$recursiveRef", "$dynamicRef"] {
+            if let Some(reference) = s.get(keyword) {
+                let reference = reference.as_str().ok_or_else(|| invalid(&schema_path.key(keyword), "must be a string"))?;
+                self.follow(reference, &schema_path.key(keyword), instance, path, out)?;
+                // Up to draft 7, keywords next to `$ref` are ignored
+                if self.draft == Draft::Draft7 && keyword == "$ref" {
+                    return Ok(());
+                }
+            }
+        }
+        let mut fail_here = |keyword: &str, message: String| {
+            out.push(ValidationError {
+                instance_path: path.clone(),
+                schema_path: schema_path.key(keyword),
+                message,
+            });
+        };
+
+        if let Some(expected) = s.get("type") {
+            let types: Vec<&str> = match expected {
+                Value::String(name) => vec![name.as_str()],
+                Value::Array(names) => names.iter().filter_map(Value::as_str).collect(),
+                _ => return Err(invalid(&schema_path.key("type"), "must be a string or an array of strings")),
+            };
+            if !types.iter().any(|name| has_type(instance, name)) {
+                fail_here("type", format!("expected {}, found {}", types.join(" or "), type_name(instance)));
+            }
+        }
+        if let Some(Value::Array(options)) = s.get("enum") {
+            if !options.iter().any(|option| equal(option, instance)) {
+                let options: Vec<String> = options.iter().map(Value::to_string).collect();
+                fail_here("enum", format!("must be one of {}", options.join(", ")));
+            }
+        }
+        if let Some(expected) = s.get("const") {
+            if !equal(expected, instance) {
+                fail_here("const", format!("must equal {}", expected));
+            }
+        }
+
+        if let Value::Number(n) = instance {
+            let value = n.as_f64().unwrap_or(f64::NAN);
+            let bound = |keyword: &str| -> Result<Option<f64>, SchemaError> {
+                match s.get(keyword) {
+                    None => Ok(None),
+                    Some(Value::Number(bound)) => Ok(bound.as_f64()),
+                    // Draft 4 style, handled with `minimum` and `maximum`
+                    Some(Value::Bool(_)) => Ok(None),
+                    Some(_) => Err(invalid(&schema_path.key(keyword), "must be a number")),
+                }
+            };
+            let mut exclusive_minimum = bound("exclusiveMinimum")?;
+            let mut exclusive_maximum = bound("exclusiveMaximum")?;
+            if let Some(minimum) = bound("minimum")? {
+                if s.get("exclusiveMinimum") == Some(&Value::Bool(true)) {
+                    exclusive_minimum = Some(minimum);
+                } else if value < minimum {
+                    fail_here("minimum", format!("must be at least {}", s["minimum"]));
+                }
+            }
+            if let Some(maximum) = bound("maximum")? {
+                if s.get("exclusiveMaximum") == Some(&Value::Bool(true)) {
+                    exclusive_maximum = Some(maximum);
+                } else if value > maximum {
+                    fail_here("maximum", format!("must be at most {}", s["maximum"]));
+                }
+            }
+            if exclusive_minimum.is_some_and(|minimum| value <= minimum) {
+                fail_here("exclusiveMinimum", format!("must be greater than {}", exclusive_minimum.unwrap_or_default()));
+            }
+            if exclusive_maximum.is_some_and(|maximum| value >= maximum) {
+                fail_here("exclusiveMaximum", format!("must be less than {}", exclusive_maximum.unwrap_or_default()));
+            }
+            if let Some(Value::Number(divisor)) = s.get("multipleOf") {
+                if !is_multiple(n, divisor) {
+                    fail_here("multipleOf", format!("must be a multiple of {}", divisor));
+                }
+            }
+        }
+
+        if let Value::String(text) = instance {
+            let length = text.chars().count();
+            if let Some(minimum) = s.get("minLength").and_then(Value::as_u64) {
+                if (length as u64) < minimum {
+                    fail_here("minLength", format!("must be at least {} characters long", minimum));
+                }
+            }
+            if let Some(maximum) = s.get("maxLength").and_then(Value::as_u64) {
+                if length as u64 > maximum {
+                    fail_here("maxLength", format!("must be at most {} characters long", maximum));
+                }
+            }
+            if let Some(pattern) = s.get("pattern") {
+                let pattern = pattern.as_str().ok_or_else(|| invalid(&schema_path.key("pattern"), "must be a string"))?;
+                if !self.matches(pattern, text, &schema_path.key("pattern"))? {
+                    fail_here("pattern", format!("must match the pattern {}", pattern));
+                }
+            }
+            if let Some(format) = s.get("format").and_then(Value::as_str) {
+                if check_format(format, text) == Some(false) {
+                    fail_here("format", format!("is not a valid {}", format));
+                }
+            }
+        }
+
+        if let Value::Array(items) = instance {
+            if let Some(minimum) = s.get("minItems").and_then(Value::as_u64) {
+                if (items.len() as u64) < minimum {
+                    fail_here("minItems", format!("must have at least {} items", minimum));
+                }
+            }
+            if let Some(maximum) = s.get("maxItems").and_then(Value::as_u64) {
+                if items.len() as u64 > maximum {
+                    fail_here("maxItems", format!("must have at most {} items", maximum));
+                }
+            }
+            if s.get("uniqueItems") == Some(&Value::Bool(true)) {
+                if let Some((first, second)) = duplicate(items) {
+                    fail_here("uniqueItems", format!("items {} and {} are equal", first, second));
+                }
+            }
+        }
+
+        if let Value::Object(map) = instance {
+            if let Some(Value::Array(required)) = s.get("required") {
+                for name in required.iter().filter_map(Value::as_str) {
+                    if !map.contains_key(name) {
+                        fail_here("required", format!("missing required property \"{}\"", name));
+                    }
+                }
+            }
+            if let Some(minimum) = s.get("minProperties").and_then(Value::as_u64) {
+                if (map.len() as u64) < minimum {
+                    fail_here("minProperties", format!("must have at least {} properties", minimum));
+                }
+            }
+            if let Some(maximum) = s.get("maxProperties").and_then(Value::as_u64) {
+                if map.len() as u64 > maximum {
+                    fail_here("maxProperties", format!("must have at most {} properties", maximum));
+                }
+            }
+            for keyword in ["dependentRequired", "dependencies"] {
+                let Some(Value::Object(dependencies)) = s.get(keyword) else {
+                    continue;
+                };
+                for (name, dependency) in dependencies.iter().filter(|(name, _)| map.contains_key(*name)) {
+                    if let Value::Array(required) = dependency {
+                        for other in required.iter().filter_map(Value::as_str) {
+                            if !map.contains_key(other) {
+                                fail_here(keyword, format!("\"{}\" is required when \"{}\" is present", other, name));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        self.check_items(s, schema_path, instance, path, out)?;
+        self.check_properties(s, schema_path, instance, path, out)?;
+        self.check_combinators(s, schema_path, instance, path, out)?;
+        self.check_unevaluated(s, schema_path, instance, path, out)
+    }
+
+    fn check_items(
+        &self,
+        s: &'a Map<String, Value>,
+        schema_path: &JsonPath,
+        instance: &Value,
+        path: &JsonPath,
+        out: &mut Vec<ValidationError>,
+    ) -> Result<(), SchemaError> {
+        let Value::Array(items) = instance else {
+            return Ok(());
+        };
+        // 2020-12 renamed tuple `items` to `prefixItems` and `additionalItems` to `items`;
+        // the old tuple form is still accepted there since it cannot mean anything else
+        let (prefix_keyword, rest_keyword) = match s.get("items") {
+            Some(Value::Array(_)) => ("items", "additionalItems"),
+            _ => ("prefixItems", "items"),
+        };
+        let prefix = match s.get(prefix_keyword) {
+            Some(Value::Array(schemas)) => schemas.as_slice(),
+            _ => &[],
+        };
+        for (index, (schema, item)) in prefix.iter().zip(items).enumerate() {
+            self.check(schema, &schema_path.key(prefix_keyword).index(index), item, &path.index(index), out)?;
+        }
+        if let Some(schema) = s.get(rest_keyword) {
+            for (index, item) in items.iter().enumerate().skip(prefix.len()) {
+                self.check(schema, &schema_path.key(rest_keyword), item, &path.index(index), out)?;
+            }
+        }
+
+        if let Some(schema) = s.get("contains") {
+            let mut matching = 0;
+            for (index, item) in items.iter().enumerate() {
+                if self.is_valid(schema, &schema_path.key("contains"), item, &path.index(index))? {
+                    matching += 1;
+                }
+            }
+            let later = self.draft != Draft::Draft7;
+            let minimum = s.get("minContains").filter(|_| later).and_then(Value::as_u64).unwrap_or(1);
+            let maximum = s.get("maxContains").filter(|_| later).and_then(Value::as_u64);
+            let mut fail = |keyword: &str, message: String| {
+                out.push(ValidationError {
+                    instance_path: path.clone(),
+                    schema_path: schema_path.key(keyword),
+                    message,
+                });
+            };
+            if matching < minimum {
+                let keyword = if s.contains_key("minContains") && later { "minContains" } else { "contains" };
+                fail(keyword, format!("must contain at least {} matching item(s), found {}", minimum, matching));
+            }
+            if maximum.is_some_and(|maximum| matching > maximum) {
+                fail(
+                    "maxContains",
+                    format!("must contain at most {} matching item(s), found {}", maximum.unwrap_or_default(), matching),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn check_properties(
+        &self,
+        s: &'a Map<String, Value>,
+        schema_path: &JsonPath,
+        instance: &Value,
+        path: &JsonPath,
+        out: &mut Vec<ValidationError>,
+    ) -> Result<(), SchemaError> {
+        let Value::Object(map) = instance else {
+            return Ok(());
+        };
+        let properties = s.get("properties").and_then(Value::as_object);
+        let patterns = s.get("patternProperties").and_then(Value::as_object);
+        for (name, value) in map {
+            let mut matched = false;
+            if let Some(schema) = properties.and_then(|properties| properties.get(name)) {
+                matched = true;
+                self.check(schema, &schema_path.key("properties").key(name), value, &path.key(name), out)?;
+            }
+            for (pattern, schema) in patterns.into_iter().flatten() {
+                let pattern_path = schema_path.key("patternProperties").key(pattern);
+                if self.matches(pattern, name, &pattern_path)? {
+                    matched = true;
+                    self.check(schema, &pattern_path, value, &path.key(name), out)?;
+                }
+            }
+            match s.get("additionalProperties") {
+                Some(Value::Bool(false)) if !matched => out.push(ValidationError {
+                    instance_path: path.key(name),
+                    schema_path: schema_path.key("additionalProperties"),
+                    message: format!("property \"{}\" is not allowed", name),
+                }),
+                Some(schema) if !matched => {
+                    self.check(schema, &schema_path.key("additionalProperties"), value, &path.key(name), out)?;
+                }
+                _ => {}
+            }
+            if let Some(schema) = s.get("propertyNames") {
+                let name_value = Value::String(name.clone());
+                self.check(schema, &schema_path.key("propertyNames"), &name_value, &path.key(name), out)?;
+            }
+        }
+        for keyword in ["dependentSchemas", "dependencies"] {
+            let Some(Value::Object(dependencies)) = s.get(keyword) else {
+                continue;
+            };
+            for (name, schema) in dependencies {
+                if map.contains_key(name) && !schema.is_array() {
+                    self.check(schema, &schema_path.key(keyword).key(name), instance, path, out)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn check_combinators(
+        &self,
+        s: &'a Map<String, Value>,
+        schema_path: &JsonPath,
+        instance: &Value,
+        path: &JsonPath,
+        out: &mut Vec<ValidationError>,
+    ) -> Result<(), SchemaError> {
+        let mut fail = |keyword: &str, message: String| {
+            out.push(ValidationError {
+                instance_path: path.clone(),
+                schema_path: schema_path.key(keyword),
+                message,
+            });
+        };
+        let valid_count = |keyword: &str| -> Result<Option<usize>, SchemaError> {
+            let Some(schemas) = s.get(keyword) else {
+                return Ok(None);
+            };
+            let schemas = schemas.as_array().ok_or_else(|| invalid(&schema_path.key(keyword), "must be an array"))?;
+            let mut count = 0;
+            for (index, schema) in schemas.iter().enumerate() {
+                if self.is_valid(schema, &schema_path.key(keyword).index(index), instance, path)? {
+                    count += 1;
+                }
+            }
+            Ok(Some(count))
+        };
+        if valid_count("anyOf")? == Some(0) {
+            fail("anyOf", "must match at least one schema in anyOf".to_string());
+        }
+        match valid_count("oneOf")? {
+            Some(0) => fail("oneOf", "must match exactly one schema in oneOf, but matches none".to_string()),
+            Some(count) if count > 1 => {
+                fail("oneOf", format!("must match exactly one schema in oneOf, but matches {}", count))
+            }
+            _ => {}
+        }
+        if let Some(schema) = s.get("not") {
+            if self.is_valid(schema, &schema_path.key("not"), instance, path)? {
+                fail("not", "must not match the schema in not".to_string());
+            }
+        }
+
+        if let Some(Value::Array(schemas)) = s.get("allOf") {
+            for (index, schema) in schemas.iter().enumerate() {
+                self.check(schema, &schema_path.key("allOf").index(index), instance, path, out)?;
+            }
+        }
+        if let Some(condition) = s.get("if") {
+            let branch = if self.is_valid(condition, &schema_path.key("if"), instance, path)? {
+                "then"
+            } else {
+                "else"
+            };
+            if let Some(schema) = s.get(branch) {
+                self.check(schema, &schema_path.key(branch), instance, path, out)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// `unevaluatedProperties` and `unevaluatedItems`, which apply to whatever no
+    /// other keyword of this schema or its valid subschemas looked at.
+    fn check_unevaluated(
+        &self,
+        s: &'a Map<String, Value>,
+        schema_path: &JsonPath,
+        instance: &Value,
+        path: &JsonPath,
+        out: &mut Vec<ValidationError>,
+    ) -> Result<(), SchemaError> {
+        if self.draft == Draft::Draft7 {
+            return Ok(());
+        }
+        let properties = s.get("unevaluatedProperties").filter(|_| instance.is_object());
+        let items = s.get("unevaluatedItems").filter(|_| instance.is_array());
+        if properties.is_none() && items.is_none() {
+            return Ok(());
+        }
+        let evaluated = self.evaluated(s, instance, path, false)?;
+        if let (Some(schema), Value::Object(map)) = (properties, instance) {
+            if !evaluated.all_properties {
+                for (name, value) in map.iter().filter(|(name, _)| !evaluated.properties.contains(*name)) {
+                    if schema == &Value::Bool(false) {
+                        out.push(ValidationError {
+                            instance_path: path.key(name),
+                            schema_path: schema_path.key("unevaluatedProperties"),
+                            message: format!("property \"{}\" is not allowed", name),
+                        });
+                    } else {
+                        self.check(schema, &schema_path.key("unevaluatedProperties"), value, &path.key(name), out)?;
+                    }
+                }
+            }
+        }
+        if let (Some(schema), Value::Array(values)) = (items, instance) {
+            if !evaluated.all_items {
+                for (index, value) in values.iter().enumerate().skip(evaluated.items) {
+                    self.check(schema, &schema_path.key("unevaluatedItems"), value, &path.index(index), out)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The properties and items that `s` evaluates. `nested` is false for the
+    /// schema holding the `unevaluated*` keyword itself, which must not count.
+    fn evaluated(
+        &self,
+        s: &'a Map<String, Value>,
+        instance: &Value,
+        path: &JsonPath,
+        nested: bool,
+    ) -> Result<Evaluated, SchemaError> {
+        let mut evaluated = Evaluated::default();
+        if let Value::Object(map) = instance {
+            if let Some(Value::Object(properties)) = s.get("properties") {
+                evaluated.properties.extend(map.keys().filter(|name| properties.contains_key(*name)).cloned());
+            }
+            if let Some(Value::Object(patterns)) = s.get("patternProperties") {
+                for pattern in patterns.keys() {
+                    for name in map.keys() {
+                        if self.matches(pattern, name, &JsonPath::root())? {
+                            evaluated.properties.insert(name.clone());
+                        }
+                    }
+                }
+            }
+            evaluated.all_properties = s.contains_key("additionalProperties")
+                || (nested && s.contains_key("unevaluatedProperties"));
+        }
+        if let Value::Array(items) = instance {
+            let (tuple, rest) = match s.get("items") {
+                Some(Value::Array(tuple)) => (Some(tuple), "additionalItems"),
+                _ => (s.get("prefixItems").and_then(Value::as_array), "items"),
+            };
+            evaluated.items = tuple.map_or(0, Vec::len).min(items.len());
+            evaluated.all_items = s.contains_key(rest) || (nested && s.contains_key("unevaluatedItems"));
+        }
+
+        let root = JsonPath::root();
+        let mut subschemas: Vec<&'a Value> = Vec::new();
+        for keyword in ["$ref", "$recursiveRef", "$dynamicRef"] {
+            if let Some(reference) = s.get(keyword).and_then(Value::as_str) {
+                subschemas.push(self.resolve(reference)?);
+            }
+        }
+        if let Some(Value::Array(schemas)) = s.get("allOf") {
+            subschemas.extend(schemas);
+        }
+        for keyword in ["anyOf", "oneOf"] {
+            if let Some(Value::Array(schemas)) = s.get(keyword) {
+                for schema in schemas {
+                    if self.is_valid(schema, &root, instance, path)? {
+                        subschemas.push(schema);
+                    }
+                }
+            }
+        }
+        if let Some(condition) = s.get("if") {
+            if self.is_valid(condition, &root, instance, path)? {
+                subschemas.push(condition);
+                subschemas.extend(s.get("then"));
+            } else {
+                subschemas.extend(s.get("else"));
+            }
+        }
+        if let (Some(Value::Object(dependencies)), Value::Object(map)) = (s.get("dependentSchemas"), instance) {
+            subschemas.extend(dependencies.iter().filter(|(name, _)| map.contains_key(*name)).map(|(_, schema)| schema));
+        }
+        for schema in subschemas {
+            match schema {
+                Value::Object(s) => evaluated.merge(self.evaluated(s, instance, path, true)?),
+                Value::Bool(true) => {
+                    evaluated.all_properties = true;
+                    evaluated.all_items = true;
+                }
+                _ => {}
+            }
+        }
+        Ok(evaluated)
+    }
+
+    fn is_valid(&self, schema: &'a Value, schema_path: &JsonPath, instance: &Value, path: &JsonPath) -> Result<bool, SchemaError> {
+        let mut errors = Vec::new();
+        self.check(schema, schema_path, instance, path, &mut errors)?;
+        Ok(errors.is_empty())
+    }
+
+    fn follow(
+        &self,
+        reference: &str,
+        schema_path: &JsonPath,
+        instance: &Value,
+        path: &JsonPath,
+        out: &mut Vec<ValidationError>,
+    ) -> Result<(), SchemaError> {
+        let target = self.resolve(reference)?;
+        let key = (target as *const Value, instance as *const Value);
+        if self.refs.borrow().contains(&key) {
+            return Err(SchemaError::RefLoop(reference.to_string()));
+        }
+        self.refs.borrow_mut().push(key);
+        let result = self.check(target, schema_path, instance, path, out);
+        self.refs.borrow_mut().pop();
+        result
+    }
+
+    fn resolve(&self, reference: &str) -> Result<&'a Value, SchemaError> {
+        let unresolved = || SchemaError::UnresolvedRef(reference.to_string());
+        let (base, fragment) = reference.split_once('#').unwrap_or((reference, ""));
+        let document = if base.is_empty() || id_matches(self.root, base) {
+            self.root
+        } else {
+            find_schema(self.root, &|schema| id_matches(schema, base)).ok_or_else(unresolved)?
+        };
+        if fragment.is_empty() {
+            return Ok(document);
+        }
+        if fragment.starts_with('/') {
+            return fragment
+                .split('/')
+                .skip(1)
+                .map(|token| {
+                    let token = urlencoding::decode(token).map(|token| token.into_owned()).unwrap_or(token.to_string());
+                    token.replace("~1", "/").replace("~0", "~")
+                })
+                .try_fold(document, |value, token| match value {
+                    Value::Object(map) => map.get(&token),
+                    Value::Array(items) => token.parse::<usize>().ok().and_then(|index| items.get(index)),
+                    _ => None,
+                })
+                .ok_or_else(unresolved);
+        }
+        let anchor = format!("#{}", fragment);
+        find_schema(document, &|schema| {
+            ["$anchor", "$dynamicAnchor", "$recursiveAnchor"]
+                .iter()
+                .any(|keyword| schema.get(*keyword).and_then(Value::as_str) == Some(fragment))
+                || schema.get("$id").and_then(Value::as_str) == Some(anchor.as_str())
+        })
+        .ok_or_else(unresolved)
+    }
+
+    fn matches(&self, pattern: &str, text: &str, schema_path: &JsonPath) -> Result<bool, SchemaError> {
+        if let Some(regex) = self.regexes.borrow().get(pattern) {
+            return Ok(regex.is_match(text));
+        }
+        let regex = Regex::new(pattern).map_err(|e| invalid(schema_path, &format!("invalid regular expression: {}", e)))?;
+        let matched = regex.is_match(text);
+        self.regexes.borrow_mut().insert(pattern.to_string(), regex);
+        Ok(matched)
+    }
+}
+
+fn invalid(path: &JsonPath, message: &str) -> SchemaError {
+    SchemaError::Invalid {
+        path: path.clone(),
+        message: message.to_string(),
+    }
+}
+
+/// Whether the schema's `$id` names `base`, comparing the last path segment when
+/// one side is a relative reference such as `item.json`.
+fn id_matches(schema: &Value, base: &str) -> bool {
+    let Some(id) = schema.get("$id").and_then(Value::as_str) else {
+        return false;
+    };
+    let id = id.trim_end_matches('#');
+    id == base || id.rsplit('/').next() == base.rsplit('/').next() && !base.is_empty()
+}
+
+/// The first subschema, searching depth first, for which `predicate` holds.
+fn find_schema<'a>(value: &'a Value, predicate: &dyn Fn(&Value) -> bool) -> Option<&'a Value> {
+    if value.is_object() && predicate(value) {
+        return Some(value);
+    }
+    match value {
+        Value::Object(map) => map.values().find_map(|child| find_schema(child, predicate)),
+        Value::Array(items) => items.iter().find_map(|child| find_schema(child, predicate)),
+        _ => None,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if is_integer(n) => "integer",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn has_type(value: &Value, name: &str) -> bool {
+    match (name, value) {
+        ("integer", Value::Number(n)) => is_integer(n),
+        ("number", Value::Number(_)) => true,
+        _ => type_name(value) == name,
+    }
+}
+
+/// Whether the number has no fractional part, so `1.0` and `1e3` count as integers.
+/// The text is inspected because `arbitrary_precision` keeps it as written and
+/// integers beyond `u64` would lose precision as `f64`.
+fn is_integer(n: &serde_json::Number) -> bool {
+    if n.is_i64() || n.is_u64() {
+        return true;
+    }
+    let text = n.to_string();
+    let (mantissa, exponent) = match text.split_once(['e', 'E']) {
+        Some((mantissa, exponent)) => (mantissa, exponent.parse::<i64>().unwrap_or(0)),
+        None => (text.as_str(), 0),
+    };
+    let fraction = mantissa.split_once('.').map_or("", |(_, fraction)| fraction.trim_end_matches('0'));
+    fraction.len() as i64 <= exponent
+}
+
+/// `multipleOf`, exact for integers and within rounding error otherwise, so that
+/// `19.99` is a multiple of `0.01`.
+fn is_multiple(n: &serde_json::Number, divisor: &serde_json::Number) -> bool {
+    if let (Some(n), Some(divisor)) = (n.as_i64(), divisor.as_i64()) {
+        return divisor != 0 && n % divisor == 0;
+    }
+    let (n, divisor) = (n.as_f64().unwrap_or(f64::NAN), divisor.as_f64().unwrap_or(f64::NAN));
+    let quotient = n / divisor;
+    quotient.is_finite() && (quotient - quotient.round()).abs() < 1e-9 * quotient.abs().max(1.0)
+}
+
+/// JSON equality with numbers compared by value, so `1` equals `1.0`.
+fn equal(a: &Value, b: &Value) -> bool {
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => match (a.as_i64(), b.as_i64()) {
+            (Some(a), Some(b)) => a == b,
+            _ => a.as_f64() == b.as_f64(),
+        },
+        (Value::Array(a), Value::Array(b)) => a.len() == b.len() && a.iter().zip(b).all(|(a, b)| equal(a, b)),
+        (Value::Object(a), Value::Object(b)) => {
+            a.len() == b.len() && a.iter().all(|(key, value)| b.get(key).is_some_and(|other| equal(value, other)))
+        }
+        _ => a == b,
+    }
+}
+
+/// Writes a key that is the same for any two values [`equal`] considers equal:
+/// numbers as `f64`, object keys sorted.
+fn canonical(value: &Value, out: &mut String) {
+    match value {
+        Value::Number(n) => {
+            // -0.0 == 0.0
+            let n = n.as_f64().unwrap_or(f64::NAN) + 0.0;
+            out.push_str(&format!("{:?}", n));
+        }
+        Value::Array(items) => {
+            out.push('[');
+            for item in items {
+                canonical(item, out);
+                out.push(',');
+            }
+            out.push(']');
+        }
+        Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_unstable_by_key(|(key, _)| *key);
+            out.push('{');
+            for (key, item) in entries {
+                out.push_str(&Value::from(key.as_str()).to_string());
+                out.push(':');
+                canonical(item, out);
+                out.push(',');
+            }
+            out.push('}');
+        }
+        other => out.push_str(&other.to_string()),
+    }
+}
+
+/// The first item equal to an earlier one, with that earlier item. Items are
+/// bucketed by [`canonical`] key; `equal` only separates the rare integers beyond
+/// `f64` precision that share a bucket.
+fn duplicate(items: &[Value]) -> Option<(usize, usize)> {
+    let mut seen: HashMap<String, Vec<usize>> = HashMap::new();
+    for (j, item) in items.iter().enumerate() {
+        let mut key = String::new();
+        canonical(item, &mut key);
+        let bucket = seen.entry(key).or_default();
+        if let Some(&i) = bucket.iter().find(|&&i| equal(&items[i], item)) {
+            return Some((i, j));
+        }
+        bucket.push(j);
+    }
+    None
+}
+
+/// Checks the formats this validator knows; `None` for any other format.
+pub fn check_format(format: &str, text: &str) -> Option<bool> {
+    let valid = match format {
+        "date" => chrono::NaiveDate::parse_from_str(text, "%Y-%m-%d").is_ok() && text.len() == 10,
+        "date-time" => chrono::DateTime::parse_from_rfc3339(text).is_ok(),
+        "time" => chrono::DateTime::parse_from_rfc3339(&format!("1970-01-01T{}", text)).is_ok(),
+        "email" => text.split_once('@').is_some_and(|(local, domain)| {
+            !local.is_empty() && !domain.contains('@') && is_hostname(domain) && domain.contains('.')
+        }),
+        "hostname" => is_hostname(text),
+        "ipv4" => text.parse::<Ipv4Addr>().is_ok(),
+        "ipv6" => text.parse::<Ipv6Addr>().is_ok(),
+        "uri" => text.split_once(':').is_some_and(|(scheme, _)| {
+            scheme.chars().next().is_some_and(|c| c.is_ascii_alphabetic())
+                && scheme.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+        }) && !text.chars().any(char::is_whitespace),
+        "uri-reference" => !text.chars().any(char::is_whitespace),
+        "uuid" => {
+            let groups: Vec<&str> = text.split('-').collect();
+            groups.iter().map(|group| group.len()).eq([8, 4, 4, 4, 12])
+                && groups.iter().all(|group| group.chars().all(|c| c.is_ascii_hexdigit()))
+        }
+        "regex" => Regex::new(text).is_ok(),
+        "json-pointer" => {
+            (text.is_empty() || text.starts_with('/'))
+                && text.split('~').skip(1).all(|rest| rest.starts_with(['0', '1']))
+        }
+        _ => return None,
+    };
+    Some(valid)
+}
+
+fn is_hostname(text: &str) -> bool {
+    !text.is_empty()
+        && text.len() <= 253
+        && text.trim_end_matches('.').split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn errors(schema: Value, instance: Value) -> Vec<(String, String)> {
+        validate(&schema, &instance, None)
+            .unwrap()
+            .into_iter()
+            .map(|e| (e.instance_path.to_string(), format!("#{}", e.schema_path.to_pointer())))
+            .collect()
+    }
+
+    #[test]
+    fn reports_instance_and_schema_paths() {
+        let schema = json!({
+            "type": "object",
+            "required": ["id", "name"],
+            "properties": {
+                "id": {"type": "integer", "minimum": 1},
+                "tags": {"type": "array", "items": {"type": "string"}, "uniqueItems": true}
+            },
+            "additionalProperties": false
+        });
+        let instance = json!({"id": 0, "tags": ["a", 2, "a"], "extra": true});
+        assert_eq!(
+            errors(schema, instance),
+            [
+                ("$".to_string(), "#/required".to_string()),
+                ("$.id".to_string(), "#/properties/id/minimum".to_string()),
+                ("$.tags".to_string(), "#/properties/tags/uniqueItems".to_string()),
+                ("$.tags[1]".to_string(), "#/properties/tags/items/type".to_string()),
+                ("$.extra".to_string(), "#/additionalProperties".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn resolves_references() {
+        let schema = json!({
+            "$defs": {
+                "node": {
+                    "$anchor": "node",
+                    "type": "object",
+                    "properties": {"children": {"type": "array", "items": {"$ref": "#node"}}},
+                    "required": ["value"]
+                }
+            },
+            "$ref": "#/$defs/node"
+        });
+        let instance = json!({"value": 1, "children": [{"value": 2}, {"children": []}]});
+        assert_eq!(
+            errors(schema, instance),
+            [("$.children[1]".to_string(), "#/$ref/properties/children/items/$ref/required".to_string())]
+        );
+        let looping = json!({"$defs": {"a": {"$ref": "#/$defs/a"}}, "$ref": "#/$defs/a"});
+        assert!(matches!(validate(&looping, &json!(1), None), Err(SchemaError::RefLoop(_))));
+        let remote = json!({"$ref": "https://example.com/other.json"});
+        assert!(matches!(validate(&remote, &json!(1), None), Err(SchemaError::UnresolvedRef(_))));
+    }
+
+    #[test]
+    fn follows_the_draft() {
+        let tuple = json!({"items": [{"type": "string"}], "additionalItems": false});
+        let draft7 = validate(&tuple, &json!(["a", 1]), Some(Draft::Draft7)).unwrap();
+        assert_eq!(draft7[0].instance_path.to_string(), "$[1]");
+        assert_eq!(draft7[0].keyword(), Some("additionalItems"));
+
+        let prefix = json!({"prefixItems": [{"type": "string"}], "items": {"type": "integer"}});
+        assert_eq!(errors(prefix, json!(["a", 1, "b"])), [("$[2]".to_string(), "#/items/type".to_string())]);
+
+        // draft 7 ignores keywords next to $ref
+        let schema = json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "definitions": {"s": {"type": "string"}},
+            "$ref": "#/definitions/s",
+            "minLength": 5
+        });
+        assert!(errors(schema, json!("abc")).is_empty());
+    }
+
+    #[test]
+    fn applies_combinators() {
+        let schema = json!({
+            "anyOf": [{"type": "string"}, {"type": "number"}],
+            "oneOf": [{"minimum": 0}, {"multipleOf": 2}],
+            "not": {"const": 4},
+            "if": {"type": "number"},
+            "then": {"maximum": 100},
+            "else": {"maxLength": 3}
+        });
+        assert!(errors(schema.clone(), json!(3)).is_empty());
+        assert_eq!(errors(schema.clone(), json!(true))[0].1, "#/anyOf");
+        let keywords: Vec<String> = errors(schema.clone(), json!(4)).into_iter().map(|(_, path)| path).collect();
+        assert_eq!(keywords, ["#/oneOf", "#/not"]);
+        assert_eq!(errors(schema, json!(102))[1].1, "#/then/maximum");
+    }
+
+    #[test]
+    fn tracks_unevaluated_properties() {
+        let schema = json!({
+            "allOf": [{"properties": {"a": true}}],
+            "if": {"required": ["kind"]},
+            "then": {"properties": {"kind": true, "b": true}},
+            "unevaluatedProperties": false
+        });
+        assert!(errors(schema.clone(), json!({"a": 1, "kind": "x", "b": 2})).is_empty());
+        assert_eq!(
+            errors(schema, json!({"a": 1, "b": 2})),
+            [("$.b".to_string(), "#/unevaluatedProperties".to_string())]
+        );
+    }
+
+    #[test]
+    fn compares_numbers_by_value() {
+        let value: Value = serde_json::from_str(r#"[1.0, 19.99, 1e2, 12345678901234567890123]"#).unwrap();
+        let schema = json!({
+            "prefixItems": [
+                {"type": "integer", "const": 1},
+                {"multipleOf": 0.01, "type": "number"},
+                {"type": "integer", "enum": [100]},
+                {"type": "integer", "minimum": 0}
+            ]
+        });
+        assert!(errors(schema, value).is_empty());
+        assert_eq!(errors(json!({"type": "integer"}), json!(1.5))[0].1, "#/type");
+        assert_eq!(errors(json!({"multipleOf": 3}), json!(10)).len(), 1);
+    }
+
+    #[test]
+    fn finds_duplicate_items_by_value() {
+        let items: Value = serde_json::from_str(r#"[{"a": 1, "b": [0]}, 2, {"b": [-0.0], "a": 1.0}]"#).unwrap();
+        assert_eq!(duplicate(items.as_array().unwrap()), Some((0, 2)));
+        let items: Value = serde_json::from_str("[9007199254740992, 9007199254740993, \"1\", 1]").unwrap();
+        assert_eq!(duplicate(items.as_array().unwrap()), None);
+
+        let items: Vec<Value> = (0..100_000).map(|i| json!({"id": i})).collect();
+        let start = std::time::Instant::now();
+        assert_eq!(duplicate(&items), None);
+        assert!(start.elapsed() < std::time::Duration::from_secs(5));
+    }
+
+    #[test]
+    fn checks_strings_and_formats() {
+        let schema = json!({
+            "properties": {
+                "email": {"format": "email"},
+                "when": {"format": "date-time"},
+                "code": {"pattern": "^[A-Z]{3}$", "maxLength": 3}
+            }
+        });
+        let valid = json!({"email": "a@example.com", "when": "2024-05-01T10:00:00Z", "code": "ABC"});
+        assert!(errors(schema.clone(), valid).is_empty());
+        let invalid = json!({"email": "nope", "when": "yesterday", "code": "abcd"});
+        assert_eq!(errors(schema, invalid).len(), 4);
+        assert_eq!(check_format("uuid", "123e4567-e89b-12d3-a456-426614174000"), Some(true));
+        assert_eq!(check_format("ipv4", "256.0.0.1"), Some(false));
+        assert_eq!(check_format("color", "red"), None);
+        let bad_pattern = json!({"pattern": "("});
+        assert!(matches!(validate(&bad_pattern, &json!("x"), None), Err(SchemaError::Invalid { .. })));
+    }
+}
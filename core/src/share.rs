@@ -0,0 +1,106 @@
+use base64::{Engine as _, engine::general_purpose::URL_SAFE_NO_PAD};
+use flate2::{read::DeflateDecoder, write::DeflateEncoder, Compression};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+
+/// The largest input that is shared or stored in the browser, in bytes.
+pub const MAX_INPUT: usize = 512 * 1024;
+
+/// The largest decoded state accepted from a link: the input and the other
+/// inputs a tool shares, such as a schema, with room for JSON escaping. A few
+/// kilobytes of deflated data can otherwise expand to gigabytes.
+const MAX_DECODED: usize = 8 * MAX_INPUT;
+
+/// A tool's input and options, as carried by a share link.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SharedState {
+    pub input: String,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub options: BTreeMap<String, String>,
+}
+
+impl SharedState {
+    pub fn new(input: String) -> Self {
+        Self {
+            input,
+            options: BTreeMap::new(),
+        }
+    }
+
+    pub fn with_option(mut self, name: &str, value: impl Into<String>) -> Self {
+        self.options.insert(name.to_string(), value.into());
+        self
+    }
+
+    pub fn option(&self, name: &str) -> Option<&str> {
+        self.options.get(name).map(String::as_str)
+    }
+}
+
+/// Serializes to JSON, deflates and encodes as URL-safe Base64.
+pub fn encode_state(state: &SharedState) -> String {
+    let json = serde_json::to_vec(state).unwrap_or_default();
+    let mut encoder = DeflateEncoder::new(Vec::new(), Compression::best());
+    let _ = encoder.write_all(&json);
+    let compressed = encoder.finish().unwrap_or_default();
+    URL_SAFE_NO_PAD.encode(compressed)
+}
+
+/// Reverses [`encode_state`]; `None` for anything that is not a share link or
+/// that inflates beyond [`MAX_DECODED`].
+pub fn decode_state(encoded: &str) -> Option<SharedState> {
+    let compressed = URL_SAFE_NO_PAD.decode(encoded.trim()).ok()?;
+    let mut json = Vec::new();
+    DeflateDecoder::new(compressed.as_slice())
+        .take(MAX_DECODED as u64 + 1)
+        .read_to_end(&mut json)
+        .ok()?;
+    if json.len() > MAX_DECODED {
+        return None;
+    }
+    serde_json::from_slice(&json).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_input_and_options() {
+        let state = SharedState::new("{\"a\": \"b#c&d\"}".into()).with_option("format", "yaml");
+        let encoded = encode_state(&state);
+        assert!(encoded
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+        assert_eq!(decode_state(&encoded), Some(state));
+    }
+
+    #[test]
+    fn compresses_repetitive_input() {
+        let state = SharedState::new("[1,2,3]".repeat(500));
+        assert!(encode_state(&state).len() < 200);
+    }
+
+    #[test]
+    fn rejects_links_that_inflate_too_far() {
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::best());
+        encoder.write_all(br#"{"input":""#).unwrap();
+        for _ in 0..64 {
+            encoder.write_all(&[b'a'; MAX_INPUT / 4]).unwrap();
+        }
+        encoder.write_all(br#""}"#).unwrap();
+        let encoded = URL_SAFE_NO_PAD.encode(encoder.finish().unwrap());
+        assert!(encoded.len() < 64 * 1024);
+        assert_eq!(decode_state(&encoded), None);
+
+        let state = SharedState::new("a".repeat(MAX_INPUT));
+        assert_eq!(decode_state(&encode_state(&state)), Some(state));
+    }
+
+    #[test]
+    fn ignores_garbage() {
+        assert_eq!(decode_state("not a share link"), None);
+        assert_eq!(decode_state(""), None);
+    }
+}
@@ -0,0 +1,184 @@
+//! JSON documents embedded in JSON strings, as log pipelines often produce:
+//! `{"payload": "{\"a\":1}"}`.
+//!
+//! [`escape`] turns a document into such a string literal, [`unescape`] reads one
+//! back, and [`unwrap`] expands every string field that itself holds JSON.
+
+use crate::json::{self, ErrorLocation, FormatOptions, JsonError};
+use crate::path::JsonPath;
+use serde_json::Value;
+use std::fmt;
+
+#[derive(Debug)]
+pub enum UnescapeError {
+    /// The input is not a JSON string literal.
+    Literal(JsonError, Option<ErrorLocation>),
+    /// The string does not hold a JSON document.
+    Document(JsonError),
+}
+
+impl fmt::Display for UnescapeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UnescapeError::Literal(e, location) => {
+                // The parser's position is in the quoted text, so report the mapped one
+                let message = e.to_string();
+                let message = message.rsplit_once(" at line ").map_or(message.as_str(), |(message, _)| message);
+                write!(f, "not a JSON string literal: {}", message)?;
+                match location {
+                    Some(location) => write!(f, " at line {} column {}", location.line, location.column),
+                    None => Ok(()),
+                }
+            }
+            UnescapeError::Document(e) => write!(f, "the string does not hold JSON: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for UnescapeError {}
+
+impl UnescapeError {
+    /// Where in the input the string literal is malformed; `None` when the
+    /// problem is inside the unescaped document.
+    pub fn location(&self) -> Option<ErrorLocation> {
+        match self {
+            UnescapeError::Literal(_, location) => *location,
+            UnescapeError::Document(_) => None,
+        }
+    }
+}
+
+/// The document as a JSON string literal holding its minified text.
+pub fn escape(value: &Value, options: &FormatOptions) -> Result<String, JsonError> {
+    let text = json::minify_value(value, options)?;
+    json::minify_value(&Value::String(text), options)
+}
+
+/// Reads a JSON string literal and parses the document inside it. The
+/// surrounding quotes may be left out, as when the literal was copied out of a
+/// log line: `{\"a\":1}`.
+pub fn unescape(input: &str) -> Result<Value, UnescapeError> {
+    let trimmed = input.trim();
+    let quoted = trimmed.starts_with('"');
+    let literal = if quoted { trimmed.to_string() } else { format!("\"{}\"", trimmed) };
+    let text = match json::parse(&literal) {
+        Ok(Value::String(text)) => text,
+        Ok(_) => unreachable!("a quoted literal parses to a string"),
+        Err(e) => {
+            let location = e.location(&literal).map(|location| {
+                // Back to the input: drop the quote that was added and put back
+                // the leading whitespace
+                let added = usize::from(!quoted && location.line == 1);
+                let leading = &input[..input.len() - input.trim_start().len()];
+                let (lines, indent) = match leading.rsplit_once('\n') {
+                    Some((before, indent)) => (before.matches('\n').count() + 1, indent),
+                    None => (0, leading),
+                };
+                let column = match location.line {
+                    1 => location.column.saturating_sub(added).max(1) + indent.chars().count(),
+                    _ => location.column,
+                };
+                ErrorLocation { line: location.line + lines, column }
+            });
+            return Err(UnescapeError::Literal(e, location));
+        }
+    };
+    json::parse(&text).map_err(UnescapeError::Document)
+}
+
+/// Replaces every string that holds a JSON object or array with the parsed
+/// value, recursing into the result so that JSON stringified several times is
+/// expanded fully. Returns the paths of the expanded fields in document order.
+pub fn unwrap(value: Value) -> (Value, Vec<JsonPath>) {
+    let mut expanded = Vec::new();
+    let value = unwrap_at(value, &JsonPath::root(), &mut expanded);
+    (value, expanded)
+}
+
+fn unwrap_at(value: Value, path: &JsonPath, expanded: &mut Vec<JsonPath>) -> Value {
+    match value {
+        Value::String(text) => match embedded(&text) {
+            Some(inner) => {
+                expanded.push(path.clone());
+                unwrap_at(inner, path, expanded)
+            }
+            None => Value::String(text),
+        },
+        Value::Array(items) => Value::Array(
+            items
+                .into_iter()
+                .enumerate()
+                .map(|(index, item)| unwrap_at(item, &path.index(index), expanded))
+                .collect(),
+        ),
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(key, item)| {
+                    let item = unwrap_at(item, &path.key(&key), expanded);
+                    (key, item)
+                })
+                .collect(),
+        ),
+        scalar => scalar,
+    }
+}
+
+/// The object or array a string holds, looking through string literals for
+/// JSON that was stringified more than once. Strings holding scalars such as
+/// `"42"` are left alone.
+fn embedded(text: &str) -> Option<Value> {
+    let trimmed = text.trim();
+    if !trimmed.starts_with(['{', '[', '"']) {
+        return None;
+    }
+    match json::parse(trimmed).ok()? {
+        Value::String(inner) => embedded(&inner),
+        value => Some(value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn escapes_and_unescapes() {
+        let value = json!({"a": [1, "x\"y"], "b": "é"});
+        let escaped = escape(&value, &FormatOptions::default()).unwrap();
+        assert_eq!(escaped, r#""{\"a\":[1,\"x\\\"y\"],\"b\":\"é\"}""#);
+        assert_eq!(unescape(&escaped).unwrap(), value);
+        assert_eq!(unescape(r#"  {\"a\":1}"#).unwrap(), json!({"a": 1}));
+
+        let error = unescape(r#"
+  {\"a\":1\q}"#).unwrap_err();
+        assert!(matches!(error, UnescapeError::Literal(..)));
+        assert_eq!(error.location(), Some(ErrorLocation { line: 2, column: 12 }));
+        assert_eq!(error.to_string(), "not a JSON string literal: invalid escape at line 2 column 12");
+        let error = unescape(r#""{\"a\":}""#).unwrap_err();
+        assert!(matches!(error, UnescapeError::Document(_)));
+        assert_eq!(error.location(), None);
+    }
+
+    #[test]
+    fn unwraps_nested_stringified_fields() {
+        let inner = json!({"user": "ann", "tags": "[1,2]"}).to_string();
+        let twice = Value::String(json!({"deep": true}).to_string()).to_string();
+        let value = json!({
+            "payload": inner,
+            "items": [twice, "42", "{not json"],
+            "plain": "text"
+        });
+        let (value, expanded) = unwrap(value);
+        assert_eq!(
+            value,
+            json!({
+                "payload": {"user": "ann", "tags": [1, 2]},
+                "items": [{"deep": true}, "42", "{not json"],
+                "plain": "text"
+            })
+        );
+        let paths: Vec<String> = expanded.iter().map(JsonPath::to_string).collect();
+        assert_eq!(paths, ["$.payload", "$.payload.tags", "$.items[0]"]);
+    }
+}
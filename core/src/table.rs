@@ -0,0 +1,484 @@
+//! Arrays of JSON records as CSV or TSV tables, and tables back into JSON.
+//!
+//! Nested fields are flattened into dotted column names such as `address.city`,
+//! and reading a table rebuilds the nesting from those names. Arrays inside
+//! records are written according to [`ArrayMode`].
+
+use crate::convert::{scalar_text, ConvertError};
+use crate::json::JsonError;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+/// Separates the items of an array written with [`ArrayMode::Join`].
+const JOIN_SEPARATOR: &str = ";";
+
+/// Column of a table made from an array of scalars.
+const VALUE_COLUMN: &str = "value";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Delimiter {
+    #[default]
+    Comma,
+    /// Tab-separated values (TSV).
+    Tab,
+    Semicolon,
+    Pipe,
+}
+
+impl Delimiter {
+    pub const ALL: [Delimiter; 4] = [Delimiter::Comma, Delimiter::Tab, Delimiter::Semicolon, Delimiter::Pipe];
+
+    pub fn as_param(&self) -> &'static str {
+        match self {
+            Delimiter::Comma => "comma",
+            Delimiter::Tab => "tab",
+            Delimiter::Semicolon => "semicolon",
+            Delimiter::Pipe => "pipe",
+        }
+    }
+
+    pub fn from_param(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|delimiter| delimiter.as_param() == value)
+    }
+
+    fn byte(&self) -> u8 {
+        match self {
+            Delimiter::Comma => b',',
+            Delimiter::Tab => b'\t',
+            Delimiter::Semicolon => b';',
+            Delimiter::Pipe => b'|',
+        }
+    }
+}
+
+/// How arrays inside a record are written.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ArrayMode {
+    /// The whole array as JSON in one cell, e.g. `["a","b"]`.
+    #[default]
+    Json,
+    /// One column per item: `tags.0`, `tags.1`, ...
+    Index,
+    /// The items in one cell separated by `;`, e.g. `a;b`. Not read back as an array.
+    Join,
+}
+
+impl ArrayMode {
+    pub const ALL: [ArrayMode; 3] = [ArrayMode::Json, ArrayMode::Index, ArrayMode::Join];
+
+    pub fn as_param(&self) -> &'static str {
+        match self {
+            ArrayMode::Json => "json",
+            ArrayMode::Index => "index",
+            ArrayMode::Join => "join",
+        }
+    }
+
+    pub fn from_param(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|mode| mode.as_param() == value)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TableOptions {
+    pub delimiter: Delimiter,
+    /// The first row names the columns. Without it, rows are read as arrays.
+    pub header: bool,
+    pub arrays: ArrayMode,
+    /// When reading, turn numbers, `true`, `false`, `null` and JSON arrays or
+    /// objects into typed values and leave out empty cells; otherwise every cell
+    /// is a string.
+    pub infer_types: bool,
+}
+
+impl Default for TableOptions {
+    fn default() -> Self {
+        TableOptions {
+            delimiter: Delimiter::default(),
+            header: true,
+            arrays: ArrayMode::default(),
+            infer_types: true,
+        }
+    }
+}
+
+impl TableOptions {
+    /// The options as query-string parameters, e.g. `delimiter=tab&header=false`.
+    pub fn to_params(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("delimiter", self.delimiter.as_param().to_string()),
+            ("header", self.header.to_string()),
+            ("arrays", self.arrays.as_param().to_string()),
+            ("infer", self.infer_types.to_string()),
+        ]
+    }
+
+    /// Reads options written by [`to_params`](Self::to_params); missing or invalid
+    /// parameters keep their defaults.
+    pub fn from_params(param: impl Fn(&str) -> Option<String>) -> Self {
+        let flag = |name: &str| param(name).is_none_or(|value| value != "false");
+        TableOptions {
+            delimiter: param("delimiter")
+                .and_then(|value| Delimiter::from_param(&value))
+                .unwrap_or_default(),
+            header: flag("header"),
+            arrays: param("arrays").and_then(|value| ArrayMode::from_param(&value)).unwrap_or_default(),
+            infer_types: flag("infer"),
+        }
+    }
+}
+
+/// Rows of text cells under named columns.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Table {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    /// Row indices ordered by one column, numbers before text and empty cells last.
+    pub fn sorted_rows(&self, column: usize, descending: bool) -> Vec<usize> {
+        let cell = |row: usize| self.rows[row].get(column).map_or("", String::as_str);
+        let mut order: Vec<usize> = (0..self.rows.len()).collect();
+        order.sort_by(|&a, &b| {
+            let (a, b) = (cell(a), cell(b));
+            match (a.is_empty(), b.is_empty()) {
+                (true, true) => Ordering::Equal,
+                (true, false) => Ordering::Greater,
+                (false, true) => Ordering::Less,
+                _ if descending => compare_cells(b, a),
+                _ => compare_cells(a, b),
+            }
+        });
+        order
+    }
+}
+
+fn compare_cells(a: &str, b: &str) -> Ordering {
+    match (a.parse::<f64>(), b.parse::<f64>()) {
+        (Ok(a), Ok(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+        (Ok(_), Err(_)) => Ordering::Less,
+        (Err(_), Ok(_)) => Ordering::Greater,
+        _ => a.cmp(b),
+    }
+}
+
+/// Flattens an array of records into a table. Columns appear in the order their
+/// fields are first seen; a record without a field gets an empty cell. An array
+/// of arrays gives numbered columns, an array of scalars a single `value` column,
+/// and a lone object a single row.
+pub fn to_table(json: &Value, options: &TableOptions) -> Result<Table, JsonError> {
+    let records = match json {
+        Value::Array(items) => items.as_slice(),
+        Value::Object(_) => std::slice::from_ref(json),
+        _ => return Err(JsonError::Write("a table needs an array of records or a single object".to_string())),
+    };
+    // Columns in order of first appearance, and each row's cells by column index
+    let mut columns: Vec<String> = Vec::new();
+    let mut indices: HashMap<String, usize> = HashMap::new();
+    let mut cells = Vec::with_capacity(records.len());
+    for record in records {
+        let mut fields = Vec::new();
+        match record {
+            Value::Object(_) | Value::Array(_) => flatten("", record, options, &mut fields),
+            scalar => fields.push((VALUE_COLUMN.to_string(), scalar_text(scalar))),
+        }
+        let row: Vec<(usize, String)> = fields
+            .into_iter()
+            .map(|(name, text)| {
+                let index = *indices.entry(name).or_insert_with_key(|name| {
+                    columns.push(name.clone());
+                    columns.len() - 1
+                });
+                (index, text)
+            })
+            .collect();
+        cells.push(row);
+    }
+    let rows = cells
+        .into_iter()
+        .map(|fields| {
+            let mut row = vec![String::new(); columns.len()];
+            for (index, text) in fields {
+                row[index] = text;
+            }
+            row
+        })
+        .collect();
+    Ok(Table { columns, rows })
+}
+
+fn flatten(prefix: &str, value: &Value, options: &TableOptions, out: &mut Vec<(String, String)>) {
+    let name = |key: &str| if prefix.is_empty() { key.to_string() } else { format!("{}.{}", prefix, key) };
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            for (key, value) in map {
+                flatten(&name(key), value, options, out);
+            }
+        }
+        // A record that is itself an array always spreads into numbered columns
+        Value::Array(items) if !items.is_empty() && (prefix.is_empty() || options.arrays == ArrayMode::Index) => {
+            for (index, item) in items.iter().enumerate() {
+                flatten(&name(&index.to_string()), item, options, out);
+            }
+        }
+        Value::Array(items) if options.arrays == ArrayMode::Join => {
+            let joined = items.iter().map(scalar_text).collect::<Vec<_>>().join(JOIN_SEPARATOR);
+            out.push((prefix.to_string(), joined));
+        }
+        other => out.push((prefix.to_string(), scalar_text(other))),
+    }
+}
+
+/// Writes a table with the delimiter and header from `options`.
+pub fn write(table: &Table, options: &TableOptions) -> Result<String, JsonError> {
+    let write_error = |e: csv::Error| JsonError::Write(e.to_string());
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(options.delimiter.byte())
+        .from_writer(Vec::new());
+    if options.header {
+        writer.write_record(&table.columns).map_err(write_error)?;
+    }
+    for row in &table.rows {
+        writer.write_record(row).map_err(write_error)?;
+    }
+    let bytes = writer.into_inner().map_err(|e| JsonError::Write(e.to_string()))?;
+    Ok(String::from_utf8(bytes).expect("tables are written from strings"))
+}
+
+/// Flattens `json` with [`to_table`] and writes it with [`write`].
+pub fn to_csv(json: &Value, options: &TableOptions) -> Result<String, JsonError> {
+    write(&to_table(json, options)?, options)
+}
+
+/// Reads a table into an array with one object per row, nested by the dotted
+/// column names; without a header each row is an array of cells.
+pub fn parse(input: &str, options: &TableOptions) -> Result<Value, ConvertError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(options.delimiter.byte())
+        .has_headers(options.header)
+        .from_reader(input.as_bytes());
+    let csv_error = |e: csv::Error| {
+        let offset = e.position().map_or(0, |position| position.byte() as usize);
+        ConvertError::at(input, offset, e)
+    };
+    let headers = if options.header {
+        reader.headers().map_err(csv_error)?.iter().map(str::to_string).collect()
+    } else {
+        Vec::new()
+    };
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(csv_error)?;
+        let values = record.iter().map(|text| {
+            if options.infer_types {
+                infer(text)
+            } else {
+                Some(Value::String(text.to_string()))
+            }
+        });
+        if !options.header {
+            rows.push(Value::Array(values.map(Option::unwrap_or_default).collect()));
+            continue;
+        }
+        let mut row = Map::new();
+        for (header, value) in headers.iter().zip(values) {
+            if let Some(value) = value {
+                insert(&mut row, header, value);
+            }
+        }
+        // Index-mode columns may leave gaps, e.g. `tags.1` without `tags.0`; each
+        // index has its own column, so none can reach the column count
+        let gaps = (options.arrays == ArrayMode::Index).then_some(headers.len());
+        rows.push(indices_to_arrays(Value::Object(row), gaps));
+    }
+    Ok(Value::Array(rows))
+}
+
+/// The typed value of a cell, or `None` for an empty cell.
+fn infer(text: &str) -> Option<Value> {
+    match text {
+        "" => None,
+        "true" => Some(Value::Bool(true)),
+        "false" => Some(Value::Bool(false)),
+        "null" => Some(Value::Null),
+        _ if is_number(text) || text.starts_with(['[', '{']) => {
+            Some(serde_json::from_str(text).unwrap_or_else(|_| Value::String(text.to_string())))
+        }
+        _ => Some(Value::String(text.to_string())),
+    }
+}
+
+/// Whether `text` is a number as JSON writes it. Leading zeros and plus signs
+/// rule it out, so codes like `007` stay strings.
+fn is_number(text: &str) -> bool {
+    let digits = text.strip_prefix('-').unwrap_or(text);
+    let (mantissa, exponent) = match digits.find(['e', 'E']) {
+        Some(index) => (&digits[..index], Some(&digits[index + 1..])),
+        None => (digits, None),
+    };
+    let (integer, fraction) = match mantissa.split_once('.') {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (mantissa, None),
+    };
+    let all_digits = |part: &str| !part.is_empty() && part.bytes().all(|byte| byte.is_ascii_digit());
+    all_digits(integer)
+        && (integer == "0" || !integer.starts_with('0'))
+        && fraction.is_none_or(all_digits)
+        && exponent.is_none_or(|exponent| all_digits(exponent.strip_prefix(['+', '-']).unwrap_or(exponent)))
+}
+
+/// Sets the field named by a dotted column, creating the objects on the way.
+fn insert(row: &mut Map<String, Value>, column: &str, value: Value) {
+    let mut target = row;
+    let mut segments = column.split('.').peekable();
+    while let Some(segment) = segments.next() {
+        if segments.peek().is_none() {
+            target.insert(segment.to_string(), value);
+            return;
+        }
+        let entry = target.entry(segment.to_string()).or_insert_with(|| Value::Object(Map::new()));
+        if !entry.is_object() {
+            *entry = Value::Object(Map::new());
+        }
+        target = entry.as_object_mut().expect("just made an object");
+    }
+}
+
+/// Turns objects whose keys are the indices `0..n`, from `tags.0` style
+/// columns, into arrays. Keys such as years (`sales.2023`) leave the object as
+/// it is. With `gaps`, indices below that bound may be missing and become `null`.
+fn indices_to_arrays(value: Value, gaps: Option<usize>) -> Value {
+    let Value::Object(map) = value else {
+        return value;
+    };
+    let map: Map<String, Value> = map.into_iter().map(|(key, value)| (key, indices_to_arrays(value, gaps))).collect();
+    let indices: Option<Vec<usize>> = map.keys().map(|key| key.parse().ok().filter(|_| is_number(key))).collect();
+    let Some(indices) = indices.filter(|indices| !indices.is_empty()) else {
+        return Value::Object(map);
+    };
+    let len = indices.iter().max().and_then(|max| max.checked_add(1));
+    let Some(len) = len.filter(|&len| len == indices.len() || gaps.is_some_and(|bound| len <= bound)) else {
+        return Value::Object(map);
+    };
+    let mut items = vec![Value::Null; len];
+    for (index, (_, value)) in indices.into_iter().zip(map) {
+        items[index] = value;
+    }
+    Value::Array(items)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn records() -> Value {
+        json!([
+            {"id": 1, "name": "Ada", "address": {"city": "London", "zip": "N1"}, "tags": ["a", "b"]},
+            {"id": 2, "name": "Bob, Jr", "address": {"city": "Paris"}, "tags": [], "active": true}
+        ])
+    }
+
+    #[test]
+    fn flattens_records_into_dotted_columns() {
+        let table = to_table(&records(), &TableOptions::default()).unwrap();
+        assert_eq!(table.columns, ["id", "name", "address.city", "address.zip", "tags", "active"]);
+        assert_eq!(table.rows[1], ["2", "Bob, Jr", "Paris", "", "[]", "true"]);
+        assert_eq!(
+            to_csv(&records(), &TableOptions::default()).unwrap(),
+            "id,name,address.city,address.zip,tags,active\n1,Ada,London,N1,\"[\"\"a\"\",\"\"b\"\"]\",\n2,\"Bob, Jr\",Paris,,[],true\n"
+        );
+        let options = TableOptions {
+            delimiter: Delimiter::Tab,
+            header: false,
+            arrays: ArrayMode::Join,
+            ..TableOptions::default()
+        };
+        assert_eq!(to_csv(&records(), &options).unwrap(), "1\tAda\tLondon\tN1\ta;b\t\n2\tBob, Jr\tParis\t\t\ttrue\n");
+        let options = TableOptions {
+            arrays: ArrayMode::Index,
+            ..TableOptions::default()
+        };
+        let table = to_table(&records(), &options).unwrap();
+        assert_eq!(table.columns, ["id", "name", "address.city", "address.zip", "tags.0", "tags.1", "tags", "active"]);
+        assert_eq!(to_table(&json!([[1, 2], [3]]), &options).unwrap().columns, ["0", "1"]);
+        assert!(to_table(&json!(1), &options).is_err());
+    }
+
+    #[test]
+    fn reads_tables_back_with_types() {
+        let csv = to_csv(&records(), &TableOptions::default()).unwrap();
+        let mut expected = records();
+        expected[1]["tags"] = json!([]);
+        assert_eq!(parse(&csv, &TableOptions::default()).unwrap(), expected);
+
+        let options = TableOptions {
+            arrays: ArrayMode::Index,
+            ..TableOptions::default()
+        };
+        let csv = to_csv(&records(), &options).unwrap();
+        let read = parse(&csv, &options).unwrap();
+        assert_eq!(read[0]["tags"], json!(["a", "b"]));
+        // An empty array has no items to spread, so it stays in the `tags` column
+        assert_eq!(read[1]["tags"], json!([]));
+
+        let input = "code;amount;flag;note\n007;-1.5e3;false;\n";
+        let options = TableOptions {
+            delimiter: Delimiter::Semicolon,
+            ..TableOptions::default()
+        };
+        assert_eq!(parse(input, &options).unwrap().to_string(), r#"[{"code":"007","amount":-1.5e3,"flag":false}]"#);
+        let options = TableOptions {
+            delimiter: Delimiter::Semicolon,
+            header: false,
+            infer_types: false,
+            ..TableOptions::default()
+        };
+        assert_eq!(parse(input, &options).unwrap()[1], json!(["007", "-1.5e3", "false", ""]));
+    }
+
+    #[test]
+    fn keeps_numeric_keys_that_are_not_indices() {
+        let input = "region,sales.2023,sales.2024,tags.1,big.18446744073709551615\nEU,10,12,x,1\n";
+        let read = parse(input, &TableOptions::default()).unwrap();
+        assert_eq!(
+            read[0],
+            json!({"region": "EU", "sales": {"2023": 10, "2024": 12}, "tags": {"1": "x"}, "big": {"18446744073709551615": 1}})
+        );
+        let options = TableOptions {
+            arrays: ArrayMode::Index,
+            ..TableOptions::default()
+        };
+        let read = parse(input, &options).unwrap();
+        assert_eq!(read[0]["sales"], json!({"2023": 10, "2024": 12}));
+        assert_eq!(read[0]["tags"], json!([null, "x"]));
+        assert_eq!(parse("a.1,a.0\nx,y\n", &TableOptions::default()).unwrap()[0]["a"], json!(["y", "x"]));
+    }
+
+    #[test]
+    fn sorts_rows_numerically() {
+        let table = Table {
+            columns: vec!["n".to_string()],
+            rows: [["10"], [""], ["9"], ["b"], ["a"]].iter().map(|row| vec![row[0].to_string()]).collect(),
+        };
+        assert_eq!(table.sorted_rows(0, false), [2, 0, 4, 3, 1]);
+        assert_eq!(table.sorted_rows(0, true), [3, 4, 0, 2, 1]);
+    }
+
+    #[test]
+    fn options_round_trip_as_params() {
+        let options = TableOptions {
+            delimiter: Delimiter::Pipe,
+            header: false,
+            arrays: ArrayMode::Index,
+            infer_types: false,
+        };
+        let params = options.to_params();
+        let param = |name: &str| params.iter().find(|(key, _)| *key == name).map(|(_, value)| value.clone());
+        assert_eq!(TableOptions::from_params(param), options);
+        assert_eq!(TableOptions::from_params(|_| None), TableOptions::default());
+    }
+}
@@ -0,0 +1,421 @@
+//! Conversions that can run off the main thread.
+//!
+//! The web app posts a [`Request`] to a worker and receives [`Response`]s back:
+//! zero or more progress reports followed by the result. Each message is split
+//! into a small JSON envelope and the input or output text, which is posted as a
+//! plain string so that it is never escaped into JSON.
+
+use crate::convert::{self, SourceFormat};
+use crate::json::{self, ErrorLocation, FormatOptions, FormatType, JsonError, Relaxation};
+use crate::base64;
+use crate::repair::{self, Repair};
+use crate::stringified;
+use crate::table::TableOptions;
+use serde::{Deserialize, Serialize};
+use std::mem;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Task {
+    JsonFormat { input: String, source: SourceFormat, format_type: FormatType, options: FormatOptions, lenient: bool },
+    JsonMinify { input: String, source: SourceFormat, options: FormatOptions, lenient: bool },
+    /// The document as a JSON string literal.
+    JsonEscape { input: String, source: SourceFormat, options: FormatOptions, lenient: bool },
+    /// A JSON string literal back to the document it holds.
+    JsonUnescape { input: String, format_type: FormatType, options: FormatOptions },
+    /// The document with every stringified JSON field expanded.
+    JsonUnwrap { input: String, source: SourceFormat, format_type: FormatType, options: FormatOptions, lenient: bool },
+    /// Almost-JSON made valid, with each repair reported.
+    JsonRepair { input: String, format_type: FormatType, options: FormatOptions },
+    Base64Encode { input: String },
+    Base64Decode { input: String },
+}
+
+impl Task {
+    pub fn input(&self) -> &str {
+        match self {
+            Task::JsonFormat { input, .. }
+            | Task::JsonMinify { input, .. }
+            | Task::JsonEscape { input, .. }
+            | Task::JsonUnescape { input, .. }
+            | Task::JsonUnwrap { input, .. }
+            | Task::JsonRepair { input, .. }
+            | Task::Base64Encode { input }
+            | Task::Base64Decode { input } => input,
+        }
+    }
+
+    fn input_mut(&mut self) -> &mut String {
+        match self {
+            Task::JsonFormat { input, .. }
+            | Task::JsonMinify { input, .. }
+            | Task::JsonEscape { input, .. }
+            | Task::JsonUnescape { input, .. }
+            | Task::JsonUnwrap { input, .. }
+            | Task::JsonRepair { input, .. }
+            | Task::Base64Encode { input }
+            | Task::Base64Decode { input } => input,
+        }
+    }
+
+    /// Runs the task, calling `progress` with a percentage as work completes.
+    ///
+    /// JSON tasks report each stage (reading the input, writing the output) as it
+    /// finishes; Base64 tasks report every chunk. Errors are returned as their
+    /// display text, since they cross a thread boundary.
+    pub fn run(&self, progress: impl FnMut(u8)) -> Result<Output, TaskError> {
+        match self {
+            Task::JsonFormat { input, source, format_type, options, lenient } => staged(
+                progress,
+                || parse_json(input, *source, &options.table, *lenient),
+                |(value, relaxations)| {
+                    let text = json::format_value(&value, *format_type, options)?;
+                    Ok(Output { text, relaxations, ..Output::default() })
+                },
+            ),
+            Task::JsonMinify { input, source, options, lenient } => staged(
+                progress,
+                || parse_json(input, *source, &options.table, *lenient),
+                |(value, relaxations)| {
+                    let text = json::minify_value(&value, options)?;
+                    Ok(Output { text, relaxations, ..Output::default() })
+                },
+            ),
+            Task::JsonEscape { input, source, options, lenient } => staged(
+                progress,
+                || parse_json(input, *source, &options.table, *lenient),
+                |(value, relaxations)| {
+                    let text = stringified::escape(&value, options)?;
+                    Ok(Output { text, relaxations, ..Output::default() })
+                },
+            ),
+            Task::JsonUnescape { input, format_type, options } => staged(
+                progress,
+                || {
+                    stringified::unescape(input).map_err(|e| TaskError {
+                        message: e.to_string(),
+                        location: e.location(),
+                    })
+                },
+                |value| Ok(json::format_value(&value, *format_type, options)?.into()),
+            ),
+            Task::JsonUnwrap { input, source, format_type, options, lenient } => staged(
+                progress,
+                || parse_json(input, *source, &options.table, *lenient),
+                |(value, relaxations)| {
+                    let (value, expanded) = stringified::unwrap(value);
+                    let text = json::format_value(&value, *format_type, options)?;
+                    Ok(Output {
+                        text,
+                        relaxations,
+                        expanded: expanded.iter().map(ToString::to_string).collect(),
+                        ..Output::default()
+                    })
+                },
+            ),
+            Task::JsonRepair { input, format_type, options } => staged(
+                progress,
+                || {
+                    let repaired = repair::repair(input).map_err(|e| TaskError {
+                        message: e.message,
+                        location: e.location,
+                    })?;
+                    Ok((json::parse(&repaired.text)?, repaired.repairs))
+                },
+                |(value, repairs)| {
+                    Ok(Output {
+                        text: json::format_value(&value, *format_type, options)?,
+                        repairs,
+                        ..Output::default()
+                    })
+                },
+            ),
+            Task::Base64Encode { input } => Ok(base64::encode_with_progress(input, progress).into()),
+            Task::Base64Decode { input } => base64::decode_with_progress(input, progress)
+                .map(Output::from)
+                .map_err(|e| TaskError::from(e.to_string())),
+        }
+    }
+}
+
+/// Progress once a JSON task has read its input; writing the output takes the rest.
+const PARSED: u8 = 50;
+
+/// Runs a JSON task in two stages, reporting progress after each.
+fn staged<T>(
+    mut progress: impl FnMut(u8),
+    parse: impl FnOnce() -> Result<T, TaskError>,
+    write: impl FnOnce(T) -> Result<Output, TaskError>,
+) -> Result<Output, TaskError> {
+    progress(0);
+    let result = parse().and_then(|parsed| {
+        progress(PARSED);
+        write(parsed)
+    });
+    progress(100);
+    result
+}
+
+/// Reads the input in its source format; `lenient` only applies to JSON and
+/// `table` only to CSV.
+fn parse_json(input: &str, source: SourceFormat, table: &TableOptions, lenient: bool) -> Result<(serde_json::Value, Vec<Relaxation>), TaskError> {
+    if source != SourceFormat::Json {
+        return convert::parse(input, source, table)
+            .map(|value| (value, Vec::new()))
+            .map_err(|e| TaskError {
+                message: e.to_string(),
+                location: e.location,
+            });
+    }
+    let parsed = if lenient {
+        json::parse_lenient(input)
+    } else {
+        json::parse(input).map(|value| (value, Vec::new()))
+    };
+    parsed.map_err(|e| TaskError {
+        message: e.to_string(),
+        location: e.location(input),
+    })
+}
+
+/// Result of a successful task.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Output {
+    pub text: String,
+    /// Relaxations a lenient JSON task relied on; empty for other tasks.
+    pub relaxations: Vec<Relaxation>,
+    /// JSONPaths of the stringified fields an unwrap task expanded.
+    #[serde(default)]
+    pub expanded: Vec<String>,
+    /// Changes a repair task made to the input.
+    #[serde(default)]
+    pub repairs: Vec<Repair>,
+}
+
+/// Why a task failed.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TaskError {
+    pub message: String,
+    /// Where in the input a JSON parse error occurred.
+    pub location: Option<ErrorLocation>,
+}
+
+impl From<String> for TaskError {
+    fn from(message: String) -> Self {
+        TaskError { message, location: None }
+    }
+}
+
+impl From<JsonError> for TaskError {
+    fn from(e: JsonError) -> Self {
+        e.to_string().into()
+    }
+}
+
+impl From<String> for Output {
+    fn from(text: String) -> Self {
+        Output { text, ..Output::default() }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Request {
+    pub id: u32,
+    pub task: Task,
+}
+
+impl Request {
+    /// Splits the request into its JSON envelope and the task's input.
+    pub fn into_parts(mut self) -> (String, String) {
+        let input = mem::take(self.task.input_mut());
+        (serde_json::to_string(&self).unwrap_or_default(), input)
+    }
+
+    /// Reassembles a request split by [`Request::into_parts`].
+    pub fn from_parts(envelope: &str, input: String) -> Option<Self> {
+        let mut request: Request = serde_json::from_str(envelope).ok()?;
+        *request.task.input_mut() = input;
+        Some(request)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Response {
+    Progress { id: u32, percent: u8 },
+    Done { id: u32, result: Result<Output, TaskError> },
+}
+
+impl Response {
+    /// Splits the response into its JSON envelope and the output text, which is
+    /// empty for progress reports and failures.
+    pub fn into_parts(mut self) -> (String, String) {
+        let text = match &mut self {
+            Response::Done { result: Ok(output), .. } => mem::take(&mut output.text),
+            _ => String::new(),
+        };
+        (serde_json::to_string(&self).unwrap_or_default(), text)
+    }
+
+    /// Reassembles a response split by [`Response::into_parts`].
+    pub fn from_parts(envelope: &str, text: String) -> Option<Self> {
+        let mut response: Response = serde_json::from_str(envelope).ok()?;
+        if let Response::Done { result: Ok(output), .. } = &mut response {
+            output.text = text;
+        }
+        Some(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn runs_like_the_direct_functions() {
+        let task = Task::JsonFormat {
+            input: "{\"a\":1}".into(),
+            source: SourceFormat::Json,
+            format_type: FormatType::Yaml,
+            options: FormatOptions::default(),
+            lenient: false,
+        };
+        assert_eq!(task.run(|_| ()), Ok(Output::from("a: 1\n".to_string())));
+        let task = Task::Base64Decode { input: "!".into() };
+        assert_eq!(task.run(|_| ()), Err(base64::decode("!").unwrap_err().to_string().into()));
+    }
+
+    #[test]
+    fn reports_completion() {
+        let mut reports = Vec::new();
+        let task = Task::Base64Encode { input: "hello".into() };
+        assert_eq!(task.run(|p| reports.push(p)).unwrap().text, "aGVsbG8=");
+        assert_eq!(reports, [100]);
+    }
+
+    #[test]
+    fn json_tasks_report_each_stage() {
+        let mut reports = Vec::new();
+        let task = Task::JsonFormat {
+            input: "[1]".into(),
+            source: SourceFormat::Json,
+            format_type: FormatType::Standard,
+            options: FormatOptions::default(),
+            lenient: false,
+        };
+        task.run(|p| reports.push(p)).unwrap();
+        assert_eq!(reports, [0, PARSED, 100]);
+
+        reports.clear();
+        let task = Task::JsonUnescape { input: "\"[1".into(), format_type: FormatType::Standard, options: FormatOptions::default() };
+        task.run(|p| reports.push(p)).unwrap_err();
+        assert_eq!(reports, [0, 100]);
+    }
+
+    #[test]
+    fn lenient_tasks_report_relaxations() {
+        let task = Task::JsonMinify {
+            input: "[1, 2,] // done".into(),
+            source: SourceFormat::Json,
+            options: FormatOptions::default(),
+            lenient: true,
+        };
+        let output = task.run(|_| ()).unwrap();
+        assert_eq!(output.text, "[1,2]");
+        assert_eq!(output.relaxations, [Relaxation::Comments, Relaxation::TrailingCommas]);
+
+        let strict = Task::JsonMinify {
+            input: "[1, 2,]".into(),
+            source: SourceFormat::Json,
+            options: FormatOptions::default(),
+            lenient: false,
+        };
+        let error = strict.run(|_| ()).unwrap_err();
+        assert_eq!(error.location, Some(ErrorLocation { line: 1, column: 7 }));
+    }
+
+    #[test]
+    fn reads_other_source_formats() {
+        let task = Task::JsonFormat {
+            input: "name = \"x\"\n".into(),
+            source: SourceFormat::Toml,
+            format_type: FormatType::Yaml,
+            options: FormatOptions::default(),
+            lenient: true,
+        };
+        assert_eq!(task.run(|_| ()), Ok(Output::from("name: x\n".to_string())));
+        let task = Task::JsonMinify {
+            input: "<a>\n<b></a>".into(),
+            source: SourceFormat::Xml,
+            options: FormatOptions::default(),
+            lenient: false,
+        };
+        assert_eq!(task.run(|_| ()).unwrap_err().location, Some(ErrorLocation { line: 2, column: 4 }));
+    }
+
+    #[test]
+    fn unwraps_stringified_json() {
+        let task = Task::JsonUnwrap {
+            input: r#"{"payload": "{\"a\":[1]}"}"#.into(),
+            source: SourceFormat::Json,
+            format_type: FormatType::Standard,
+            options: FormatOptions { max_width: 80, ..FormatOptions::default() },
+            lenient: false,
+        };
+        let output = task.run(|_| ()).unwrap();
+        assert_eq!(output.text, r#"{ "payload": { "a": [1] } }"#);
+        assert_eq!(output.expanded, ["$.payload"]);
+        let task = Task::JsonUnescape {
+            input: "\"[1,\n".into(),
+            format_type: FormatType::Standard,
+            options: FormatOptions::default(),
+        };
+        assert_eq!(task.run(|_| ()).unwrap_err().location, Some(ErrorLocation { line: 1, column: 4 }));
+    }
+
+    #[test]
+    fn repairs_almost_json() {
+        let task = Task::JsonRepair {
+            input: "{'a': None,".into(),
+            format_type: FormatType::Yaml,
+            options: FormatOptions::default(),
+        };
+        let output = task.run(|_| ()).unwrap();
+        assert_eq!(output.text, "a: null\n");
+        assert_eq!(output.repairs.len(), 3);
+        assert_eq!(output.repairs[2].location, ErrorLocation { line: 1, column: 12 });
+    }
+
+    #[test]
+    fn messages_round_trip_as_json() {
+        let request = Request { id: 7, task: Task::JsonMinify {
+                input: "[1, 2]".into(),
+                source: SourceFormat::Yaml,
+                options: FormatOptions::default(),
+                lenient: false,
+            } };
+        let json = serde_json::to_string(&request).unwrap();
+        assert_eq!(serde_json::from_str::<Request>(&json).unwrap(), request);
+
+        let response = Response::Done { id: 7, result: Err("bad".to_string().into()) };
+        let json = serde_json::to_string(&response).unwrap();
+        assert_eq!(serde_json::from_str::<Response>(&json).unwrap(), response);
+    }
+
+    #[test]
+    fn messages_keep_text_out_of_the_envelope() {
+        let input = "\"quoted\"\n".repeat(100);
+        let request = Request { id: 7, task: Task::Base64Encode { input: input.clone() } };
+        let (envelope, text) = request.clone().into_parts();
+        assert!(envelope.len() < 100);
+        assert_eq!(text, input);
+        assert_eq!(Request::from_parts(&envelope, text), Some(request));
+
+        let response = Response::Done { id: 7, result: Ok(Output { text: input.clone(), relaxations: vec![Relaxation::Comments], ..Output::default() }) };
+        let (envelope, text) = response.clone().into_parts();
+        assert!(envelope.len() < 100);
+        assert_eq!(Response::from_parts(&envelope, text), Some(response));
+
+        let response = Response::Progress { id: 7, percent: 30 };
+        let (envelope, text) = response.clone().into_parts();
+        assert_eq!(Response::from_parts(&envelope, text), Some(response));
+    }
+}
@@ -0,0 +1,39 @@
+use std::string::FromUtf8Error;
+
+pub fn encode(input: &str) -> String {
+    urlencoding::encode(input).into_owned()
+}
+
+pub fn decode(input: &str) -> Result<String, FromUtf8Error> {
+    urlencoding::decode(input).map(|decoded| decoded.into_owned())
+}
+
+/// Percent-encodes arbitrary bytes; non-UTF-8 bytes become `%XX` escapes.
+pub fn encode_bytes(input: &[u8]) -> String {
+    urlencoding::encode_binary(input).into_owned()
+}
+
+/// Decodes to bytes without requiring them to be UTF-8 text.
+pub fn decode_bytes(input: &str) -> Vec<u8> {
+    urlencoding::decode_binary(input.as_bytes()).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_reserved_characters() {
+        assert_eq!(encode("a b&c=d/é"), "a%20b%26c%3Dd%2F%C3%A9");
+    }
+
+    #[test]
+    fn decodes_percent_escapes() {
+        assert_eq!(decode("a%20b%26c%3Dd%2F%C3%A9").unwrap(), "a b&c=d/é");
+    }
+
+    #[test]
+    fn rejects_invalid_utf8_escapes() {
+        assert!(decode("%ff").is_err());
+    }
+}
@@ -1,4 +1,5 @@
 use leptos::*;
+use leptos_router::{use_params_map, Redirect, Route, Router, Routes, A};
 use rust_i18n::t;
 use web_sys::window;
 use std::rc::Rc;
@@ -28,35 +29,16 @@ fn main() {
     mount_to_body(App);
 }
 
-mod tools {
-    pub mod base64;
-    pub mod cron;
-    pub mod json;
-    pub mod url;
-}
+mod tools;
 
-use tools::{
-    base64::Base64Tool,
-    cron::CronTool,
-    json::JsonTool,
-    url::UrlTool,
-};
+use tools::TOOLS;
 
 mod utils;
 
 use crate::utils::I18nState;
 
-#[derive(Clone, PartialEq, Debug)]
-enum Tool {
-    UrlEncoder,
-    Base64,
-    JsonFormatter,
-    Cron,
-}
-
 #[component]
 pub fn App() -> impl IntoView {
-    let (current_tool, set_current_tool) = create_signal(Tool::UrlEncoder);
     let i18n = store_value(Rc::new(I18nState::new()));
     
     // 初始化时设置语言
@@ -80,126 +62,107 @@ pub fn App() -> impl IntoView {
     };
 
     view! {
-        <div class="min-h-screen bg-gray-50">
-            <nav class="bg-white shadow-sm">
-                <div class="max-w-7xl mx-auto px-4 sm:px-6 lg:px-8">
-                    <div class="flex justify-between h-16">
-                        <div class="flex-shrink-0 flex items-center">
-                            <span class="text-xl font-bold text-gray-900">
-                                {move || {
-                                    let _ = current_locale.get();  // 使用 memo
-                                    t!("app.title")
-                                }}
-                            </span>
-                        </div>
-                        <div class="flex space-x-4">
-                            <button 
-                                class=move || {
-                                    if current_tool.get() == Tool::UrlEncoder {
-                                        "px-3 py-2 text-sm font-medium text-blue-600 border-b-2 border-blue-600"
-                                    } else {
-                                        "px-3 py-2 text-sm font-medium text-gray-500 hover:text-blue-600"
-                                    }
-                                }
-                                on:click=move |_| set_current_tool.set(Tool::UrlEncoder)
-                            >
-                                {move || {
-                                    let _ = current_locale.get();  // 使用 memo
-                                    t!("nav.url_encoder")
-                                }}
-                            </button>
-                            <button 
-                                class=move || {
-                                    if current_tool.get() == Tool::Base64 {
-                                        "px-3 py-2 text-sm font-medium text-blue-600 border-b-2 border-blue-600"
-                                    } else {
-                                        "px-3 py-2 text-sm font-medium text-gray-500 hover:text-blue-600"
-                                    }
-                                }
-                                on:click=move |_| set_current_tool.set(Tool::Base64)
-                            >
-                                {move || {
-                                    let _ = current_locale.get();  // 使用 memo
-                                    t!("nav.base64")
-                                }}
-                            </button>
-                            <button 
-                                class=move || {
-                                    if current_tool.get() == Tool::JsonFormatter {
-                                        "px-3 py-2 text-sm font-medium text-blue-600 border-b-2 border-blue-600"
-                                    } else {
-                                        "px-3 py-2 text-sm font-medium text-gray-500 hover:text-blue-600"
-                                    }
-                                }
-                                on:click=move |_| set_current_tool.set(Tool::JsonFormatter)
-                            >
-                                {move || {
-                                    let _ = current_locale.get();  // 使用 memo
-                                    t!("nav.json_formatter")
-                                }}
-                            </button>
-                            <button 
-                                class=move || {
-                                    if current_tool.get() == Tool::Cron {
-                                        "px-3 py-2 text-sm font-medium text-blue-600 border-b-2 border-blue-600"
-                                    } else {
-                                        "px-3 py-2 text-sm font-medium text-gray-500 hover:text-blue-600"
-                                    }
-                                }
-                                on:click=move |_| set_current_tool.set(Tool::Cron)
-                            >
-                                {move || {
-                                    let _ = current_locale.get();  // 使用 memo
-                                    t!("nav.cron")
-                                }}
-                            </button>
-                        </div>
-                        <div class="ml-4 flex items-center">
-                            <button
-                                class=move || {
-                                    if current_locale.get() == "en" {  // 使用 memo
-                                        "px-3 py-2 text-sm font-medium text-blue-600 border-b-2 border-blue-600"
-                                    } else {
-                                        "px-3 py-2 text-sm font-medium text-gray-500 hover:text-blue-600"
+        <Router>
+            <div class="min-h-screen bg-gray-50">
+                <nav class="bg-white shadow-sm">
+                    <div class="max-w-7xl mx-auto px-4 sm:px-6 lg:px-8">
+                        <div class="flex justify-between h-16">
+                            <div class="flex-shrink-0 flex items-center">
+                                <span class="text-xl font-bold text-gray-900">
+                                    {move || {
+                                        let _ = current_locale.get();  // 使用 memo
+                                        t!("app.title")
+                                    }}
+                                </span>
+                            </div>
+                            <div class="flex space-x-4">
+                                {TOOLS
+                                    .iter()
+                                    .enumerate()
+                                    .map(|(index, tool)| {
+                                        // 不同分类之间加分隔线
+                                        let new_group = index > 0 && TOOLS[index - 1].category() != tool.category();
+                                        view! {
+                                            {new_group.then(|| view! {
+                                                <span class="self-center h-6 border-l border-gray-200"></span>
+                                            })}
+                                            <A
+                                                href=tool.path()
+                                                class="inline-flex items-center px-3 py-2 text-sm font-medium text-gray-500 hover:text-blue-600 aria-[current=page]:text-blue-600 aria-[current=page]:border-b-2 aria-[current=page]:border-blue-600"
+                                            >
+                                                <svg xmlns="http://www.w3.org/2000/svg" class="h-4 w-4 mr-1" fill="none" viewBox="0 0 24 24" stroke="currentColor" stroke-width="2">
+                                                    <path stroke-linecap="round" stroke-linejoin="round" d=tool.icon() />
+                                                </svg>
+                                                {move || {
+                                                    let _ = current_locale.get();  // 使用 memo
+                                                    t!(tool.name_key())
+                                                }}
+                                            </A>
+                                        }
+                                    })
+                                    .collect_view()}
+                            </div>
+                            <div class="ml-4 flex items-center">
+                                <button
+                                    class=move || {
+                                        if current_locale.get() == "en" {  // 使用 memo
+                                            "px-3 py-2 text-sm font-medium text-blue-600 border-b-2 border-blue-600"
+                                        } else {
+                                            "px-3 py-2 text-sm font-medium text-gray-500 hover:text-blue-600"
+                                        }
                                     }
-                                }
-                                on:click=move |_| switch_locale("en")
-                            >
-                                "EN"
-                            </button>
-                            <button
-                                class=move || {
-                                    if current_locale.get() == "zh" {  // 使用 memo
-                                        "px-3 py-2 text-sm font-medium text-blue-600 border-b-2 border-blue-600"
-                                    } else {
-                                        "px-3 py-2 text-sm font-medium text-gray-500 hover:text-blue-600"
+                                    on:click=move |_| switch_locale("en")
+                                >
+                                    "EN"
+                                </button>
+                                <button
+                                    class=move || {
+                                        if current_locale.get() == "zh" {  // 使用 memo
+                                            "px-3 py-2 text-sm font-medium text-blue-600 border-b-2 border-blue-600"
+                                        } else {
+                                            "px-3 py-2 text-sm font-medium text-gray-500 hover:text-blue-600"
+                                        }
                                     }
-                                }
-                                on:click=move |_| switch_locale("zh")
-                            >
-                                "中"
-                            </button>
+                                    on:click=move |_| switch_locale("zh")
+                                >
+                                    "中"
+                                </button>
+                            </div>
                         </div>
                     </div>
-                </div>
-            </nav>
-            <main class="max-w-7xl mx-auto py-6 sm:px-6 lg:px-8">
-                <div class="px-4 py-6 sm:px-0">
-                    <div class="bg-white rounded-lg shadow">
-                        {move || {
-                            let _ = current_locale.get();  // 使用 memo
-                            let _ = current_tool.get();   
-                            let i18n_value = i18n.get_value();  // 获取实际的 Rc<I18nState>
-                            match current_tool.get() {
-                                Tool::UrlEncoder => view! { <UrlTool i18n=i18n_value.clone()/> },
-                                Tool::Base64 => view! { <Base64Tool i18n=i18n_value.clone()/> },
-                                Tool::JsonFormatter => view! { <JsonTool i18n=i18n_value.clone()/> },
-                                Tool::Cron => view! { <CronTool i18n=i18n_value.clone()/> },
-                            }
-                        }}
+                </nav>
+                <main class="max-w-7xl mx-auto py-6 sm:px-6 lg:px-8">
+                    <div class="px-4 py-6 sm:px-0">
+                        <div class="bg-white rounded-lg shadow">
+                            <Routes>
+                                <Route
+                                    path="/"
+                                    view=|| view! { <Redirect path=TOOLS[0].path()/> }
+                                />
+                                <Route
+                                    path="/:tool"
+                                    view=move || view! { <ToolPage i18n=i18n current_locale=current_locale/> }
+                                />
+                            </Routes>
+                        </div>
                     </div>
-                </div>
-            </main>
-        </div>
+                </main>
+            </div>
+        </Router>
+    }
+}
+
+/// 根据路由参数渲染对应的工具，未知的工具跳转到默认工具
+#[component]
+fn ToolPage(i18n: StoredValue<Rc<I18nState>>, current_locale: Memo<String>) -> impl IntoView {
+    let params = use_params_map();
+
+    move || {
+        let _ = current_locale.get();  // 使用 memo
+        let i18n_value = i18n.get_value();  // 获取实际的 Rc<I18nState>
+        match params.with(|params| params.get("tool").and_then(|id| tools::find_tool(id))) {
+            Some(tool) => tool.view(i18n_value),
+            None => view! { <Redirect path=TOOLS[0].path()/> }.into_view(),
+        }
     }
 }
\ No newline at end of file
@@ -1,37 +1,98 @@
 use leptos::*;
 use rust_i18n::t;
-use base64::{Engine as _, engine::general_purpose::STANDARD};
+use dev_tools_core::task::{Output, Task, TaskError};
+use crate::tools::{DevTool, ToolCategory};
 use crate::utils::{copy_to_clipboard, I18nState};
+use crate::utils::history::{load_draft, save_draft, HistoryDrawer, ToolHistory};
+use crate::utils::share::{read_shared_state, ShareButton, SharedState};
+use crate::utils::worker::{TaskProgress, TaskRunner};
 use std::rc::Rc;
 
+const ID: &str = "base64";
+
+pub struct Base64;
+
+impl DevTool for Base64 {
+    fn id(&self) -> &'static str {
+        ID
+    }
+
+    fn name_key(&self) -> &'static str {
+        "nav.base64"
+    }
+
+    fn category(&self) -> ToolCategory {
+        ToolCategory::Encoding
+    }
+
+    fn icon(&self) -> &'static str {
+        "M7 20l4-16m2 16l4-16M6 9h14M4 15h14"
+    }
+
+    fn view(&self, i18n: Rc<I18nState>) -> View {
+        view! { <Base64Tool i18n=i18n/> }.into_view()
+    }
+}
+
 #[component]
 pub fn Base64Tool(i18n: Rc<I18nState>) -> impl IntoView {
     let current_locale = i18n.locale();
     
-    let (input, set_input) = create_signal(String::new());
+    let shared = read_shared_state();
+    let (input, set_input) = create_signal(
+        shared
+            .as_ref()
+            .map(|state| state.input.clone())
+            .or_else(|| load_draft(ID))
+            .unwrap_or_default(),
+    );
+    let history = ToolHistory::new(ID);
+    create_effect(move |_| save_draft(ID, &input.get()));
     let (output, set_output) = create_signal(String::new());
     let (error, set_error) = create_signal(String::new());
+    // 最近一次执行的操作，分享时一并带上
+    let (last_action, set_last_action) = create_signal("encode");
 
-    let encode = move |_| {
-        let input_text = input.get();
-        let encoded = STANDARD.encode(input_text.as_bytes());
-        set_output.set(encoded);
-        set_error.set(String::new());
+    let runner = TaskRunner::new();
+    let show_result = move |result: Result<Output, TaskError>| match result {
+        Ok(output) => {
+            set_output.set(output.text);
+            set_error.set(String::new());
+        }
+        Err(e) => set_error.set(e.message),
+    };
+
+    let share_state = move || {
+        SharedState::new(input.get_untracked())
+            .with_option("action", last_action.get_untracked())
     };
 
-    let decode = move |_| {
-        let input_text = input.get();
-        match STANDARD.decode(input_text.as_bytes()) {
-            Ok(decoded) => {
-                match String::from_utf8(decoded) {
-                    Ok(text) => {
-                        set_output.set(text);
-                        set_error.set(String::new());
-                    }
-                    Err(e) => set_error.set(e.to_string()),
-                }
-            }
-            Err(e) => set_error.set(e.to_string()),
+    let encode = move || {
+        set_last_action.set("encode");
+        history.record(share_state());
+        runner.run(Task::Base64Encode { input: input.get() }, show_result);
+    };
+
+    let decode = move || {
+        set_last_action.set("decode");
+        history.record(share_state());
+        runner.run(Task::Base64Decode { input: input.get() }, show_result);
+    };
+
+    // 通过分享链接打开时，直接显示对方看到的结果
+    if let Some(state) = &shared {
+        match state.option("action") {
+            Some("encode") => encode(),
+            Some("decode") => decode(),
+            _ => {}
+        }
+    }
+
+    let restore = move |state: SharedState| {
+        set_input.set(state.input.clone());
+        match state.option("action") {
+            Some("decode") => decode(),
+            _ => encode(),
         }
     };
 
@@ -58,8 +119,9 @@ pub fn Base64Tool(i18n: Rc<I18nState>) -> impl IntoView {
 
                 <div class="flex flex-wrap gap-2">
                     <button
-                        class="inline-flex items-center px-4 py-2 border border-transparent text-sm font-medium rounded-md shadow-sm text-white bg-blue-600 hover:bg-blue-700 focus:outline-none focus:ring-2 focus:ring-offset-2 focus:ring-blue-500"
-                        on:click=encode
+                        class="inline-flex items-center px-4 py-2 border border-transparent text-sm font-medium rounded-md shadow-sm text-white bg-blue-600 hover:bg-blue-700 disabled:opacity-50 focus:outline-none focus:ring-2 focus:ring-offset-2 focus:ring-blue-500"
+                        prop:disabled=move || runner.busy()
+                        on:click=move |_| encode()
                     >
                         {move || {
                             let _ = current_locale.get();
@@ -67,16 +129,21 @@ pub fn Base64Tool(i18n: Rc<I18nState>) -> impl IntoView {
                         }}
                     </button>
                     <button
-                        class="inline-flex items-center px-4 py-2 border border-transparent text-sm font-medium rounded-md shadow-sm text-white bg-blue-600 hover:bg-blue-700 focus:outline-none focus:ring-2 focus:ring-offset-2 focus:ring-blue-500"
-                        on:click=decode
+                        class="inline-flex items-center px-4 py-2 border border-transparent text-sm font-medium rounded-md shadow-sm text-white bg-blue-600 hover:bg-blue-700 disabled:opacity-50 focus:outline-none focus:ring-2 focus:ring-offset-2 focus:ring-blue-500"
+                        prop:disabled=move || runner.busy()
+                        on:click=move |_| decode()
                     >
                         {move || {
                             let _ = current_locale.get();
                             t!("tools.base64.decode")
                         }}
                     </button>
+                    <ShareButton state=share_state current_locale=current_locale/>
+                    <HistoryDrawer history=history current_locale=current_locale on_restore=restore/>
                 </div>
 
+                <TaskProgress runner=runner current_locale=current_locale/>
+
                 <Show
                     when=move || !error.get().is_empty()
                     fallback=|| view! { }
@@ -104,7 +171,7 @@ pub fn Base64Tool(i18n: Rc<I18nState>) -> impl IntoView {
                             <button
                                 class="inline-flex items-center px-3 py-1 border border-gray-300 shadow-sm text-sm leading-4 font-medium rounded-md text-gray-700 bg-white hover:bg-gray-50 focus:outline-none focus:ring-2 focus:ring-offset-2 focus:ring-blue-500"
                                 on:click=move |_| {
-                                    let _ = copy_to_clipboard(&output.get());
+                                    copy_to_clipboard(&output.get());
                                 }
                             >
                                 {move || {
@@ -1,117 +1,99 @@
 use leptos::*;
-use crate::utils::{copy_to_clipboard, I18nState};
-use chrono::{DateTime, Local};
-use cron::Schedule;
-use std::str::FromStr;
+use crate::tools::{DevTool, ToolCategory};
+use crate::utils::{copy_to_clipboard, use_query_setter, I18nState};
+use crate::utils::history::{load_draft, save_draft, HistoryDrawer, ToolHistory};
+use crate::utils::share::{initial_option, read_shared_state, ShareButton, SharedState};
+use dev_tools_core::cron::{
+    describe_cron, get_next_occurrences, parse_cron, Field, FieldPattern, OutputFormat,
+};
 use rust_i18n::t;
 use std::rc::Rc;
 
-#[derive(Clone, Copy, Debug, PartialEq)]
-pub enum OutputFormat {
-    Default,     // 2024-01-01 12:00:00
-    ISO8601,     // 2024-01-01T12:00:00+08:00
-    Unix,        // 1704085200
+fn field_label(field: Field) -> String {
+    match field {
+        Field::Second => t!("tools.cron.field.second"),
+        Field::Minute => t!("tools.cron.field.minute"),
+        Field::Hour => t!("tools.cron.field.hour"),
+        Field::Day => t!("tools.cron.field.day"),
+        Field::Month => t!("tools.cron.field.month"),
+        Field::Week => t!("tools.cron.field.week"),
+    }
 }
 
-fn format_time(time: DateTime<Local>, format: OutputFormat) -> String {
-    match format {
-        OutputFormat::Default => time.format("%Y-%m-%d %H:%M:%S").to_string(),
-        OutputFormat::ISO8601 => time.to_rfc3339(),
-        OutputFormat::Unix => time.timestamp().to_string(),
+fn describe_pattern(pattern: &FieldPattern) -> String {
+    match pattern {
+        FieldPattern::Every => t!("tools.cron.field.every"),
+        FieldPattern::Any => t!("tools.cron.field.any"),
+        FieldPattern::Step(step) => format!("{} {}", t!("tools.cron.field.every"), step),
+        FieldPattern::Range(from, to) => format!("{} {} {}", t!("tools.cron.field.from"), from, to),
+        FieldPattern::List(values) => format!("{} [{}]", t!("tools.cron.field.specific"), values),
+        FieldPattern::Value(value) => value.clone(),
     }
 }
 
-fn parse_cron(expr: &str, include_seconds: bool) -> Result<Schedule, String> {
-    // 如果不包含秒，在表达式前添加 "0 "
-    let expr = if !include_seconds && !expr.trim().is_empty() {
-        format!("0 {}", expr)
-    } else {
-        expr.to_string()
-    };
-    
-    Schedule::from_str(&expr).map_err(|e| e.to_string())
+fn describe(expr: &str, include_seconds: bool) -> String {
+    match describe_cron(expr, include_seconds) {
+        Ok(fields) => fields
+            .iter()
+            .map(|desc| format!("{}: {}", field_label(desc.field), describe_pattern(&desc.pattern)))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        Err(_) => t!("tools.cron.invalid_expression").to_string(),
+    }
 }
 
-fn get_next_occurrences(schedule: &Schedule, count: usize, format: OutputFormat) -> Vec<String> {
-    schedule
-        .upcoming(Local)
-        .take(count)
-        .map(|time| format_time(time, format))
-        .collect()
-}
+const ID: &str = "cron";
 
-fn describe_field(field: &str) -> String {
-    match field {
-        "*" => t!("tools.cron.field.every"),
-        "?" => t!("tools.cron.field.any"),
-        field if field.contains('/') => {
-            let parts: Vec<&str> = field.split('/').collect();
-            if parts.len() == 2 {
-                format!("{} {}", t!("tools.cron.field.every"), parts[1])
-            } else {
-                field.to_string()
-            }
-        }
-        field if field.contains('-') => {
-            let parts: Vec<&str> = field.split('-').collect();
-            if parts.len() == 2 {
-                format!("{} {} {}", t!("tools.cron.field.from"), parts[0], parts[1])
-            } else {
-                field.to_string()
-            }
-        }
-        field if field.contains(',') => {
-            format!("{} [{}]", t!("tools.cron.field.specific"), field)
-        }
-        _ => field.to_string()
-    }
-}
+pub struct Cron;
 
-fn describe_cron(expr: &str, include_seconds: bool) -> String {
-    let parts: Vec<&str> = expr.split_whitespace().collect();
-    let (seconds, rest) = if include_seconds {
-        (Some(parts[0]), &parts[1..])
-    } else {
-        (None, &parts[..])
-    };
+impl DevTool for Cron {
+    fn id(&self) -> &'static str {
+        ID
+    }
 
-    if rest.len() != 5 {
-        return t!("tools.cron.invalid_expression").to_string();
+    fn name_key(&self) -> &'static str {
+        "nav.cron"
     }
 
-    let mut desc = Vec::new();
-    
-    // 添加秒描述
-    if let Some(s) = seconds {
-        desc.push(format!("{}: {}", t!("tools.cron.field.second"), describe_field(s)));
+    fn category(&self) -> ToolCategory {
+        ToolCategory::Time
     }
 
-    // 添加其他字段描述
-    let fields = [
-        t!("tools.cron.field.minute"),
-        t!("tools.cron.field.hour"),
-        t!("tools.cron.field.day"),
-        t!("tools.cron.field.month"),
-        t!("tools.cron.field.week")
-    ];
-    
-    for (i, &field) in rest.iter().enumerate() {
-        desc.push(format!("{}: {}", fields[i], describe_field(field)));
+    fn icon(&self) -> &'static str {
+        "M12 8v4l3 3m6-3a9 9 0 11-18 0 9 9 0 0118 0z"
     }
 
-    desc.join("\n")
+    fn view(&self, i18n: Rc<I18nState>) -> View {
+        view! { <CronTool i18n=i18n/> }.into_view()
+    }
 }
 
 #[component]
 pub fn CronTool(i18n: Rc<I18nState>) -> impl IntoView {
     let current_locale = i18n.locale();
 
-    let (input, set_input) = create_signal("* * * * *".to_string());
+    let shared = read_shared_state();
+    let (input, set_input) = create_signal(
+        shared
+            .as_ref()
+            .map(|state| state.input.clone())
+            .or_else(|| load_draft(ID))
+            .unwrap_or_else(|| "* * * * *".to_string()),
+    );
+    let history = ToolHistory::new(ID);
+    create_effect(move |_| save_draft(ID, &input.get()));
     let (description, set_description) = create_signal(String::new());
     let (next_times, set_next_times) = create_signal(Vec::new());
     let (error, set_error) = create_signal(String::new());
-    let (include_seconds, set_include_seconds) = create_signal(false);
-    let (output_format, set_output_format) = create_signal(OutputFormat::Default);
+    let (include_seconds, set_include_seconds) = create_signal(
+        initial_option(&shared, "seconds").is_some_and(|value| value == "true"),
+    );
+    let (output_format, set_output_format) = create_signal(
+        initial_option(&shared, "output")
+            .and_then(|value| OutputFormat::from_param(&value))
+            .unwrap_or(OutputFormat::Default),
+    );
+    let set_query = use_query_setter();
 
     let parse_cron_expr = move || {
         let input_text = input.get();
@@ -130,7 +112,7 @@ pub fn CronTool(i18n: Rc<I18nState>) -> impl IntoView {
         match parse_cron(&input_text, include_seconds.get()) {
             Ok(schedule) => {
                 // 设置描述
-                set_description.set(describe_cron(&input_text, include_seconds.get()));
+                set_description.set(describe(&input_text, include_seconds.get()));
                 
                 // 获取接下来的执行时间
                 let times = get_next_occurrences(&schedule, 5, output_format.get());
@@ -142,7 +124,14 @@ pub fn CronTool(i18n: Rc<I18nState>) -> impl IntoView {
         }
     };
 
+    let share_state = move || {
+        SharedState::new(input.get_untracked())
+            .with_option("seconds", include_seconds.get_untracked().to_string())
+            .with_option("output", output_format.get_untracked().as_param())
+    };
+
     let handle_click = move |_: web_sys::MouseEvent| {
+        history.record(share_state());
         parse_cron_expr();
     };
 
@@ -151,6 +140,18 @@ pub fn CronTool(i18n: Rc<I18nState>) -> impl IntoView {
         parse_cron_expr();
     });
 
+    // 选项变化后自动重新解析
+    let restore = move |state: SharedState| {
+        set_input.set(state.input.clone());
+        let seconds = state.option("seconds") == Some("true");
+        set_include_seconds.set(seconds);
+        set_query.call(("seconds", seconds.to_string()));
+        if let Some(format) = state.option("output").and_then(OutputFormat::from_param) {
+            set_output_format.set(format);
+            set_query.call(("output", format.as_param().to_string()));
+        }
+    };
+
     view! {
         <div class="flex flex-col gap-4 p-4">
             <h2 class="text-2xl font-bold text-gray-800">{move || {
@@ -177,7 +178,9 @@ pub fn CronTool(i18n: Rc<I18nState>) -> impl IntoView {
                             class="h-4 w-4 text-blue-600 border-gray-300 rounded focus:ring-blue-500"
                             prop:checked=move || include_seconds.get()
                             on:change=move |ev| {
-                                set_include_seconds.set(event_target_checked(&ev));
+                                let checked = event_target_checked(&ev);
+                                set_include_seconds.set(checked);
+                                set_query.call(("seconds", checked.to_string()));
                             }
                         />
                         <label class="ml-2 text-sm text-gray-700">
@@ -189,13 +192,11 @@ pub fn CronTool(i18n: Rc<I18nState>) -> impl IntoView {
                     </div>
                     <select
                         class="px-3 py-2 border border-gray-300 rounded-lg focus:outline-none focus:ring-2 focus:ring-blue-500 focus:border-blue-500"
+                        prop:value=move || output_format.get().as_param()
                         on:change=move |ev| {
-                            let value = event_target_value(&ev);
-                            match value.as_str() {
-                                "default" => set_output_format.set(OutputFormat::Default),
-                                "iso8601" => set_output_format.set(OutputFormat::ISO8601),
-                                "unix" => set_output_format.set(OutputFormat::Unix),
-                                _ => (),
+                            if let Some(format) = OutputFormat::from_param(&event_target_value(&ev)) {
+                                set_output_format.set(format);
+                                set_query.call(("output", format.as_param().to_string()));
                             }
                         }
                     >
@@ -221,6 +222,8 @@ pub fn CronTool(i18n: Rc<I18nState>) -> impl IntoView {
                             t!("tools.cron.parse")
                         }}
                     </button>
+                    <ShareButton state=share_state current_locale=current_locale/>
+                    <HistoryDrawer history=history current_locale=current_locale on_restore=restore/>
                 </div>
             </div>
             
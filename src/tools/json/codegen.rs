@@ -0,0 +1,134 @@
+use leptos::*;
+use rust_i18n::t;
+use dev_tools_core::infer::{generate, parse_samples, InferOptions, Target};
+use crate::utils::copy_to_clipboard;
+use super::parse_input;
+
+/// 从工具主输入中的一个或多个样本推断类型，生成 JSON Schema、Rust 或 TypeScript 代码
+#[component]
+pub fn JsonCodegen(
+    samples: ReadSignal<String>,
+    lenient: ReadSignal<bool>,
+    target: ReadSignal<Target>,
+    #[prop(into)] on_target: Callback<Target>,
+    root_name: ReadSignal<String>,
+    #[prop(into)] on_root_name: Callback<String>,
+    detect_enums: ReadSignal<bool>,
+    #[prop(into)] on_detect_enums: Callback<bool>,
+    current_locale: RwSignal<String>,
+) -> impl IntoView {
+    let code = create_memo(move |_| {
+        samples.with(|text| {
+            if text.trim().is_empty() {
+                return None;
+            }
+            // 宽松模式只接受单个文档，严格模式可以是多个以空白分隔的文档
+            let parsed = if lenient.get() {
+                parse_input(text, true).map(|sample| vec![sample])
+            } else {
+                parse_samples(text)
+            };
+            let options = InferOptions {
+                root_name: root_name.get(),
+                detect_enums: detect_enums.get(),
+            };
+            Some(parsed.map(|samples| generate(&samples, target.get(), &options)).map_err(|e| e.to_string()))
+        })
+    });
+
+    let tab_class = move |active: bool| {
+        format!(
+            "px-3 py-1 text-sm font-medium rounded-md {}",
+            if active { "bg-blue-100 text-blue-700" } else { "text-gray-700 hover:bg-gray-100" }
+        )
+    };
+
+    view! {
+        <div class="space-y-4">
+            <div class="flex flex-wrap items-center gap-4">
+                <div class="flex items-center gap-1">
+                    <span class="mr-1 text-sm text-gray-700">
+                        {move || {
+                            let _ = current_locale.get();
+                            t!("tools.json.codegen.target")
+                        }}
+                    </span>
+                    {Target::ALL
+                        .into_iter()
+                        .map(|value| view! {
+                            <button class=move || tab_class(target.get() == value) on:click=move |_| on_target.call(value)>
+                                {move || {
+                                    let _ = current_locale.get();
+                                    t!(&format!("tools.json.codegen.targets.{}", value.as_param()))
+                                }}
+                            </button>
+                        })
+                        .collect_view()}
+                </div>
+                <label class="flex items-center gap-2 text-sm text-gray-700">
+                    {move || {
+                        let _ = current_locale.get();
+                        t!("tools.json.codegen.root_name")
+                    }}
+                    <input
+                        type="text"
+                        class="w-32 px-2 py-1 border border-gray-300 rounded-md font-mono text-sm focus:outline-none focus:ring-2 focus:ring-blue-500 focus:border-blue-500"
+                        on:change=move |ev| on_root_name.call(event_target_value(&ev))
+                        prop:value=root_name
+                    />
+                </label>
+                <div class="flex items-center">
+                    <input
+                        type="checkbox"
+                        class="h-4 w-4 text-blue-600 border-gray-300 rounded focus:ring-blue-500"
+                        prop:checked=move || detect_enums.get()
+                        on:change=move |ev| on_detect_enums.call(event_target_checked(&ev))
+                    />
+                    <label class="ml-2 text-sm text-gray-700">
+                        {move || {
+                            let _ = current_locale.get();
+                            t!("tools.json.codegen.detect_enums")
+                        }}
+                    </label>
+                </div>
+            </div>
+            <p class="text-xs text-gray-500">
+                {move || {
+                    let _ = current_locale.get();
+                    t!("tools.json.codegen.hint")
+                }}
+            </p>
+
+            {move || {
+                let _ = current_locale.get();
+                code.get().map(|result| match result {
+                    Err(message) => view! {
+                        <div class="p-4 bg-red-50 border border-red-200 rounded-lg text-red-700 whitespace-pre-wrap">
+                            {format!("{}: {}", t!("tools.json.codegen.invalid_input"), message)}
+                        </div>
+                    }
+                    .into_view(),
+                    Ok(code) => {
+                        let copied = code.clone();
+                        view! {
+                            <div>
+                                <div class="flex justify-end mb-2">
+                                    <button
+                                        class="inline-flex items-center px-3 py-1 border border-gray-300 shadow-sm text-sm leading-4 font-medium rounded-md text-gray-700 bg-white hover:bg-gray-50 focus:outline-none focus:ring-2 focus:ring-offset-2 focus:ring-blue-500"
+                                        on:click=move |_| copy_to_clipboard(&copied)
+                                    >
+                                        {t!("tools.json.codegen.copy")}
+                                    </button>
+                                </div>
+                                <pre class="w-full max-h-96 p-2 bg-gray-50 border border-gray-200 rounded-md overflow-auto font-mono text-sm">
+                                    {code}
+                                </pre>
+                            </div>
+                        }
+                        .into_view()
+                    }
+                })
+            }}
+        </div>
+    }
+}
@@ -0,0 +1,182 @@
+use leptos::*;
+use rust_i18n::t;
+use dev_tools_core::diff::{diff, to_patch, Change, DiffOptions};
+use dev_tools_core::json::{format_value, FormatOptions, FormatType};
+use crate::utils::copy_to_clipboard;
+use super::{compact, parse_input};
+
+/// 结构化比较两份 JSON：左侧是工具的主输入，右侧是第二个输入框
+#[component]
+pub fn JsonDiff(
+    left: ReadSignal<String>,
+    right: RwSignal<String>,
+    lenient: ReadSignal<bool>,
+    ignore_array_order: ReadSignal<bool>,
+    #[prop(into)] on_ignore_array_order: Callback<bool>,
+    current_locale: RwSignal<String>,
+) -> impl IntoView {
+    // 并排视图或 JSON Patch 导出
+    let (show_patch, set_show_patch) = create_signal(false);
+    let changes = create_memo(move |_| {
+        let (left, right) = (left.get(), right.get());
+        if left.trim().is_empty() || right.trim().is_empty() {
+            return None;
+        }
+        let lenient = lenient.get();
+        let left = parse_input(&left, lenient).map_err(|e| ("left", e.to_string()));
+        let right = parse_input(&right, lenient).map_err(|e| ("right", e.to_string()));
+        let options = DiffOptions {
+            ignore_array_order: ignore_array_order.get(),
+        };
+        Some(left.and_then(|left| right.map(|right| diff(&left, &right, &options))))
+    });
+    let patch = move || {
+        changes.with(|changes| match changes {
+            Some(Ok(changes)) => {
+                format_value(&to_patch(changes), FormatType::Standard, &FormatOptions::default()).unwrap_or_default()
+            }
+            _ => String::new(),
+        })
+    };
+
+    let row = |change: Change| {
+        let (class, sign) = match change {
+            Change::Added { .. } => ("bg-green-50 text-green-800", "+"),
+            Change::Removed { .. } => ("bg-red-50 text-red-800", "-"),
+            Change::Changed { .. } => ("bg-yellow-50 text-yellow-800", "~"),
+        };
+        view! {
+            <tr class=class>
+                <td class="px-2 py-1 w-4 align-top select-none">{sign}</td>
+                <td class="px-2 py-1 align-top text-purple-800 break-all">{change.path().to_string()}</td>
+                <td class="px-2 py-1 align-top break-all">{change.left().map(compact)}</td>
+                <td class="px-2 py-1 align-top break-all">{change.right().map(compact)}</td>
+            </tr>
+        }
+    };
+
+    let tab_class = move |active: bool| {
+        format!(
+            "px-3 py-1 text-sm font-medium rounded-md {}",
+            if active { "bg-blue-100 text-blue-700" } else { "text-gray-700 hover:bg-gray-100" }
+        )
+    };
+
+    view! {
+        <div class="space-y-4">
+            <div>
+                <label class="block text-sm font-medium text-gray-700 mb-2">
+                    {move || {
+                        let _ = current_locale.get();
+                        t!("tools.json.diff.right")
+                    }}
+                </label>
+                <textarea
+                    class="w-full h-48 p-2 border border-gray-300 rounded-md shadow-sm focus:ring-blue-500 focus:border-blue-500"
+                    placeholder=move || {
+                        let _ = current_locale.get();
+                        t!("tools.json.diff.right_placeholder")
+                    }
+                    on:input=move |ev| right.set(event_target_value(&ev))
+                    prop:value=right
+                ></textarea>
+            </div>
+
+            <div class="flex flex-wrap items-center gap-4">
+                <div class="flex items-center">
+                    <input
+                        type="checkbox"
+                        class="h-4 w-4 text-blue-600 border-gray-300 rounded focus:ring-blue-500"
+                        prop:checked=move || ignore_array_order.get()
+                        on:change=move |ev| on_ignore_array_order.call(event_target_checked(&ev))
+                    />
+                    <label class="ml-2 text-sm text-gray-700">
+                        {move || {
+                            let _ = current_locale.get();
+                            t!("tools.json.diff.ignore_array_order")
+                        }}
+                    </label>
+                </div>
+                <div class="flex gap-1">
+                    <button class=move || tab_class(!show_patch.get()) on:click=move |_| set_show_patch.set(false)>
+                        {move || {
+                            let _ = current_locale.get();
+                            t!("tools.json.diff.side_by_side")
+                        }}
+                    </button>
+                    <button class=move || tab_class(show_patch.get()) on:click=move |_| set_show_patch.set(true)>
+                        {move || {
+                            let _ = current_locale.get();
+                            t!("tools.json.diff.patch")
+                        }}
+                    </button>
+                </div>
+            </div>
+
+            {move || {
+                let _ = current_locale.get();
+                changes.get().map(|result| match result {
+                    Err((side, message)) => view! {
+                        <div class="p-4 bg-red-50 border border-red-200 rounded-lg text-red-700 whitespace-pre-wrap">
+                            {format!("{}: {}", t!(&format!("tools.json.diff.invalid_{}", side)), message)}
+                        </div>
+                    }
+                    .into_view(),
+                    Ok(changes) if changes.is_empty() => view! {
+                        <div class="p-4 bg-green-50 border border-green-200 rounded-lg text-green-700">
+                            {t!("tools.json.diff.identical")}
+                        </div>
+                    }
+                    .into_view(),
+                    Ok(changes) if show_patch.get() => view! {
+                        <div>
+                            <div class="flex justify-end mb-2">
+                                <button
+                                    class="inline-flex items-center px-3 py-1 border border-gray-300 shadow-sm text-sm leading-4 font-medium rounded-md text-gray-700 bg-white hover:bg-gray-50 focus:outline-none focus:ring-2 focus:ring-offset-2 focus:ring-blue-500"
+                                    on:click=move |_| copy_to_clipboard(&patch())
+                                >
+                                    {t!("tools.common.copy")}
+                                </button>
+                            </div>
+                            <pre class="w-full max-h-96 p-2 bg-gray-50 border border-gray-200 rounded-md overflow-auto whitespace-pre-wrap">
+                                {patch()}
+                            </pre>
+                            <p class="mt-1 text-xs text-gray-500">{t!("tools.json.diff.operations", count = changes.len())}</p>
+                        </div>
+                    }
+                    .into_view(),
+                    Ok(changes) => {
+                        let count = |f: fn(&Change) -> bool| changes.iter().filter(|change| f(change)).count();
+                        let summary = t!(
+                            "tools.json.diff.summary",
+                            added = count(|change| matches!(change, Change::Added { .. })),
+                            removed = count(|change| matches!(change, Change::Removed { .. })),
+                            changed = count(|change| matches!(change, Change::Changed { .. }))
+                        );
+                        view! {
+                            <div>
+                                <p class="mb-2 text-sm text-gray-600">{summary}</p>
+                                <div class="max-h-96 overflow-auto border border-gray-200 rounded-md">
+                                    <table class="w-full font-mono text-sm">
+                                        <thead class="bg-gray-100 font-sans text-xs text-gray-600 text-left">
+                                            <tr>
+                                                <th class="px-2 py-1"></th>
+                                                <th class="px-2 py-1">{t!("tools.json.diff.path")}</th>
+                                                <th class="px-2 py-1">{t!("tools.json.diff.left")}</th>
+                                                <th class="px-2 py-1">{t!("tools.json.diff.right")}</th>
+                                            </tr>
+                                        </thead>
+                                        <tbody class="divide-y divide-gray-200">
+                                            {changes.into_iter().map(row).collect_view()}
+                                        </tbody>
+                                    </table>
+                                </div>
+                            </div>
+                        }
+                        .into_view()
+                    }
+                })
+            }}
+        </div>
+    }
+}
@@ -0,0 +1,94 @@
+use leptos::*;
+use rust_i18n::t;
+use dev_tools_core::table::Table;
+use std::rc::Rc;
+
+/// 表格预览最多显示的行数
+const MAX_GRID_ROWS: usize = 500;
+
+/// CSV/TSV 的表格预览，点击列名按该列排序，再次点击倒序，第三次恢复原顺序
+#[component]
+pub fn JsonGrid(table: Rc<Table>, current_locale: RwSignal<String>) -> impl IntoView {
+    // 排序的列和是否倒序
+    let (sort, set_sort) = create_signal(None::<(usize, bool)>);
+    let toggle_sort = move |column: usize| {
+        set_sort.update(|sort| {
+            *sort = match *sort {
+                Some((current, false)) if current == column => Some((column, true)),
+                Some((current, true)) if current == column => None,
+                _ => Some((column, false)),
+            }
+        });
+    };
+    let sorted = table.clone();
+    let order = create_memo(move |_| match sort.get() {
+        Some((column, descending)) => sorted.sorted_rows(column, descending),
+        None => (0..sorted.rows.len()).collect(),
+    });
+    let count = table.rows.len();
+    let hidden = count.saturating_sub(MAX_GRID_ROWS);
+    let columns = table.columns.clone();
+
+    view! {
+        <div class="border border-gray-200 rounded-md">
+            <div class="px-2 py-1 border-b border-gray-200 bg-gray-50 text-xs text-gray-500">
+                {move || {
+                    let _ = current_locale.get();
+                    format!("{} · {}", t!("tools.json.table.preview", count = count), t!("tools.json.table.sort_hint"))
+                }}
+            </div>
+            <div class="max-h-96 overflow-auto">
+                <table class="min-w-full font-mono text-sm">
+                    <thead class="sticky top-0 bg-gray-100">
+                        <tr>
+                            {columns
+                                .into_iter()
+                                .enumerate()
+                                .map(|(index, column)| view! {
+                                    <th
+                                        class="px-2 py-1 text-left font-medium text-gray-700 whitespace-nowrap cursor-pointer select-none hover:bg-gray-200"
+                                        on:click=move |_| toggle_sort(index)
+                                    >
+                                        {column}
+                                        {move || match sort.get() {
+                                            Some((current, false)) if current == index => " ▲",
+                                            Some((current, true)) if current == index => " ▼",
+                                            _ => "",
+                                        }}
+                                    </th>
+                                })
+                                .collect_view()}
+                        </tr>
+                    </thead>
+                    <tbody class="divide-y divide-gray-200">
+                        {move || {
+                            order
+                                .get()
+                                .into_iter()
+                                .take(MAX_GRID_ROWS)
+                                .map(|row| view! {
+                                    <tr class="hover:bg-gray-50">
+                                        {table.rows[row]
+                                            .iter()
+                                            .map(|cell| view! {
+                                                <td class="px-2 py-1 text-gray-800 whitespace-nowrap">{cell.clone()}</td>
+                                            })
+                                            .collect_view()}
+                                    </tr>
+                                })
+                                .collect_view()
+                        }}
+                    </tbody>
+                </table>
+            </div>
+            {move || {
+                let _ = current_locale.get();
+                (hidden > 0).then(|| view! {
+                    <p class="px-2 py-1 border-t border-gray-200 text-xs text-gray-500">
+                        {t!("tools.json.table.more", count = hidden)}
+                    </p>
+                })
+            }}
+        </div>
+    }
+}
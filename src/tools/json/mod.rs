@@ -0,0 +1,1293 @@
+use leptos::*;
+use rust_i18n::t;
+use dev_tools_core::json::{error_context, minify_value, parse, parse_lenient, ContextLine, ErrorLocation, FormatOptions, FormatType, Indent, JsonError, Relaxation};
+use dev_tools_core::convert::{self, SourceFormat};
+use dev_tools_core::infer::Target;
+use dev_tools_core::patch::PatchFormat;
+use dev_tools_core::query::{evaluate, Match};
+use dev_tools_core::schema::Draft;
+use dev_tools_core::repair::{repair, summarize, Repair};
+use dev_tools_core::stringified::{unescape, unwrap};
+use dev_tools_core::table::{to_table, ArrayMode, Delimiter, Table, TableOptions};
+use dev_tools_core::task::{Output, Task, TaskError};
+use crate::tools::{DevTool, ToolCategory};
+use crate::utils::{copy_to_clipboard, use_query_setter, I18nState};
+use crate::utils::history::{load_draft, save_draft, HistoryDrawer, ToolHistory};
+use crate::utils::share::{initial_option, read_shared_state, ShareButton, SharedState};
+use crate::utils::worker::{TaskProgress, TaskRunner, WORKER_THRESHOLD};
+use serde_json::Value;
+use std::rc::Rc;
+
+mod codegen;
+mod diff;
+mod grid;
+mod patch;
+mod schema;
+mod tree;
+
+use codegen::JsonCodegen;
+use diff::JsonDiff;
+use grid::JsonGrid;
+use patch::{JsonPatch, PatchAction};
+use schema::JsonSchema;
+use tree::JsonTree;
+
+const ID: &str = "json";
+
+/// 比较模式右侧输入的草稿
+const RIGHT_DRAFT_ID: &str = "json.right";
+
+/// 校验模式 Schema 输入的草稿
+const SCHEMA_DRAFT_ID: &str = "json.schema";
+
+/// 补丁模式补丁输入的草稿
+const PATCH_DRAFT_ID: &str = "json.patch";
+
+pub struct Json;
+
+impl DevTool for Json {
+    fn id(&self) -> &'static str {
+        ID
+    }
+
+    fn name_key(&self) -> &'static str {
+        "nav.json_formatter"
+    }
+
+    fn category(&self) -> ToolCategory {
+        ToolCategory::Formatting
+    }
+
+    fn icon(&self) -> &'static str {
+        "M10 20l4-16m4 4l4 4-4 4M6 16l-4-4 4-4"
+    }
+
+    fn view(&self, i18n: Rc<I18nState>) -> View {
+        view! { <JsonTool i18n=i18n/> }.into_view()
+    }
+}
+
+/// 错误位置前后显示的行数
+const CONTEXT_RADIUS: usize = 3;
+
+/// 行宽选项，0 表示不合并短数组和对象
+const MAX_WIDTHS: [usize; 4] = [0, 80, 100, 120];
+
+/// 查询结果最多显示的条数，复制时包含全部结果
+const MAX_QUERY_RESULTS: usize = 200;
+
+/// 格式化模式下的操作，对应分享状态中的 `action`
+const ACTIONS: [&str; 6] = ["format", "minify", "escape", "unescape", "unwrap", "repair"];
+
+/// 工具的工作模式，对应查询参数 `mode`
+#[derive(Clone, Copy, PartialEq)]
+enum Mode {
+    Format,
+    Compare,
+    Validate,
+    Generate,
+    Patch,
+}
+
+impl Mode {
+    const ALL: [Mode; 5] = [Mode::Format, Mode::Compare, Mode::Validate, Mode::Generate, Mode::Patch];
+
+    fn as_param(self) -> &'static str {
+        match self {
+            Mode::Format => "format",
+            Mode::Compare => "compare",
+            Mode::Validate => "validate",
+            Mode::Generate => "generate",
+            Mode::Patch => "patch",
+        }
+    }
+
+    fn from_param(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|mode| mode.as_param() == value)
+    }
+}
+
+/// 主线程上解析出的主输入；比较时只看是否为同一次解析，避免逐个比较整份文档
+#[derive(Clone)]
+struct Parsed(Rc<Value>);
+
+impl PartialEq for Parsed {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+/// 查询无结果的原因
+#[derive(Clone, PartialEq)]
+enum QueryFailure {
+    InvalidInput,
+    TooLarge,
+    Query(String),
+}
+
+/// 在主线程上解析输入，供树形视图、查询和比较使用
+fn parse_input(text: &str, lenient: bool) -> Result<Value, JsonError> {
+    if lenient {
+        parse_lenient(text).map(|(value, _)| value)
+    } else {
+        parse(text)
+    }
+}
+
+/// 按格式化模式所选的输入格式解析主输入，供树形视图、表格预览和查询使用
+fn parse_source(text: &str, source: SourceFormat, table: &TableOptions, lenient: bool) -> Option<Value> {
+    match source {
+        SourceFormat::Json => parse_input(text, lenient).ok(),
+        source => convert::parse(text, source, table).ok(),
+    }
+}
+
+/// 查询结果的单行表示，与 `jq -c` 一致
+fn compact(value: &Value) -> String {
+    minify_value(value, &FormatOptions::default()).unwrap_or_default()
+}
+
+/// 错误位置在文本框中的选区，文本框以 UTF-16 码元计数
+fn selection_range(text: &str, location: ErrorLocation) -> (u32, u32) {
+    let mut start = 0;
+    for (index, line) in text.split('\n').enumerate() {
+        if index + 1 < location.line {
+            start += line.encode_utf16().count() + 1;
+            continue;
+        }
+        let mut chars = line.chars().skip(location.column - 1);
+        start += line.chars().take(location.column - 1).map(char::len_utf16).sum::<usize>();
+        let end = start + chars.next().map_or(0, char::len_utf16);
+        return (start as u32, end as u32);
+    }
+    (start as u32, start as u32)
+}
+
+/// 出错行及其上下文，出错的字符高亮并在下一行用 `^` 标出
+fn context_view(lines: Vec<ContextLine>, column: usize) -> impl IntoView {
+    lines
+        .into_iter()
+        .map(|line| {
+            let number = view! {
+                <span class="inline-block w-12 pr-3 text-right text-gray-400 select-none">{line.number}</span>
+            };
+            if !line.is_error {
+                return view! { <div>{number}{line.text}</div> }.into_view();
+            }
+            let before: String = line.text.chars().take(column - 1).collect();
+            let at = line.text.chars().nth(column - 1).map_or(" ".to_string(), String::from);
+            let after: String = line.text.chars().skip(column).collect();
+            // 保留制表符，让 `^` 与出错字符对齐
+            let caret: String = before
+                .chars()
+                .map(|c| if c == '\t' { '\t' } else { ' ' })
+                .chain(std::iter::once('^'))
+                .collect();
+            view! {
+                <div class="bg-red-100">
+                    {number}{before}<span class="bg-red-500 text-white">{at}</span>{after}
+                </div>
+                <div class="text-red-600">
+                    <span class="inline-block w-12 pr-3 select-none"></span>{caret}
+                </div>
+            }
+            .into_view()
+        })
+        .collect_view()
+}
+
+#[component]
+pub fn JsonTool(i18n: Rc<I18nState>) -> impl IntoView {
+    let current_locale = i18n.locale();
+    
+    let shared = read_shared_state();
+    let (input, set_input) = create_signal(
+        shared
+            .as_ref()
+            .map(|state| state.input.clone())
+            .or_else(|| load_draft(ID))
+            .unwrap_or_default(),
+    );
+    let history = ToolHistory::new(ID);
+    let (mode, set_mode) = create_signal(
+        initial_option(&shared, "mode")
+            .and_then(|value| Mode::from_param(&value))
+            .unwrap_or(Mode::Format),
+    );
+    create_effect(move |_| save_draft(ID, &input.get()));
+    let (output, set_output) = create_signal(String::new());
+    let (error, set_error) = create_signal(String::new());
+    let (error_location, set_error_location) = create_signal(None::<ErrorLocation>);
+    let (error_lines, set_error_lines) = create_signal(Vec::<ContextLine>::new());
+    let textarea = create_node_ref::<html::Textarea>();
+    // 最近一次执行的操作，分享时一并带上
+    let (last_action, set_last_action) = create_signal("format");
+    let (format_type, set_format_type) = create_signal(
+        initial_option(&shared, "format")
+            .and_then(|value| FormatType::from_param(&value))
+            .unwrap_or(FormatType::Standard),
+    );
+    let set_query = use_query_setter();
+    let select_format = move |format_type: FormatType| {
+        set_format_type.set(format_type);
+        set_query.call(("format", format_type.as_param().to_string()));
+    };
+    // 输入格式，YAML、TOML 等先读成 JSON 再按目标格式输出
+    let (source, set_source) = create_signal(
+        initial_option(&shared, "from")
+            .and_then(|value| SourceFormat::from_param(&value))
+            .unwrap_or_default(),
+    );
+    let select_source = move |value: SourceFormat| {
+        set_source.set(value);
+        set_query.call(("from", value.as_param().to_string()));
+    };
+    // 宽松模式接受注释、尾随逗号、单引号字符串和无引号的键
+    let (lenient, set_lenient) = create_signal(
+        initial_option(&shared, "lenient").is_some_and(|value| value == "true"),
+    );
+    let select_lenient = move |checked: bool| {
+        set_lenient.set(checked);
+        set_query.call(("lenient", checked.to_string()));
+    };
+    // 缩进、行宽、排序等输出选项，每项对应一个查询参数
+    let (format_options, set_format_options) = create_signal(FormatOptions::from_params(|name| {
+        initial_option(&shared, name)
+    }));
+    let update_options = move |f: &dyn Fn(&mut FormatOptions)| {
+        set_format_options.update(|options| f(options));
+        for (name, value) in format_options.get_untracked().to_params() {
+            set_query.call((name, value));
+        }
+    };
+    let options = move || format_options.get();
+    let (relaxations, set_relaxations) = create_signal(Vec::<Relaxation>::new());
+    // 展开操作解析出的字符串字段路径，在提示和树形视图中标出
+    let (expanded, set_expanded) = create_signal(Vec::<String>::new());
+    // 修复操作对输入做的改动，按类型汇总显示
+    let (repairs, set_repairs) = create_signal(Vec::<Repair>::new());
+
+    // 输出旁的树形视图，在主线程上重新解析输入，因此默认关闭
+    let (show_tree, set_show_tree) = create_signal(
+        initial_option(&shared, "tree").is_some_and(|value| value == "true"),
+    );
+    // 交给 Web Worker 处理的大输入不在主线程上重新解析，不显示树形视图、表格预览和查询结果
+    let large_input = create_memo(move |_| input.with(|text| text.len() >= WORKER_THRESHOLD));
+    let (document, set_document) = create_signal(None::<Rc<Value>>);
+    // 主输入的解析结果，查询、树形视图和表格预览共用；只在被读取时解析，大输入不解析
+    let parsed_input = create_memo(move |_| {
+        if large_input.get() {
+            return None;
+        }
+        // 比较、校验等模式的输入总是 JSON
+        let source = if mode.get() == Mode::Format { source.get() } else { SourceFormat::Json };
+        let table = format_options.with(|options| options.table);
+        input.with(|text| parse_source(text, source, &table, lenient.get())).map(|value| Parsed(Rc::new(value)))
+    });
+    // 按最近一次操作取得输出对应的文档，反转义和展开后的文档与输出一致
+    let parse_document = move || {
+        let parsed = || parsed_input.get_untracked().map(|parsed| Value::clone(&parsed.0));
+        match last_action.get_untracked() {
+            "unescape" => input.with_untracked(|text| unescape(text).ok()),
+            "unwrap" => parsed().map(|value| unwrap(value).0),
+            "repair" => input.with_untracked(|text| repair(text).ok()).and_then(|repaired| parse(&repaired.text).ok()),
+            _ => parsed(),
+        }
+    };
+    let build_tree = move || {
+        if large_input.get_untracked() {
+            set_document.set(None);
+            return;
+        }
+        set_document.set(parse_document().map(|mut value| {
+            if format_options.with_untracked(|options| options.sort_keys) {
+                value.sort_all_objects();
+            }
+            Rc::new(value)
+        }));
+    };
+    // 输出或输入为 CSV 时，在输出下方显示可排序的表格预览
+    let uses_table = move || format_type.get() == FormatType::Csv || source.get() == SourceFormat::Csv;
+    let (grid, set_grid) = create_signal(None::<Rc<Table>>);
+    let build_grid = move || {
+        let table = format_options.with_untracked(|options| options.table);
+        let parsed = (untrack(uses_table) && !large_input.get_untracked()).then(parse_document).flatten();
+        set_grid.set(parsed.and_then(|value| to_table(&value, &table).ok()).map(Rc::new));
+    };
+    let select_show_tree = move |checked: bool| {
+        set_show_tree.set(checked);
+        set_query.call(("tree", checked.to_string()));
+        if checked && error.get_untracked().is_empty() && !output.get_untracked().is_empty() {
+            build_tree();
+        }
+    };
+
+    // 输入下方的查询框，随输入和查询实时求值
+    let (query, set_query_text) = create_signal(initial_option(&shared, "query").unwrap_or_default());
+    let select_query = move |text: String| {
+        set_query_text.set(text.clone());
+        set_query.call(("query", text));
+    };
+    let query_results = create_memo(move |_| {
+        let query = query.get();
+        if query.trim().is_empty() {
+            return None;
+        }
+        if large_input.get() {
+            return Some(Err(QueryFailure::TooLarge));
+        }
+        Some(match parsed_input.get() {
+            Some(Parsed(document)) => evaluate(&query, &document).map_err(|e| QueryFailure::Query(e.to_string())),
+            None => Err(QueryFailure::InvalidInput),
+        })
+    });
+    let copy_query_results = move || {
+        if let Some(Ok(matches)) = query_results.get_untracked() {
+            let text = matches.iter().map(|m| compact(&m.value)).collect::<Vec<_>>().join("\n");
+            copy_to_clipboard(&text);
+        }
+    };
+
+    // 比较模式的右侧文档和选项
+    let right = create_rw_signal(
+        shared
+            .as_ref()
+            .and_then(|state| state.option("right").map(str::to_string))
+            .or_else(|| load_draft(RIGHT_DRAFT_ID))
+            .unwrap_or_default(),
+    );
+    create_effect(move |_| save_draft(RIGHT_DRAFT_ID, &right.get()));
+    let (ignore_array_order, set_ignore_array_order) = create_signal(
+        initial_option(&shared, "ignore_array_order").is_some_and(|value| value == "true"),
+    );
+    let select_ignore_array_order = move |checked: bool| {
+        set_ignore_array_order.set(checked);
+        set_query.call(("ignore_array_order", checked.to_string()));
+    };
+    // 校验模式的 Schema 和草案版本，未指定版本时按 `$schema` 判断
+    let schema = create_rw_signal(
+        shared
+            .as_ref()
+            .and_then(|state| state.option("schema").map(str::to_string))
+            .or_else(|| load_draft(SCHEMA_DRAFT_ID))
+            .unwrap_or_default(),
+    );
+    create_effect(move |_| save_draft(SCHEMA_DRAFT_ID, &schema.get()));
+    let (schema_draft, set_schema_draft) = create_signal(
+        initial_option(&shared, "draft").and_then(|value| Draft::from_param(&value)),
+    );
+    let select_schema_draft = move |draft: Option<Draft>| {
+        set_schema_draft.set(draft);
+        set_query.call(("draft", draft.map_or("auto", |draft| draft.as_param()).to_string()));
+    };
+    // 生成模式的目标语言、根类型名和是否识别枚举
+    let (target, set_target) = create_signal(
+        initial_option(&shared, "target")
+            .and_then(|value| Target::from_param(&value))
+            .unwrap_or(Target::TypeScript),
+    );
+    let select_target = move |value: Target| {
+        set_target.set(value);
+        set_query.call(("target", value.as_param().to_string()));
+    };
+    let (root_name, set_root_name) = create_signal(initial_option(&shared, "root").unwrap_or_else(|| "Root".to_string()));
+    let select_root_name = move |value: String| {
+        set_root_name.set(value.clone());
+        set_query.call(("root", value));
+    };
+    let (detect_enums, set_detect_enums) = create_signal(
+        initial_option(&shared, "enums").is_none_or(|value| value == "true"),
+    );
+    let select_detect_enums = move |checked: bool| {
+        set_detect_enums.set(checked);
+        set_query.call(("enums", checked.to_string()));
+    };
+    // 补丁模式的补丁、操作和格式；生成补丁时的目标文档沿用比较模式的右侧输入
+    let patch = create_rw_signal(
+        shared
+            .as_ref()
+            .and_then(|state| state.option("patch").map(str::to_string))
+            .or_else(|| load_draft(PATCH_DRAFT_ID))
+            .unwrap_or_default(),
+    );
+    create_effect(move |_| save_draft(PATCH_DRAFT_ID, &patch.get()));
+    let (patch_action, set_patch_action) = create_signal(
+        initial_option(&shared, "patch_action")
+            .and_then(|value| PatchAction::from_param(&value))
+            .unwrap_or(PatchAction::Apply),
+    );
+    let select_patch_action = move |value: PatchAction| {
+        set_patch_action.set(value);
+        set_query.call(("patch_action", value.as_param().to_string()));
+    };
+    let (patch_format, set_patch_format) = create_signal(
+        initial_option(&shared, "patch_format")
+            .and_then(|value| PatchFormat::from_param(&value))
+            .unwrap_or_default(),
+    );
+    let select_patch_format = move |value: PatchFormat| {
+        set_patch_format.set(value);
+        set_query.call(("patch_format", value.as_param().to_string()));
+    };
+    let select_mode = move |value: Mode| {
+        set_mode.set(value);
+        set_query.call(("mode", value.as_param().to_string()));
+    };
+
+    let runner = TaskRunner::new();
+    let show_result = move |result: Result<Output, TaskError>| match result {
+        Ok(output) => {
+            set_output.set(output.text);
+            set_relaxations.set(output.relaxations);
+            set_expanded.set(output.expanded);
+            set_repairs.set(output.repairs);
+            set_error.set(String::new());
+            set_error_location.set(None);
+            if show_tree.get_untracked() {
+                build_tree();
+            }
+            build_grid();
+        }
+        Err(e) => {
+            set_relaxations.set(Vec::new());
+            set_expanded.set(Vec::new());
+            set_repairs.set(Vec::new());
+            set_document.set(None);
+            set_grid.set(None);
+            set_error_lines.set(
+                e.location
+                    .map(|location| input.with_untracked(|input| error_context(input, location, CONTEXT_RADIUS)))
+                    .unwrap_or_default(),
+            );
+            set_error_location.set(e.location);
+            set_error.set(e.message);
+        }
+    };
+
+    // 在输入框中选中某个位置，用于解析错误和 Schema 校验错误
+    let select_location = move |location: ErrorLocation| {
+        let Some(textarea) = textarea.get_untracked() else {
+            return;
+        };
+        let (start, end) = input.with_untracked(|input| selection_range(input, location));
+        let _ = textarea.focus();
+        let _ = textarea.set_selection_range(start, end);
+        // 按行号比例滚动，让出错行大致位于文本框中间
+        let lines = input.with_untracked(|input| input.lines().count().max(1)) as i32;
+        let line = location.line as i32 - 1;
+        textarea.set_scroll_top(textarea.scroll_height() * line / lines - textarea.client_height() / 2);
+    };
+    let jump_to_error = move || {
+        if let Some(location) = error_location.get_untracked() {
+            select_location(location);
+        }
+    };
+
+    let share_state = move || {
+        let state = SharedState::new(input.get_untracked())
+            .with_option("format", format_type.get_untracked().as_param())
+            .with_option("from", source.get_untracked().as_param())
+            .with_option("lenient", lenient.get_untracked().to_string())
+            .with_option("query", query.get_untracked())
+            .with_option("mode", mode.get_untracked().as_param())
+            .with_option("action", last_action.get_untracked());
+        let state = match mode.get_untracked() {
+            Mode::Format => state,
+            Mode::Compare => state
+                .with_option("right", right.get_untracked())
+                .with_option("ignore_array_order", ignore_array_order.get_untracked().to_string()),
+            Mode::Validate => state
+                .with_option("schema", schema.get_untracked())
+                .with_option("draft", schema_draft.get_untracked().map_or("auto", |draft| draft.as_param())),
+            Mode::Generate => state
+                .with_option("target", target.get_untracked().as_param())
+                .with_option("root", root_name.get_untracked())
+                .with_option("enums", detect_enums.get_untracked().to_string()),
+            Mode::Patch => {
+                let state = state
+                    .with_option("patch_action", patch_action.get_untracked().as_param())
+                    .with_option("patch_format", patch_format.get_untracked().as_param());
+                match patch_action.get_untracked() {
+                    PatchAction::Apply => state.with_option("patch", patch.get_untracked()),
+                    PatchAction::Generate => state.with_option("right", right.get_untracked()),
+                }
+            }
+        };
+        format_options
+            .get_untracked()
+            .to_params()
+            .into_iter()
+            .fold(state, |state, (name, value)| state.with_option(name, value))
+    };
+
+    let run_action = move |action: &'static str| {
+        set_last_action.set(action);
+        history.record(share_state());
+        if input.get().is_empty() {
+            set_output.set(String::new());
+            set_relaxations.set(Vec::new());
+            set_expanded.set(Vec::new());
+            set_repairs.set(Vec::new());
+            set_error.set(String::new());
+            set_error_location.set(None);
+            return;
+        }
+
+        let task = match action {
+            "minify" => Task::JsonMinify {
+                input: input.get(),
+                source: source.get(),
+                options: options(),
+                lenient: lenient.get(),
+            },
+            // 把整个文档写成一个 JSON 字符串字面量
+            "escape" => Task::JsonEscape {
+                input: input.get(),
+                source: source.get(),
+                options: options(),
+                lenient: lenient.get(),
+            },
+            // 输入是字符串字面量，引号可以省略
+            "unescape" => Task::JsonUnescape {
+                input: input.get(),
+                format_type: format_type.get(),
+                options: options(),
+            },
+            // 递归展开值为 JSON 文本的字符串字段
+            "unwrap" => Task::JsonUnwrap {
+                input: input.get(),
+                source: source.get(),
+                format_type: format_type.get(),
+                options: options(),
+                lenient: lenient.get(),
+            },
+            // 修复接近 JSON 的输入，例如 Python 字典、JS 对象字面量和被截断的文档
+            "repair" => Task::JsonRepair {
+                input: input.get(),
+                format_type: format_type.get(),
+                options: options(),
+            },
+            _ => Task::JsonFormat {
+                input: input.get(),
+                source: source.get(),
+                format_type: format_type.get(),
+                options: options(),
+                lenient: lenient.get(),
+            },
+        };
+        runner.run(task, show_result);
+    };
+    let find_action = |value: &str| ACTIONS.into_iter().find(|action| *action == value);
+
+    // 通过分享链接打开时，直接显示对方看到的结果
+    if let Some(action) = shared.as_ref().and_then(|state| state.option("action")).and_then(find_action) {
+        run_action(action);
+    }
+
+    let restore = move |state: SharedState| {
+        set_input.set(state.input.clone());
+        if let Some(format_type) = state.option("format").and_then(FormatType::from_param) {
+            select_format(format_type);
+        }
+        select_source(state.option("from").and_then(SourceFormat::from_param).unwrap_or_default());
+        select_lenient(state.option("lenient") == Some("true"));
+        select_query(state.option("query").unwrap_or_default().to_string());
+        if let Some(value) = state.option("right") {
+            right.set(value.to_string());
+        }
+        select_ignore_array_order(state.option("ignore_array_order") == Some("true"));
+        if let Some(value) = state.option("schema") {
+            schema.set(value.to_string());
+        }
+        select_schema_draft(state.option("draft").and_then(Draft::from_param));
+        if let Some(value) = state.option("target").and_then(Target::from_param) {
+            select_target(value);
+        }
+        if let Some(value) = state.option("root") {
+            select_root_name(value.to_string());
+        }
+        select_detect_enums(state.option("enums") != Some("false"));
+        if let Some(value) = state.option("patch") {
+            patch.set(value.to_string());
+        }
+        select_patch_action(state.option("patch_action").and_then(PatchAction::from_param).unwrap_or(PatchAction::Apply));
+        select_patch_format(state.option("patch_format").and_then(PatchFormat::from_param).unwrap_or_default());
+        select_mode(state.option("mode").and_then(Mode::from_param).unwrap_or(Mode::Format));
+        let restored = FormatOptions::from_params(|name| state.option(name).map(str::to_string));
+        update_options(&|options| *options = restored.clone());
+        run_action(state.option("action").and_then(find_action).unwrap_or("format"));
+    };
+
+    let is_format = move || mode.get() == Mode::Format;
+
+    view! {
+        <div class="p-6">
+            <div class="space-y-4">
+                <div class="flex gap-2 border-b border-gray-200">
+                    {Mode::ALL
+                        .into_iter()
+                        .map(|value| view! {
+                            <button
+                                class=move || format!(
+                                    "px-3 py-2 -mb-px text-sm font-medium border-b-2 {}",
+                                    if mode.get() == value {
+                                        "border-blue-600 text-blue-700"
+                                    } else {
+                                        "border-transparent text-gray-600 hover:text-gray-900"
+                                    }
+                                )
+                                on:click=move |_| select_mode(value)
+                            >
+                                {move || {
+                                    let _ = current_locale.get();
+                                    t!(&format!("tools.json.modes.{}", value.as_param()))
+                                }}
+                            </button>
+                        })
+                        .collect_view()}
+                </div>
+
+                <div>
+                    <label class="block text-sm font-medium text-gray-700 mb-2">
+                        {move || {
+                            let _ = current_locale.get();
+                            match mode.get() {
+                                Mode::Format => t!("tools.common.input_placeholder"),
+                                Mode::Compare => t!("tools.json.diff.left"),
+                                Mode::Validate => t!("tools.json.schema.document"),
+                                Mode::Generate => t!("tools.json.codegen.samples"),
+                                Mode::Patch => t!("tools.json.patch.document"),
+                            }
+                        }}
+                    </label>
+                    <textarea
+                        node_ref=textarea
+                        class="w-full h-48 p-2 border border-gray-300 rounded-md shadow-sm focus:ring-blue-500 focus:border-blue-500"
+                        placeholder=move || {
+                            let _ = current_locale.get();
+                            t!("tools.common.input_placeholder")
+                        }
+                        on:input=move |ev| set_input.set(event_target_value(&ev))
+                        prop:value=input
+                    ></textarea>
+                </div>
+
+                <div>
+                    <label class="block text-sm font-medium text-gray-700 mb-2">
+                        {move || {
+                            let _ = current_locale.get();
+                            t!("tools.json.query.label")
+                        }}
+                    </label>
+                    <input
+                        type="text"
+                        class="w-full p-2 border border-gray-300 rounded-md shadow-sm font-mono text-sm focus:ring-blue-500 focus:border-blue-500"
+                        placeholder=move || {
+                            let _ = current_locale.get();
+                            t!("tools.json.query.placeholder")
+                        }
+                        on:input=move |ev| set_query_text.set(event_target_value(&ev))
+                        on:change=move |ev| select_query(event_target_value(&ev))
+                        prop:value=query
+                    />
+                    {move || {
+                        let _ = current_locale.get();
+                        query_results.get().map(|results| match results {
+                            Err(QueryFailure::InvalidInput) => view! {
+                                <p class="mt-2 text-sm text-gray-500">{t!("tools.json.query.invalid_input")}</p>
+                            }
+                            .into_view(),
+                            Err(QueryFailure::TooLarge) => view! {
+                                <p class="mt-2 text-sm text-gray-500">{t!("tools.json.query.too_large")}</p>
+                            }
+                            .into_view(),
+                            Err(QueryFailure::Query(message)) => view! {
+                                <p class="mt-2 text-sm text-red-600">
+                                    {format!("{}: {}", t!("tools.json.query.error"), message)}
+                                </p>
+                            }
+                            .into_view(),
+                            Ok(matches) => {
+                                let hidden = matches.len().saturating_sub(MAX_QUERY_RESULTS);
+                                view! {
+                                    <div class="mt-2 border border-gray-200 rounded-md bg-gray-50">
+                                        <div class="flex justify-between items-center px-2 py-1 border-b border-gray-200 text-xs text-gray-500">
+                                            <span>{t!("tools.json.query.results", count = matches.len())}</span>
+                                            <button
+                                                class="inline-flex items-center px-2 py-0.5 border border-gray-300 rounded-md text-gray-700 bg-white hover:bg-gray-50"
+                                                on:click=move |_| copy_query_results()
+                                            >
+                                                {t!("tools.json.query.copy")}
+                                            </button>
+                                        </div>
+                                        <ul class="max-h-64 overflow-auto divide-y divide-gray-200 font-mono text-sm">
+                                            {matches
+                                                .into_iter()
+                                                .take(MAX_QUERY_RESULTS)
+                                                .map(|Match { path, value }| view! {
+                                                    <li class="flex gap-3 px-2 py-1">
+                                                        {path.map(|path| view! {
+                                                            <span class="shrink-0 text-purple-800">{path.to_string()}</span>
+                                                        })}
+                                                        <span class="text-gray-800 break-all">{compact(&value)}</span>
+                                                    </li>
+                                                })
+                                                .collect_view()}
+                                        </ul>
+                                        {(hidden > 0).then(|| view! {
+                                            <p class="px-2 py-1 text-xs text-gray-500">{t!("tools.json.query.more", count = hidden)}</p>
+                                        })}
+                                    </div>
+                                }
+                                .into_view()
+                            }
+                        })
+                    }}
+                </div>
+
+                <Show when=move || mode.get() == Mode::Compare fallback=|| view! { }>
+                    <JsonDiff
+                        left=input
+                        right=right
+                        lenient=lenient
+                        ignore_array_order=ignore_array_order
+                        on_ignore_array_order=select_ignore_array_order
+                        current_locale=current_locale
+                    />
+                </Show>
+                <Show when=move || mode.get() == Mode::Validate fallback=|| view! { }>
+                    <JsonSchema
+                        document=input
+                        schema=schema
+                        lenient=lenient
+                        draft=schema_draft
+                        on_draft=select_schema_draft
+                        on_locate=select_location
+                        current_locale=current_locale
+                    />
+                </Show>
+                <Show when=move || mode.get() == Mode::Generate fallback=|| view! { }>
+                    <JsonCodegen
+                        samples=input
+                        lenient=lenient
+                        target=target
+                        on_target=select_target
+                        root_name=root_name
+                        on_root_name=select_root_name
+                        detect_enums=detect_enums
+                        on_detect_enums=select_detect_enums
+                        current_locale=current_locale
+                    />
+                </Show>
+                <Show when=move || mode.get() == Mode::Patch fallback=|| view! { }>
+                    <JsonPatch
+                        document=input
+                        patch=patch
+                        target=right
+                        lenient=lenient
+                        action=patch_action
+                        on_action=select_patch_action
+                        format=patch_format
+                        on_format=select_patch_format
+                        current_locale=current_locale
+                    />
+                </Show>
+
+                <div class="flex flex-wrap gap-2">
+                    <button
+                        class="inline-flex items-center px-4 py-2 border border-transparent text-sm font-medium rounded-md shadow-sm text-white bg-blue-600 hover:bg-blue-700 disabled:opacity-50 focus:outline-none focus:ring-2 focus:ring-offset-2 focus:ring-blue-500"
+                        class:hidden=move || !is_format()
+                        prop:disabled=move || runner.busy()
+                        on:click=move |_| run_action("format")
+                    >
+                        {move || {
+                            let _ = current_locale.get();
+                            t!("tools.common.format")
+                        }}
+                    </button>
+                    <button
+                        class="inline-flex items-center px-4 py-2 border border-transparent text-sm font-medium rounded-md shadow-sm text-white bg-blue-600 hover:bg-blue-700 disabled:opacity-50 focus:outline-none focus:ring-2 focus:ring-offset-2 focus:ring-blue-500"
+                        class:hidden=move || !is_format()
+                        prop:disabled=move || runner.busy()
+                        on:click=move |_| run_action("minify")
+                    >
+                        {move || {
+                            let _ = current_locale.get();
+                            t!("tools.common.minify")
+                        }}
+                    </button>
+                    {ACTIONS[2..]
+                        .iter()
+                        .map(|&action| view! {
+                            <button
+                                class="inline-flex items-center px-4 py-2 border border-gray-300 text-sm font-medium rounded-md shadow-sm text-gray-700 bg-white hover:bg-gray-50 disabled:opacity-50 focus:outline-none focus:ring-2 focus:ring-offset-2 focus:ring-blue-500"
+                                class:hidden=move || !is_format()
+                                prop:disabled=move || runner.busy()
+                                on:click=move |_| run_action(action)
+                            >
+                                {move || {
+                                    let _ = current_locale.get();
+                                    t!(&format!("tools.json.actions.{}", action))
+                                }}
+                            </button>
+                        })
+                        .collect_view()}
+                    <ShareButton state=share_state current_locale=current_locale/>
+                    <HistoryDrawer history=history current_locale=current_locale on_restore=restore/>
+                </div>
+
+                <div class="flex flex-wrap gap-2">
+                    <div class="flex flex-wrap items-center gap-2" class:hidden=move || !is_format()>
+                        <label class="flex items-center gap-2 mr-2 text-sm text-gray-700">
+                            {move || {
+                                let _ = current_locale.get();
+                                t!("tools.json.source")
+                            }}
+                            <select
+                                class="px-2 py-1 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500 focus:border-blue-500"
+                                prop:value=move || source.get().as_param()
+                                on:change=move |ev| {
+                                    if let Some(source) = SourceFormat::from_param(&event_target_value(&ev)) {
+                                        select_source(source);
+                                    }
+                                }
+                            >
+                                {SourceFormat::ALL
+                                    .into_iter()
+                                    .map(|source| view! {
+                                        <option value=source.as_param()>{move || {
+                                            let _ = current_locale.get();
+                                            t!(&format!("tools.json.sources.{}", source.as_param()))
+                                        }}</option>
+                                    })
+                                    .collect_view()}
+                            </select>
+                        </label>
+                        {FormatType::ALL
+                            .into_iter()
+                            .map(|value| view! {
+                                <button
+                                    class=move || format!(
+                                        "px-3 py-2 text-sm font-medium rounded-md {} {}",
+                                        if format_type.get() == value {
+                                            "bg-blue-100 text-blue-700"
+                                        } else {
+                                            "text-gray-700 hover:bg-gray-100"
+                                        },
+                                        "focus:outline-none focus:ring-2 focus:ring-offset-2 focus:ring-blue-500"
+                                    )
+                                    on:click=move |_| select_format(value)
+                                >
+                                    {move || {
+                                        let _ = current_locale.get();
+                                        t!(&format!("tools.json.format_{}", value.as_param()))
+                                    }}
+                                </button>
+                            })
+                            .collect_view()}
+                    </div>
+                    <div class="flex items-center ml-2" class:hidden=move || is_format() && source.get() != SourceFormat::Json>
+                        <input
+                            type="checkbox"
+                            class="h-4 w-4 text-blue-600 border-gray-300 rounded focus:ring-blue-500"
+                            prop:checked=move || lenient.get()
+                            on:change=move |ev| select_lenient(event_target_checked(&ev))
+                        />
+                        <label class="ml-2 text-sm text-gray-700">
+                            {move || {
+                                let _ = current_locale.get();
+                                t!("tools.json.lenient")
+                            }}
+                        </label>
+                    </div>
+                </div>
+
+                <div class="flex flex-wrap items-center gap-4" class:hidden=move || !is_format()>
+                    <label class="flex items-center gap-2 text-sm text-gray-700">
+                        {move || {
+                            let _ = current_locale.get();
+                            t!("tools.json.indent")
+                        }}
+                        <select
+                            class="px-2 py-1 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500 focus:border-blue-500"
+                            prop:value=move || format_options.with(|options| options.indent.as_param())
+                            on:change=move |ev| {
+                                if let Some(indent) = Indent::from_param(&event_target_value(&ev)) {
+                                    update_options(&|options| options.indent = indent);
+                                }
+                            }
+                        >
+                            {Indent::ALL
+                                .into_iter()
+                                .map(|indent| view! {
+                                    <option value=indent.as_param()>{move || {
+                                        let _ = current_locale.get();
+                                        t!(&format!("tools.json.indents.{}", indent.as_param()))
+                                    }}</option>
+                                })
+                                .collect_view()}
+                        </select>
+                    </label>
+                    <label class="flex items-center gap-2 text-sm text-gray-700">
+                        {move || {
+                            let _ = current_locale.get();
+                            t!("tools.json.max_width")
+                        }}
+                        <select
+                            class="px-2 py-1 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500 focus:border-blue-500"
+                            prop:value=move || format_options.with(|options| options.max_width.to_string())
+                            on:change=move |ev| {
+                                if let Ok(width) = event_target_value(&ev).parse::<usize>() {
+                                    update_options(&|options| options.max_width = width);
+                                }
+                            }
+                        >
+                            {MAX_WIDTHS
+                                .into_iter()
+                                .map(|width| view! {
+                                    <option value=width.to_string()>{move || {
+                                        let _ = current_locale.get();
+                                        if width == 0 { t!("tools.json.max_width_off") } else { width.to_string() }
+                                    }}</option>
+                                })
+                                .collect_view()}
+                        </select>
+                    </label>
+                    <div class="flex items-center">
+                        <input
+                            type="checkbox"
+                            class="h-4 w-4 text-blue-600 border-gray-300 rounded focus:ring-blue-500"
+                            prop:checked=move || format_options.with(|options| options.sort_keys)
+                            on:change=move |ev| {
+                                let checked = event_target_checked(&ev);
+                                update_options(&|options| options.sort_keys = checked);
+                            }
+                        />
+                        <label class="ml-2 text-sm text-gray-700">
+                            {move || {
+                                let _ = current_locale.get();
+                                t!("tools.json.sort_keys")
+                            }}
+                        </label>
+                    </div>
+                    <div class="flex items-center">
+                        <input
+                            type="checkbox"
+                            class="h-4 w-4 text-blue-600 border-gray-300 rounded focus:ring-blue-500"
+                            prop:checked=move || format_options.with(|options| options.escape_non_ascii)
+                            on:change=move |ev| {
+                                let checked = event_target_checked(&ev);
+                                update_options(&|options| options.escape_non_ascii = checked);
+                            }
+                        />
+                        <label class="ml-2 text-sm text-gray-700">
+                            {move || {
+                                let _ = current_locale.get();
+                                t!("tools.json.escape_non_ascii")
+                            }}
+                        </label>
+                    </div>
+                    <div class="flex items-center">
+                        <input
+                            type="checkbox"
+                            class="h-4 w-4 text-blue-600 border-gray-300 rounded focus:ring-blue-500"
+                            prop:checked=move || format_options.with(|options| options.trailing_newline)
+                            on:change=move |ev| {
+                                let checked = event_target_checked(&ev);
+                                update_options(&|options| options.trailing_newline = checked);
+                            }
+                        />
+                        <label class="ml-2 text-sm text-gray-700">
+                            {move || {
+                                let _ = current_locale.get();
+                                t!("tools.json.trailing_newline")
+                            }}
+                        </label>
+                    </div>
+                </div>
+
+                <div class="flex flex-wrap items-center gap-4" class:hidden=move || !is_format() || !uses_table()>
+                    <label class="flex items-center gap-2 text-sm text-gray-700">
+                        {move || {
+                            let _ = current_locale.get();
+                            t!("tools.json.table.delimiter")
+                        }}
+                        <select
+                            class="px-2 py-1 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500 focus:border-blue-500"
+                            prop:value=move || format_options.with(|options| options.table.delimiter.as_param())
+                            on:change=move |ev| {
+                                if let Some(delimiter) = Delimiter::from_param(&event_target_value(&ev)) {
+                                    update_options(&|options| options.table.delimiter = delimiter);
+                                }
+                            }
+                        >
+                            {Delimiter::ALL
+                                .into_iter()
+                                .map(|delimiter| view! {
+                                    <option value=delimiter.as_param()>{move || {
+                                        let _ = current_locale.get();
+                                        t!(&format!("tools.json.table.delimiters.{}", delimiter.as_param()))
+                                    }}</option>
+                                })
+                                .collect_view()}
+                        </select>
+                    </label>
+                    <label class="flex items-center gap-2 text-sm text-gray-700" class:hidden=move || format_type.get() != FormatType::Csv>
+                        {move || {
+                            let _ = current_locale.get();
+                            t!("tools.json.table.arrays")
+                        }}
+                        <select
+                            class="px-2 py-1 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500 focus:border-blue-500"
+                            prop:value=move || format_options.with(|options| options.table.arrays.as_param())
+                            on:change=move |ev| {
+                                if let Some(arrays) = ArrayMode::from_param(&event_target_value(&ev)) {
+                                    update_options(&|options| options.table.arrays = arrays);
+                                }
+                            }
+                        >
+                            {ArrayMode::ALL
+                                .into_iter()
+                                .map(|arrays| view! {
+                                    <option value=arrays.as_param()>{move || {
+                                        let _ = current_locale.get();
+                                        t!(&format!("tools.json.table.array_modes.{}", arrays.as_param()))
+                                    }}</option>
+                                })
+                                .collect_view()}
+                        </select>
+                    </label>
+                    <div class="flex items-center">
+                        <input
+                            type="checkbox"
+                            class="h-4 w-4 text-blue-600 border-gray-300 rounded focus:ring-blue-500"
+                            prop:checked=move || format_options.with(|options| options.table.header)
+                            on:change=move |ev| {
+                                let checked = event_target_checked(&ev);
+                                update_options(&|options| options.table.header = checked);
+                            }
+                        />
+                        <label class="ml-2 text-sm text-gray-700">
+                            {move || {
+                                let _ = current_locale.get();
+                                t!("tools.json.table.header")
+                            }}
+                        </label>
+                    </div>
+                    <div class="flex items-center" class:hidden=move || source.get() != SourceFormat::Csv>
+                        <input
+                            type="checkbox"
+                            class="h-4 w-4 text-blue-600 border-gray-300 rounded focus:ring-blue-500"
+                            prop:checked=move || format_options.with(|options| options.table.infer_types)
+                            on:change=move |ev| {
+                                let checked = event_target_checked(&ev);
+                                update_options(&|options| options.table.infer_types = checked);
+                            }
+                        />
+                        <label class="ml-2 text-sm text-gray-700">
+                            {move || {
+                                let _ = current_locale.get();
+                                t!("tools.json.table.infer_types")
+                            }}
+                        </label>
+                    </div>
+                </div>
+
+                <div class="space-y-4" class:hidden=move || !is_format()>
+                    <TaskProgress runner=runner current_locale=current_locale/>
+
+                    <Show
+                        when=move || !error.get().is_empty()
+                        fallback=|| view! { }
+                    >
+                        <div class="p-4 bg-red-50 border border-red-200 rounded-lg text-red-700">
+                            <div class="flex justify-between items-start gap-4">
+                                <span class="whitespace-pre-wrap">
+                                    {move || {
+                                        let _ = current_locale.get();
+                                        // 反转义和修复的输入总是按 JSON 读取
+                                        let label = match (last_action.get(), source.get()) {
+                                            ("unescape" | "repair", _) | (_, SourceFormat::Json) => t!("tools.json.invalid_json"),
+                                            (_, source) => t!(
+                                                "tools.json.invalid_source",
+                                                format = t!(&format!("tools.json.sources.{}", source.as_param()))
+                                            ),
+                                        };
+                                        format!("{}: {}", label, error.get())
+                                    }}
+                                </span>
+                                <Show when=move || error_location.get().is_some() fallback=|| view! { }>
+                                    <button
+                                        class="shrink-0 inline-flex items-center px-3 py-1 border border-red-300 shadow-sm text-sm leading-4 font-medium rounded-md text-red-700 bg-white hover:bg-red-50 focus:outline-none focus:ring-2 focus:ring-offset-2 focus:ring-red-500"
+                                        on:click=move |_| jump_to_error()
+                                    >
+                                        {move || {
+                                            let _ = current_locale.get();
+                                            t!("tools.json.jump_to_error")
+                                        }}
+                                    </button>
+                                </Show>
+                            </div>
+                            <Show when=move || error_location.get().is_some() fallback=|| view! { }>
+                                <pre class="mt-3 p-2 bg-white border border-red-200 rounded-md overflow-auto font-mono text-sm text-gray-800">
+                                    {move || {
+                                        let column = error_location.get().map_or(1, |location| location.column);
+                                        context_view(error_lines.get(), column)
+                                    }}
+                                </pre>
+                            </Show>
+                        </div>
+                    </Show>
+
+                    <Show
+                        when=move || error.get().is_empty() && !relaxations.with(Vec::is_empty)
+                        fallback=|| view! { }
+                    >
+                        <div class="p-3 bg-yellow-50 border border-yellow-200 rounded-lg text-sm text-yellow-800">
+                            {move || {
+                                let _ = current_locale.get();
+                                let names = relaxations
+                                    .get()
+                                    .iter()
+                                    .map(|relaxation| t!(&format!("tools.json.relaxations.{}", relaxation.as_param())))
+                                    .collect::<Vec<_>>()
+                                    .join(", ");
+                                format!("{}: {}", t!("tools.json.relaxations_applied"), names)
+                            }}
+                        </div>
+                    </Show>
+
+                    <Show
+                        when=move || error.get().is_empty() && !repairs.with(Vec::is_empty)
+                        fallback=|| view! { }
+                    >
+                        <div class="p-3 bg-green-50 border border-green-200 rounded-lg text-sm text-green-800">
+                            <p>
+                                {move || {
+                                    let _ = current_locale.get();
+                                    t!("tools.json.repaired", count = repairs.with(Vec::len))
+                                }}
+                            </p>
+                            <ul class="mt-1 space-y-0.5">
+                                {move || {
+                                    let _ = current_locale.get();
+                                    repairs.with(|repairs| summarize(repairs))
+                                        .into_iter()
+                                        .map(|summary| {
+                                            let location = summary.first;
+                                            let description = t!(&format!("tools.json.repairs.{}", summary.kind.as_param()));
+                                            view! {
+                                                <li class="flex flex-wrap items-center gap-2">
+                                                    <span>
+                                                        {if summary.count > 1 {
+                                                            format!("{} ×{}", description, summary.count)
+                                                        } else {
+                                                            description
+                                                        }}
+                                                    </span>
+                                                    <button
+                                                        class="text-xs text-green-700 underline hover:text-green-900"
+                                                        on:click=move |_| select_location(location)
+                                                    >
+                                                        {t!(
+                                                            "tools.json.repair_location",
+                                                            line = location.line,
+                                                            column = location.column
+                                                        )}
+                                                    </button>
+                                                </li>
+                                            }
+                                        })
+                                        .collect_view()
+                                }}
+                            </ul>
+                        </div>
+                    </Show>
+
+                    <Show
+                        when=move || error.get().is_empty() && !expanded.with(Vec::is_empty)
+                        fallback=|| view! { }
+                    >
+                        <div class="p-3 bg-blue-50 border border-blue-200 rounded-lg text-sm text-blue-800">
+                            {move || {
+                                let _ = current_locale.get();
+                                let paths = expanded.get();
+                                format!("{}: {}", t!("tools.json.unwrapped", count = paths.len()), paths.join(", "))
+                            }}
+                        </div>
+                    </Show>
+
+                    <Show
+                        when=move || !output.get().is_empty()
+                        fallback=|| view! { }
+                    >
+                        <div>
+                            <div class="flex justify-between items-center mb-2">
+                                <label class="block text-sm font-medium text-gray-700">
+                                    {move || {
+                                        let _ = current_locale.get();
+                                        t!("tools.common.output_label")
+                                    }}
+                                </label>
+                                <div class="flex items-center gap-4">
+                                    <div class="flex items-center">
+                                        <input
+                                            type="checkbox"
+                                            class="h-4 w-4 text-blue-600 border-gray-300 rounded focus:ring-blue-500"
+                                            prop:checked=move || show_tree.get()
+                                            on:change=move |ev| select_show_tree(event_target_checked(&ev))
+                                        />
+                                        <label class="ml-2 text-sm text-gray-700">
+                                            {move || {
+                                                let _ = current_locale.get();
+                                                t!("tools.json.tree.show")
+                                            }}
+                                        </label>
+                                    </div>
+                                    <button
+                                        class="inline-flex items-center px-3 py-1 border border-gray-300 shadow-sm text-sm leading-4 font-medium rounded-md text-gray-700 bg-white hover:bg-gray-50 focus:outline-none focus:ring-2 focus:ring-offset-2 focus:ring-blue-500"
+                                        on:click=move |_| {
+                                            copy_to_clipboard(&output.get());
+                                        }
+                                    >
+                                        {move || {
+                                            let _ = current_locale.get();
+                                            t!("tools.common.copy")
+                                        }}
+                                    </button>
+                                </div>
+                            </div>
+                            <div class=move || if show_tree.get() && !large_input.get() { "grid gap-4 md:grid-cols-2" } else { "" }>
+                                <pre class="w-full max-h-96 p-2 bg-gray-50 border border-gray-200 rounded-md overflow-auto whitespace-pre-wrap">
+                                    {output}
+                                </pre>
+                                {move || {
+                                    show_tree
+                                        .get()
+                                        .then(|| document.get())
+                                        .flatten()
+                                        .map(|document| view! {
+                                            <JsonTree document=document marked=Rc::new(expanded.get()) current_locale=current_locale/>
+                                        })
+                                }}
+                            </div>
+                            {move || {
+                                let _ = current_locale.get();
+                                (large_input.get() && (show_tree.get() || uses_table())).then(|| view! {
+                                    <p class="mt-2 text-xs text-gray-500">{t!("tools.json.tree.too_large")}</p>
+                                })
+                            }}
+                            {move || {
+                                grid.get().map(|table| view! {
+                                    <div class="mt-4">
+                                        <JsonGrid table=table current_locale=current_locale/>
+                                    </div>
+                                })
+                            }}
+                        </div>
+                    </Show>
+                </div>
+            </div>
+        </div>
+    }
+}
\ No newline at end of file
@@ -0,0 +1,317 @@
+use leptos::*;
+use rust_i18n::t;
+use dev_tools_core::json::{format_value, FormatOptions, FormatType};
+use dev_tools_core::patch::{apply, generate, PatchFormat};
+use crate::utils::copy_to_clipboard;
+use super::{compact, parse_input};
+use serde_json::Value;
+
+/// 补丁模式下是应用补丁还是从两份文档生成补丁，对应查询参数 `patch_action`
+#[derive(Clone, Copy, PartialEq)]
+pub enum PatchAction {
+    Apply,
+    Generate,
+}
+
+impl PatchAction {
+    pub const ALL: [PatchAction; 2] = [PatchAction::Apply, PatchAction::Generate];
+
+    pub fn as_param(self) -> &'static str {
+        match self {
+            PatchAction::Apply => "apply",
+            PatchAction::Generate => "generate",
+        }
+    }
+
+    pub fn from_param(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|action| action.as_param() == value)
+    }
+}
+
+/// JSON Patch 中单个操作的执行情况
+#[derive(Clone, PartialEq)]
+enum Status {
+    Applied,
+    Failed(String),
+    Skipped,
+}
+
+#[derive(Clone, PartialEq)]
+enum Outcome {
+    /// 某个输入无法解析，附带对应的提示键
+    Invalid(&'static str, String),
+    Applied { result: String, steps: Vec<(String, Status)> },
+    Failed { message: String, steps: Vec<(String, Status)> },
+    Generated { patch: String, empty: bool },
+    NotGenerated(String),
+}
+
+fn pretty(value: &Value) -> String {
+    format_value(value, FormatType::Standard, &FormatOptions::default()).unwrap_or_default()
+}
+
+/// 每个操作的单行表示和状态；失败的操作之后的操作都未执行
+fn steps(patch: &Value, failed: Option<(usize, &str)>) -> Vec<(String, Status)> {
+    let Some(operations) = patch.as_array() else {
+        return Vec::new();
+    };
+    operations
+        .iter()
+        .enumerate()
+        .map(|(index, operation)| {
+            let status = match failed {
+                Some((at, message)) if index == at => Status::Failed(message.to_string()),
+                Some((at, _)) if index > at => Status::Skipped,
+                _ => Status::Applied,
+            };
+            (compact(operation), status)
+        })
+        .collect()
+}
+
+/// 对工具的主输入应用 JSON Patch 或 Merge Patch，或生成把主输入变成目标文档的补丁
+#[component]
+pub fn JsonPatch(
+    document: ReadSignal<String>,
+    patch: RwSignal<String>,
+    target: RwSignal<String>,
+    lenient: ReadSignal<bool>,
+    action: ReadSignal<PatchAction>,
+    #[prop(into)] on_action: Callback<PatchAction>,
+    format: ReadSignal<PatchFormat>,
+    #[prop(into)] on_format: Callback<PatchFormat>,
+    current_locale: RwSignal<String>,
+) -> impl IntoView {
+    let outcome = create_memo(move |_| {
+        let second = match action.get() {
+            PatchAction::Apply => patch,
+            PatchAction::Generate => target,
+        };
+        let (document, second) = (document.get(), second.get());
+        if document.trim().is_empty() || second.trim().is_empty() {
+            return None;
+        }
+        let lenient = lenient.get();
+        let document = match parse_input(&document, lenient) {
+            Ok(document) => document,
+            Err(e) => return Some(Outcome::Invalid("invalid_document", e.to_string())),
+        };
+        let format = format.get();
+        let outcome = match action.get() {
+            PatchAction::Apply => match parse_input(&second, lenient) {
+                Err(e) => Outcome::Invalid("invalid_patch", e.to_string()),
+                Ok(patch) => match apply(&document, &patch, format) {
+                    Ok(result) => Outcome::Applied {
+                        result: pretty(&result),
+                        steps: steps(&patch, None),
+                    },
+                    Err(e) => Outcome::Failed {
+                        steps: e
+                            .operation
+                            .map(|index| steps(&patch, Some((index, &e.message))))
+                            .unwrap_or_default(),
+                        message: e.to_string(),
+                    },
+                },
+            },
+            PatchAction::Generate => match parse_input(&second, lenient) {
+                Err(e) => Outcome::Invalid("invalid_target", e.to_string()),
+                Ok(target) => match generate(&document, &target, format) {
+                    Ok(patch) => Outcome::Generated {
+                        empty: patch.as_array().is_some_and(Vec::is_empty)
+                            || patch.as_object().is_some_and(|map| map.is_empty()),
+                        patch: pretty(&patch),
+                    },
+                    Err(e) => Outcome::NotGenerated(e.to_string()),
+                },
+            },
+        };
+        Some(outcome)
+    });
+
+    let tab_class = move |active: bool| {
+        format!(
+            "px-3 py-1 text-sm font-medium rounded-md {}",
+            if active { "bg-blue-100 text-blue-700" } else { "text-gray-700 hover:bg-gray-100" }
+        )
+    };
+    let textarea_class = "w-full h-48 p-2 border border-gray-300 rounded-md shadow-sm focus:ring-blue-500 focus:border-blue-500";
+
+    let output = |label: String, text: String| {
+        let copied = text.clone();
+        view! {
+            <div>
+                <div class="flex justify-between items-center mb-2">
+                    <span class="text-sm font-medium text-gray-700">{label}</span>
+                    <button
+                        class="inline-flex items-center px-3 py-1 border border-gray-300 shadow-sm text-sm leading-4 font-medium rounded-md text-gray-700 bg-white hover:bg-gray-50 focus:outline-none focus:ring-2 focus:ring-offset-2 focus:ring-blue-500"
+                        on:click=move |_| copy_to_clipboard(&copied)
+                    >
+                        {t!("tools.json.patch.copy")}
+                    </button>
+                </div>
+                <pre class="w-full max-h-96 p-2 bg-gray-50 border border-gray-200 rounded-md overflow-auto whitespace-pre-wrap">
+                    {text}
+                </pre>
+            </div>
+        }
+    };
+    let step_list = |steps: Vec<(String, Status)>| {
+        (!steps.is_empty()).then(|| {
+            let count = steps.len();
+            view! {
+                <div>
+                    <p class="mb-1 text-xs text-gray-500">{t!("tools.json.patch.operations", count = count)}</p>
+                    <ol class="max-h-64 overflow-auto border border-gray-200 rounded-md divide-y divide-gray-200 font-mono text-sm">
+                        {steps
+                            .into_iter()
+                            .enumerate()
+                            .map(|(index, (operation, status))| {
+                                let (class, sign, note) = match status {
+                                    Status::Applied => ("text-gray-800", "✓", t!("tools.json.patch.applied").to_string()),
+                                    Status::Failed(message) => ("bg-red-50 text-red-800", "✗", message),
+                                    Status::Skipped => ("text-gray-400", "–", t!("tools.json.patch.skipped").to_string()),
+                                };
+                                view! {
+                                    <li class=format!("flex gap-3 px-2 py-1 {}", class)>
+                                        <span class="shrink-0 w-6 text-right select-none">{index}</span>
+                                        <span class="shrink-0 select-none">{sign}</span>
+                                        <span class="break-all">{operation}</span>
+                                        <span class="ml-auto shrink-0 pl-2 font-sans text-xs">{note}</span>
+                                    </li>
+                                }
+                            })
+                            .collect_view()}
+                    </ol>
+                </div>
+            }
+        })
+    };
+
+    view! {
+        <div class="space-y-4">
+            <div class="flex flex-wrap items-center gap-4">
+                <div class="flex items-center gap-1">
+                    <span class="mr-1 text-sm text-gray-700">
+                        {move || {
+                            let _ = current_locale.get();
+                            t!("tools.json.patch.action")
+                        }}
+                    </span>
+                    {PatchAction::ALL
+                        .into_iter()
+                        .map(|value| view! {
+                            <button class=move || tab_class(action.get() == value) on:click=move |_| on_action.call(value)>
+                                {move || {
+                                    let _ = current_locale.get();
+                                    t!(&format!("tools.json.patch.actions.{}", value.as_param()))
+                                }}
+                            </button>
+                        })
+                        .collect_view()}
+                </div>
+                <div class="flex items-center gap-1">
+                    <span class="mr-1 text-sm text-gray-700">
+                        {move || {
+                            let _ = current_locale.get();
+                            t!("tools.json.patch.format")
+                        }}
+                    </span>
+                    {PatchFormat::ALL
+                        .into_iter()
+                        .map(|value| view! {
+                            <button class=move || tab_class(format.get() == value) on:click=move |_| on_format.call(value)>
+                                {move || {
+                                    let _ = current_locale.get();
+                                    t!(&format!("tools.json.patch.formats.{}", value.as_param()))
+                                }}
+                            </button>
+                        })
+                        .collect_view()}
+                </div>
+            </div>
+
+            <div class:hidden=move || action.get() != PatchAction::Apply>
+                <label class="block text-sm font-medium text-gray-700 mb-2">
+                    {move || {
+                        let _ = current_locale.get();
+                        t!("tools.json.patch.label")
+                    }}
+                </label>
+                <textarea
+                    class=textarea_class
+                    placeholder=move || {
+                        let _ = current_locale.get();
+                        t!("tools.json.patch.placeholder")
+                    }
+                    on:input=move |ev| patch.set(event_target_value(&ev))
+                    prop:value=patch
+                ></textarea>
+            </div>
+            <div class:hidden=move || action.get() != PatchAction::Generate>
+                <label class="block text-sm font-medium text-gray-700 mb-2">
+                    {move || {
+                        let _ = current_locale.get();
+                        t!("tools.json.patch.target")
+                    }}
+                </label>
+                <textarea
+                    class=textarea_class
+                    placeholder=move || {
+                        let _ = current_locale.get();
+                        t!("tools.json.patch.target_placeholder")
+                    }
+                    on:input=move |ev| target.set(event_target_value(&ev))
+                    prop:value=target
+                ></textarea>
+            </div>
+
+            {move || {
+                let _ = current_locale.get();
+                outcome.get().map(|outcome| match outcome {
+                    Outcome::Invalid(key, message) => view! {
+                        <div class="p-4 bg-red-50 border border-red-200 rounded-lg text-red-700 whitespace-pre-wrap">
+                            {format!("{}: {}", t!(&format!("tools.json.patch.{}", key)), message)}
+                        </div>
+                    }
+                    .into_view(),
+                    Outcome::Applied { result, steps } => view! {
+                        <div class="space-y-4">
+                            {step_list(steps)}
+                            {output(t!("tools.json.patch.result").to_string(), result)}
+                        </div>
+                    }
+                    .into_view(),
+                    Outcome::Failed { message, steps } => view! {
+                        <div class="space-y-4">
+                            <div class="p-4 bg-red-50 border border-red-200 rounded-lg text-red-700">
+                                <p class="font-medium">{t!("tools.json.patch.failed")}</p>
+                                <p class="mt-1 whitespace-pre-wrap">{message}</p>
+                                <p class="mt-1 text-sm">{t!("tools.json.patch.not_applied")}</p>
+                            </div>
+                            {step_list(steps)}
+                        </div>
+                    }
+                    .into_view(),
+                    Outcome::Generated { patch, empty } => view! {
+                        <div class="space-y-2">
+                            {empty.then(|| view! {
+                                <div class="p-4 bg-green-50 border border-green-200 rounded-lg text-green-700">
+                                    {t!("tools.json.patch.empty")}
+                                </div>
+                            })}
+                            {output(t!("tools.json.patch.generated").to_string(), patch)}
+                        </div>
+                    }
+                    .into_view(),
+                    Outcome::NotGenerated(message) => view! {
+                        <div class="p-4 bg-red-50 border border-red-200 rounded-lg text-red-700 whitespace-pre-wrap">
+                            {message}
+                        </div>
+                    }
+                    .into_view(),
+                })
+            }}
+        </div>
+    }
+}
@@ -0,0 +1,301 @@
+use leptos::*;
+use rust_i18n::t;
+use dev_tools_core::json::{locate, ErrorLocation};
+use dev_tools_core::schema::{validate, Draft, ValidationError};
+use crate::utils::history::local_storage;
+use super::parse_input;
+use std::collections::BTreeMap;
+
+/// 最多列出（并在输入中定位）的错误数；每次定位都要扫描一遍文档
+const MAX_SHOWN_ERRORS: usize = 100;
+
+/// 已保存的 Schema 在 localStorage 中的键，值为名称到 Schema 文本的 JSON 对象
+const SAVED_SCHEMAS_KEY: &str = "json.schemas";
+
+/// 按名称保存的 Schema，例如接口约定的 Schema
+#[derive(Clone, Copy)]
+struct SavedSchemas {
+    schemas: RwSignal<BTreeMap<String, String>>,
+}
+
+impl SavedSchemas {
+    fn load() -> Self {
+        let schemas = local_storage()
+            .and_then(|storage| storage.get_item(SAVED_SCHEMAS_KEY).ok().flatten())
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+        Self {
+            schemas: create_rw_signal(schemas),
+        }
+    }
+
+    fn names(&self) -> Vec<String> {
+        self.schemas.with(|schemas| schemas.keys().cloned().collect())
+    }
+
+    fn get(&self, name: &str) -> Option<String> {
+        self.schemas.with_untracked(|schemas| schemas.get(name).cloned())
+    }
+
+    fn save(&self, name: String, schema: String) {
+        self.update(|schemas| {
+            schemas.insert(name, schema);
+        });
+    }
+
+    fn remove(&self, name: &str) {
+        self.update(|schemas| {
+            schemas.remove(name);
+        });
+    }
+
+    fn update(&self, f: impl FnOnce(&mut BTreeMap<String, String>)) {
+        self.schemas.update(f);
+        if let Some(storage) = local_storage() {
+            let json = self.schemas.with_untracked(|schemas| serde_json::to_string(schemas).unwrap_or_default());
+            let _ = storage.set_item(SAVED_SCHEMAS_KEY, &json);
+        }
+    }
+}
+
+/// 校验失败的原因：文档或 Schema 无法解析，或 Schema 本身有误
+#[derive(Clone, PartialEq)]
+enum Failure {
+    InvalidDocument(String),
+    InvalidSchema(String),
+}
+
+/// 按 JSON Schema 校验工具的主输入，点击错误可在输入中定位
+#[component]
+pub fn JsonSchema(
+    document: ReadSignal<String>,
+    schema: RwSignal<String>,
+    lenient: ReadSignal<bool>,
+    draft: ReadSignal<Option<Draft>>,
+    #[prop(into)] on_draft: Callback<Option<Draft>>,
+    #[prop(into)] on_locate: Callback<ErrorLocation>,
+    current_locale: RwSignal<String>,
+) -> impl IntoView {
+    let saved = SavedSchemas::load();
+    let (selected, set_selected) = create_signal(String::new());
+    let (name, set_name) = create_signal(String::new());
+
+    let errors = create_memo(move |_| {
+        if schema.with(|schema| schema.trim().is_empty()) || document.with(|document| document.trim().is_empty()) {
+            return None;
+        }
+        let lenient = lenient.get();
+        let parsed_schema = schema.with(|schema| parse_input(schema, lenient));
+        let parsed_document = document.with(|document| parse_input(document, lenient));
+        let result = match (parsed_schema, parsed_document) {
+            (Err(e), _) => Err(Failure::InvalidSchema(e.to_string())),
+            (_, Err(e)) => Err(Failure::InvalidDocument(e.to_string())),
+            (Ok(parsed_schema), Ok(parsed_document)) => validate(&parsed_schema, &parsed_document, draft.get())
+                .map(|errors| {
+                    let total = errors.len();
+                    let shown = document.with(|document| {
+                        errors
+                            .into_iter()
+                            .take(MAX_SHOWN_ERRORS)
+                            .map(|error| {
+                                let location = locate(document, &error.instance_path);
+                                (error, location)
+                            })
+                            .collect::<Vec<(ValidationError, Option<ErrorLocation>)>>()
+                    });
+                    (total, shown)
+                })
+                .map_err(|e| Failure::InvalidSchema(e.to_string())),
+        };
+        Some(result)
+    });
+
+    let load_saved = move |value: String| {
+        if let Some(text) = saved.get(&value) {
+            schema.set(text);
+            set_name.set(value.clone());
+        }
+        set_selected.set(value);
+    };
+    let save = move || {
+        let value = name.get_untracked().trim().to_string();
+        if value.is_empty() {
+            return;
+        }
+        saved.save(value.clone(), schema.get_untracked());
+        set_selected.set(value);
+    };
+    let remove = move || {
+        saved.remove(&selected.get_untracked());
+        set_selected.set(String::new());
+    };
+
+    view! {
+        <div class="space-y-4">
+            <div>
+                <label class="block text-sm font-medium text-gray-700 mb-2">
+                    {move || {
+                        let _ = current_locale.get();
+                        t!("tools.json.schema.label")
+                    }}
+                </label>
+                <textarea
+                    class="w-full h-48 p-2 border border-gray-300 rounded-md shadow-sm font-mono text-sm focus:ring-blue-500 focus:border-blue-500"
+                    placeholder=move || {
+                        let _ = current_locale.get();
+                        t!("tools.json.schema.placeholder")
+                    }
+                    on:input=move |ev| schema.set(event_target_value(&ev))
+                    prop:value=schema
+                ></textarea>
+            </div>
+
+            <div class="flex flex-wrap items-center gap-4">
+                <label class="flex items-center gap-2 text-sm text-gray-700">
+                    {move || {
+                        let _ = current_locale.get();
+                        t!("tools.json.schema.draft")
+                    }}
+                    <select
+                        class="px-2 py-1 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500 focus:border-blue-500"
+                        prop:value=move || draft.get().map_or("auto", |draft| draft.as_param())
+                        on:change=move |ev| on_draft.call(Draft::from_param(&event_target_value(&ev)))
+                    >
+                        <option value="auto">{move || {
+                            let _ = current_locale.get();
+                            t!("tools.json.schema.draft_auto")
+                        }}</option>
+                        {Draft::ALL
+                            .into_iter()
+                            .map(|draft| view! {
+                                <option value=draft.as_param()>{move || {
+                                    let _ = current_locale.get();
+                                    t!(&format!("tools.json.schema.drafts.{}", draft.as_param()))
+                                }}</option>
+                            })
+                            .collect_view()}
+                    </select>
+                </label>
+                <label class="flex items-center gap-2 text-sm text-gray-700">
+                    {move || {
+                        let _ = current_locale.get();
+                        t!("tools.json.schema.saved")
+                    }}
+                    <select
+                        class="px-2 py-1 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500 focus:border-blue-500"
+                        prop:value=selected
+                        on:change=move |ev| load_saved(event_target_value(&ev))
+                    >
+                        <option value="">{move || {
+                            let _ = current_locale.get();
+                            t!("tools.json.schema.choose")
+                        }}</option>
+                        {move || {
+                            saved
+                                .names()
+                                .into_iter()
+                                .map(|name| view! { <option value=name.clone()>{name}</option> })
+                                .collect_view()
+                        }}
+                    </select>
+                </label>
+                <button
+                    class="inline-flex items-center px-3 py-1 border border-gray-300 shadow-sm text-sm leading-4 font-medium rounded-md text-gray-700 bg-white hover:bg-gray-50 disabled:opacity-50"
+                    prop:disabled=move || selected.with(String::is_empty)
+                    on:click=move |_| remove()
+                >
+                    {move || {
+                        let _ = current_locale.get();
+                        t!("tools.json.schema.delete")
+                    }}
+                </button>
+                <div class="flex items-center gap-2">
+                    <input
+                        type="text"
+                        class="px-2 py-1 border border-gray-300 rounded-md text-sm focus:outline-none focus:ring-2 focus:ring-blue-500 focus:border-blue-500"
+                        placeholder=move || {
+                            let _ = current_locale.get();
+                            t!("tools.json.schema.name")
+                        }
+                        on:input=move |ev| set_name.set(event_target_value(&ev))
+                        prop:value=name
+                    />
+                    <button
+                        class="inline-flex items-center px-3 py-1 border border-gray-300 shadow-sm text-sm leading-4 font-medium rounded-md text-gray-700 bg-white hover:bg-gray-50 disabled:opacity-50"
+                        prop:disabled=move || name.with(|name| name.trim().is_empty()) || schema.with(String::is_empty)
+                        on:click=move |_| save()
+                    >
+                        {move || {
+                            let _ = current_locale.get();
+                            t!("tools.json.schema.save")
+                        }}
+                    </button>
+                </div>
+            </div>
+
+            {move || {
+                let _ = current_locale.get();
+                errors.get().map(|result| match result {
+                    Err(failure) => {
+                        let (key, message) = match failure {
+                            Failure::InvalidDocument(message) => ("tools.json.schema.invalid_document", message),
+                            Failure::InvalidSchema(message) => ("tools.json.schema.invalid_schema", message),
+                        };
+                        view! {
+                            <div class="p-4 bg-red-50 border border-red-200 rounded-lg text-red-700 whitespace-pre-wrap">
+                                {format!("{}: {}", t!(key), message)}
+                            </div>
+                        }
+                        .into_view()
+                    }
+                    Ok((0, _)) => view! {
+                        <div class="p-4 bg-green-50 border border-green-200 rounded-lg text-green-700">
+                            {t!("tools.json.schema.valid")}
+                        </div>
+                    }
+                    .into_view(),
+                    Ok((total, errors)) => view! {
+                        <div class="border border-red-200 rounded-lg">
+                            <p class="px-3 py-2 bg-red-50 text-sm font-medium text-red-700">
+                                {if total > errors.len() {
+                                    t!("tools.json.schema.errors_shown", count = total, shown = errors.len())
+                                } else {
+                                    t!("tools.json.schema.errors", count = total)
+                                }}
+                            </p>
+                            <ul class="max-h-96 overflow-auto divide-y divide-red-100 text-sm">
+                                {errors
+                                    .into_iter()
+                                    .map(|(error, location)| view! {
+                                        <li class="px-3 py-2 space-y-1">
+                                            <div class="flex flex-wrap items-center gap-2">
+                                                <span class="font-mono text-purple-800">{error.instance_path.to_string()}</span>
+                                                <span class="text-gray-800">{error.message}</span>
+                                                {location.map(|location| view! {
+                                                    <button
+                                                        class="ml-auto text-xs text-blue-600 hover:underline"
+                                                        on:click=move |_| on_locate.call(location)
+                                                    >
+                                                        {t!(
+                                                            "tools.json.schema.line",
+                                                            line = location.line,
+                                                            column = location.column
+                                                        )}
+                                                    </button>
+                                                })}
+                                            </div>
+                                            <div class="font-mono text-xs text-gray-500">
+                                                {format!("#{}", error.schema_path.to_pointer())}
+                                            </div>
+                                        </li>
+                                    })
+                                    .collect_view()}
+                            </ul>
+                        </div>
+                    }
+                    .into_view(),
+                })
+            }}
+        </div>
+    }
+}
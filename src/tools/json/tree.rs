@@ -0,0 +1,244 @@
+use leptos::*;
+use rust_i18n::t;
+use dev_tools_core::json::{format_value, FormatOptions, FormatType};
+use dev_tools_core::path::JsonPath;
+use crate::utils::copy_to_clipboard;
+use serde_json::Value;
+use std::rc::Rc;
+use std::time::Duration;
+
+/// 默认展开的层数
+const EXPANDED_DEPTH: usize = 2;
+
+/// 每次显示的子节点数，其余的通过“显示更多”加载
+const PAGE_SIZE: usize = 100;
+
+/// 字符串值预览的最大字符数
+const PREVIEW_CHARS: usize = 80;
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Object(_) => "object",
+        Value::Array(_) => "array",
+        Value::String(_) => "string",
+        Value::Number(_) => "number",
+        Value::Bool(_) => "boolean",
+        Value::Null => "null",
+    }
+}
+
+fn badge_class(value: &Value) -> &'static str {
+    match value {
+        Value::Object(_) => "bg-purple-100 text-purple-700",
+        Value::Array(_) => "bg-indigo-100 text-indigo-700",
+        Value::String(_) => "bg-green-100 text-green-700",
+        Value::Number(_) => "bg-blue-100 text-blue-700",
+        Value::Bool(_) => "bg-yellow-100 text-yellow-800",
+        Value::Null => "bg-gray-100 text-gray-600",
+    }
+}
+
+fn preview(value: &Value) -> String {
+    match value {
+        Value::String(text) if text.chars().count() > PREVIEW_CHARS => {
+            let truncated: String = text.chars().take(PREVIEW_CHARS).collect();
+            format!("{}…", Value::from(truncated))
+        }
+        other => other.to_string(),
+    }
+}
+
+/// 复制值时字符串不带引号，其余按标准格式输出
+fn copy_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        other => format_value(other, FormatType::Standard, &FormatOptions::default()).unwrap_or_default(),
+    }
+}
+
+/// 可折叠的 JSON 树，点击键名复制 JSONPath，悬停时可复制值；`marked` 中的路径标为从字符串展开
+#[component]
+pub fn JsonTree(
+    document: Rc<Value>,
+    #[prop(optional)] marked: Rc<Vec<String>>,
+    current_locale: RwSignal<String>,
+) -> impl IntoView {
+    let (copied, set_copied) = create_signal(None::<String>);
+    let on_copy = Callback::new(move |(text, notice): (String, String)| {
+        copy_to_clipboard(&text);
+        set_copied.set(Some(notice));
+        set_timeout(move || set_copied.set(None), Duration::from_secs(2));
+    });
+
+    view! {
+        <div class="p-2 max-h-96 bg-gray-50 border border-gray-200 rounded-md overflow-auto font-mono text-sm">
+            <p class="mb-2 font-sans text-xs text-gray-500">
+                {move || {
+                    let _ = current_locale.get();
+                    match copied.get() {
+                        Some(notice) => notice,
+                        None => t!("tools.json.tree.hint"),
+                    }
+                }}
+            </p>
+            {node_view(document, JsonPath::root(), "$".to_string(), 0, marked, on_copy, current_locale)}
+        </div>
+    }
+}
+
+fn node_view(
+    document: Rc<Value>,
+    path: JsonPath,
+    label: String,
+    depth: usize,
+    marked: Rc<Vec<String>>,
+    on_copy: Callback<(String, String)>,
+    current_locale: RwSignal<String>,
+) -> View {
+    let Some(value) = path.lookup(&document) else {
+        return View::default();
+    };
+    let path_text = path.to_string();
+    let badge = format!("px-1 rounded font-sans text-xs {}", badge_class(value));
+    let kind = type_name(value);
+
+    let copy_path = {
+        let path_text = path_text.clone();
+        move |_| {
+            on_copy.call((path_text.clone(), format!("{} {}", t!("tools.json.tree.copied"), path_text)));
+        }
+    };
+    // 值的文本在点击时才生成，渲染时不必格式化整棵子树
+    let copy_value = {
+        let (document, path, path_text) = (document.clone(), path.clone(), path_text.clone());
+        move |_| {
+            let Some(value) = path.lookup(&document) else {
+                return;
+            };
+            let notice = format!("{} {}", t!("tools.json.tree.copied_value"), path_text);
+            on_copy.call((copy_text(value), notice));
+        }
+    };
+    let actions = view! {
+        <span class="hidden group-hover:inline-flex gap-2 font-sans text-xs">
+            <button class="text-gray-500 hover:text-blue-600" on:click=copy_path.clone()>
+                {move || {
+                    let _ = current_locale.get();
+                    t!("tools.json.tree.copy_path")
+                }}
+            </button>
+            <button class="text-gray-500 hover:text-blue-600" on:click=copy_value>
+                {move || {
+                    let _ = current_locale.get();
+                    t!("tools.json.tree.copy_value")
+                }}
+            </button>
+        </span>
+    };
+    let marker = marked.contains(&path_text).then(|| view! {
+        <span class="px-1 rounded font-sans text-xs bg-orange-100 text-orange-700">
+            {move || {
+                let _ = current_locale.get();
+                t!("tools.json.tree.unwrapped")
+            }}
+        </span>
+    });
+    let key = view! {
+        <button class="text-purple-800 hover:underline" title=path_text on:click=copy_path>
+            {label}
+        </button>
+        {marker}
+    };
+
+    let count = match value {
+        Value::Object(map) => map.len(),
+        Value::Array(items) => items.len(),
+        _ => 0,
+    };
+    if count == 0 {
+        return view! {
+            <div class="group flex items-center gap-2 py-0.5">
+                <span class="w-4"></span>
+                {key}
+                <span class=badge>{kind}</span>
+                <span class="text-gray-800 break-all">{preview(value)}</span>
+                {actions}
+            </div>
+        }
+        .into_view();
+    }
+
+    let is_object = value.is_object();
+    let (expanded, set_expanded) = create_signal(depth < EXPANDED_DEPTH);
+    let (limit, set_limit) = create_signal(PAGE_SIZE);
+    // 只在展开时渲染子节点，大文档也能很快显示
+    let children = move || {
+        if !expanded.get() {
+            return None;
+        }
+        let value = path.lookup(&document)?;
+        let entries: Vec<(JsonPath, String)> = match value {
+            Value::Object(map) => map
+                .keys()
+                .take(limit.get())
+                .map(|key| (path.key(key), key.clone()))
+                .collect(),
+            Value::Array(items) => (0..items.len().min(limit.get()))
+                .map(|index| (path.index(index), format!("[{}]", index)))
+                .collect(),
+            _ => Vec::new(),
+        };
+        let remaining = count.saturating_sub(limit.get());
+        let document = document.clone();
+        let marked = marked.clone();
+        Some(view! {
+            <ul class="ml-2 pl-3 border-l border-gray-200">
+                {entries
+                    .into_iter()
+                    .map(|(child, label)| view! {
+                        <li>{node_view(document.clone(), child, label, depth + 1, marked.clone(), on_copy, current_locale)}</li>
+                    })
+                    .collect_view()}
+                {(remaining > 0).then(|| view! {
+                    <li>
+                        <button
+                            class="font-sans text-xs text-blue-600 hover:underline"
+                            on:click=move |_| set_limit.update(|limit| *limit += PAGE_SIZE)
+                        >
+                            {move || {
+                                let _ = current_locale.get();
+                                format!("{} ({})", t!("tools.json.tree.show_more"), remaining)
+                            }}
+                        </button>
+                    </li>
+                })}
+            </ul>
+        })
+    };
+
+    view! {
+        <div class="group flex items-center gap-2 py-0.5">
+            <button
+                class="w-4 text-gray-500 hover:text-gray-900"
+                on:click=move |_| set_expanded.update(|expanded| *expanded = !*expanded)
+            >
+                {move || if expanded.get() { "▾" } else { "▸" }}
+            </button>
+            {key}
+            <span class=badge>{kind}</span>
+            <span class="font-sans text-xs text-gray-500">
+                {move || {
+                    let _ = current_locale.get();
+                    if is_object {
+                        t!("tools.json.tree.keys", count = count)
+                    } else {
+                        t!("tools.json.tree.items", count = count)
+                    }
+                }}
+            </span>
+            {actions}
+        </div>
+        {children}
+    }
+    .into_view()
+}
@@ -1,4 +1,55 @@
+use leptos::*;
+use crate::utils::I18nState;
+use std::rc::Rc;
+
 pub mod base64;
 pub mod json;
+pub mod paste;
 pub mod url;
-pub mod cron;
\ No newline at end of file
+pub mod cron;
+pub mod pipeline;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ToolCategory {
+    Encoding,
+    Formatting,
+    Time,
+    Workflow,
+}
+
+/// A tool that can be mounted in the app.
+///
+/// Adding a tool only requires implementing this trait and listing it in [`TOOLS`];
+/// the navigation and the main view are built from the registry.
+pub trait DevTool: Sync {
+    /// Stable identifier, unique within the registry.
+    fn id(&self) -> &'static str;
+
+    /// Route of the tool, e.g. `/json`.
+    fn path(&self) -> String {
+        format!("/{}", self.id())
+    }
+
+    /// Locale key of the name shown in the navigation.
+    fn name_key(&self) -> &'static str;
+
+    fn category(&self) -> ToolCategory;
+
+    /// SVG path data (24x24 viewBox, stroked) for the navigation icon.
+    fn icon(&self) -> &'static str;
+
+    fn view(&self, i18n: Rc<I18nState>) -> View;
+}
+
+pub static TOOLS: &[&dyn DevTool] = &[
+    &url::Url,
+    &base64::Base64,
+    &json::Json,
+    &cron::Cron,
+    &paste::Paste,
+    &pipeline::Pipeline,
+];
+
+pub fn find_tool(id: &str) -> Option<&'static dyn DevTool> {
+    TOOLS.iter().copied().find(|tool| tool.id() == id)
+}
@@ -0,0 +1,124 @@
+use leptos::*;
+use leptos_router::use_navigate;
+use rust_i18n::t;
+use dev_tools_core::detect::{detect, Kind};
+use crate::tools::{find_tool, DevTool, ToolCategory};
+use crate::utils::I18nState;
+use crate::utils::share::share_path;
+use std::rc::Rc;
+
+const ID: &str = "paste";
+
+pub struct Paste;
+
+impl DevTool for Paste {
+    fn id(&self) -> &'static str {
+        ID
+    }
+
+    fn name_key(&self) -> &'static str {
+        "nav.paste"
+    }
+
+    fn category(&self) -> ToolCategory {
+        ToolCategory::Workflow
+    }
+
+    fn icon(&self) -> &'static str {
+        "M9 5H7a2 2 0 00-2 2v12a2 2 0 002 2h10a2 2 0 002-2V7a2 2 0 00-2-2h-2M9 5a2 2 0 002 2h2a2 2 0 002-2M9 5a2 2 0 012-2h2a2 2 0 012 2"
+    }
+
+    fn view(&self, i18n: Rc<I18nState>) -> View {
+        view! { <PasteTool i18n=i18n/> }.into_view()
+    }
+}
+
+fn kind_name(kind: Kind) -> String {
+    t!(&format!("tools.paste.kinds.{}", kind.as_param()))
+}
+
+/// 粘贴任意内容，识别其格式并推荐对应的工具
+#[component]
+pub fn PasteTool(i18n: Rc<I18nState>) -> impl IntoView {
+    let current_locale = i18n.locale();
+    let navigate = use_navigate();
+
+    let (input, set_input) = create_signal(String::new());
+    let candidates = create_memo(move |_| detect(&input.get()));
+
+    view! {
+        <div class="p-6">
+            <div class="space-y-4">
+                <h2 class="text-lg font-semibold text-gray-900">
+                    {move || {
+                        let _ = current_locale.get();
+                        t!("tools.paste.title")
+                    }}
+                </h2>
+                <textarea
+                    class="w-full h-48 p-2 border border-gray-300 rounded-md shadow-sm font-mono focus:ring-blue-500 focus:border-blue-500"
+                    autofocus
+                    placeholder=move || {
+                        let _ = current_locale.get();
+                        t!("tools.paste.placeholder")
+                    }
+                    on:input=move |ev| set_input.set(event_target_value(&ev))
+                    prop:value=input
+                ></textarea>
+
+                <Show
+                    when=move || {
+                        !input.with(|input| input.trim().is_empty()) && candidates.with(Vec::is_empty)
+                    }
+                    fallback=|| view! { }
+                >
+                    <p class="text-sm text-gray-500">
+                        {move || {
+                            let _ = current_locale.get();
+                            t!("tools.paste.no_match")
+                        }}
+                    </p>
+                </Show>
+
+                <ul class="space-y-3">
+                    {move || {
+                        let _ = current_locale.get();
+                        let navigate = navigate.clone();
+                        candidates
+                            .get()
+                            .into_iter()
+                            .filter_map(|candidate| {
+                                let tool = find_tool(candidate.kind.tool_id())?;
+                                let state = candidate.state;
+                                let navigate = navigate.clone();
+                                Some(view! {
+                                    <li class="p-3 border border-gray-200 rounded-lg">
+                                        <div class="flex justify-between items-center mb-2 gap-4">
+                                            <span class="text-sm font-medium text-gray-900">
+                                                {kind_name(candidate.kind)}
+                                            </span>
+                                            <div class="flex items-center gap-3">
+                                                <span class="text-xs text-gray-500">
+                                                    {format!("{}%", candidate.score)}
+                                                </span>
+                                                <button
+                                                    class="inline-flex items-center px-3 py-1 border border-transparent text-sm font-medium rounded-md shadow-sm text-white bg-blue-600 hover:bg-blue-700 focus:outline-none focus:ring-2 focus:ring-offset-2 focus:ring-blue-500"
+                                                    on:click=move |_| navigate(&share_path(&tool.path(), &state), Default::default())
+                                                >
+                                                    {format!("{} {}", t!("tools.paste.open_in"), t!(tool.name_key()))}
+                                                </button>
+                                            </div>
+                                        </div>
+                                        <pre class="p-2 max-h-48 bg-gray-50 border border-gray-200 rounded-md overflow-auto whitespace-pre-wrap font-mono text-sm">
+                                            {candidate.preview}
+                                        </pre>
+                                    </li>
+                                })
+                            })
+                            .collect_view()
+                    }}
+                </ul>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,225 @@
+use leptos::*;
+use rust_i18n::t;
+use dev_tools_core::pipeline::{run, steps_from_param, steps_to_param, Step};
+use crate::tools::{DevTool, ToolCategory};
+use crate::utils::{copy_to_clipboard, use_query_setter, I18nState};
+use crate::utils::history::{load_draft, save_draft, HistoryDrawer, ToolHistory};
+use crate::utils::share::{initial_option, read_shared_state, ShareButton, SharedState};
+use std::rc::Rc;
+
+const ID: &str = "pipeline";
+
+pub struct Pipeline;
+
+impl DevTool for Pipeline {
+    fn id(&self) -> &'static str {
+        ID
+    }
+
+    fn name_key(&self) -> &'static str {
+        "nav.pipeline"
+    }
+
+    fn category(&self) -> ToolCategory {
+        ToolCategory::Workflow
+    }
+
+    fn icon(&self) -> &'static str {
+        "M13 7l5 5m0 0l-5 5m5-5H6"
+    }
+
+    fn view(&self, i18n: Rc<I18nState>) -> View {
+        view! { <PipelineTool i18n=i18n/> }.into_view()
+    }
+}
+
+fn step_name(step: Step) -> String {
+    t!(&format!("tools.pipeline.steps.{}", step.as_param()))
+}
+
+#[component]
+pub fn PipelineTool(i18n: Rc<I18nState>) -> impl IntoView {
+    let current_locale = i18n.locale();
+
+    let shared = read_shared_state();
+    let (input, set_input) = create_signal(
+        shared
+            .as_ref()
+            .map(|state| state.input.clone())
+            .or_else(|| load_draft(ID))
+            .unwrap_or_default(),
+    );
+    let history = ToolHistory::new(ID);
+    create_effect(move |_| save_draft(ID, &input.get()));
+
+    // 步骤保存在查询参数中，例如 `/pipeline?steps=url_decode,base64_decode`
+    let (steps, set_steps) = create_signal(
+        initial_option(&shared, "steps")
+            .map(|value| steps_from_param(&value))
+            .unwrap_or_default(),
+    );
+    let set_query = use_query_setter();
+    let update_steps = move |f: &dyn Fn(&mut Vec<Step>)| {
+        set_steps.update(|steps| f(steps));
+        set_query.call(("steps", steps_to_param(&steps.get_untracked())));
+    };
+
+    let share_state = move || {
+        SharedState::new(input.get_untracked())
+            .with_option("steps", steps_to_param(&steps.get_untracked()))
+    };
+
+    let restore = move |state: SharedState| {
+        set_input.set(state.input.clone());
+        let restored = steps_from_param(state.option("steps").unwrap_or_default());
+        update_steps(&|steps| *steps = restored.clone());
+    };
+
+    view! {
+        <div class="p-6">
+            <div class="space-y-4">
+                <h2 class="text-lg font-semibold text-gray-900">
+                    {move || {
+                        let _ = current_locale.get();
+                        t!("tools.pipeline.title")
+                    }}
+                </h2>
+                <div>
+                    <label class="block text-sm font-medium text-gray-700 mb-2">
+                        {move || {
+                            let _ = current_locale.get();
+                            t!("tools.common.input_placeholder")
+                        }}
+                    </label>
+                    <textarea
+                        class="w-full h-32 p-2 border border-gray-300 rounded-md shadow-sm focus:ring-blue-500 focus:border-blue-500"
+                        placeholder=move || {
+                            let _ = current_locale.get();
+                            t!("tools.common.input_placeholder")
+                        }
+                        on:input=move |ev| set_input.set(event_target_value(&ev))
+                        prop:value=input
+                    ></textarea>
+                </div>
+
+                <div class="flex flex-wrap gap-2">
+                    <select
+                        class="px-3 py-2 border border-gray-300 rounded-md focus:outline-none focus:ring-2 focus:ring-blue-500 focus:border-blue-500"
+                        prop:value=""
+                        on:change=move |ev| {
+                            if let Some(step) = Step::from_param(&event_target_value(&ev)) {
+                                update_steps(&|steps| steps.push(step));
+                            }
+                            event_target::<web_sys::HtmlSelectElement>(&ev).set_value("");
+                        }
+                    >
+                        <option value="">{move || {
+                            let _ = current_locale.get();
+                            t!("tools.pipeline.add_step")
+                        }}</option>
+                        {Step::ALL
+                            .into_iter()
+                            .map(|step| view! {
+                                <option value=step.as_param()>{move || {
+                                    let _ = current_locale.get();
+                                    step_name(step)
+                                }}</option>
+                            })
+                            .collect_view()}
+                    </select>
+                    <button
+                        class="inline-flex items-center px-4 py-2 border border-transparent text-sm font-medium rounded-md shadow-sm text-white bg-blue-600 hover:bg-blue-700 focus:outline-none focus:ring-2 focus:ring-offset-2 focus:ring-blue-500"
+                        on:click=move |_| history.save(share_state())
+                    >
+                        {move || {
+                            let _ = current_locale.get();
+                            t!("tools.pipeline.save")
+                        }}
+                    </button>
+                    <ShareButton state=share_state current_locale=current_locale/>
+                    <HistoryDrawer history=history current_locale=current_locale on_restore=restore/>
+                </div>
+
+                <Show
+                    when=move || !steps.with(Vec::is_empty)
+                    fallback=move || view! {
+                        <p class="text-sm text-gray-500">
+                            {move || {
+                                let _ = current_locale.get();
+                                t!("tools.pipeline.empty")
+                            }}
+                        </p>
+                    }
+                >
+                    <ol class="space-y-3">
+                        {move || {
+                            let _ = current_locale.get();
+                            let steps = steps.get();
+                            let results = run(&input.get(), &steps);
+                            steps
+                                .into_iter()
+                                .enumerate()
+                                .map(|(index, step)| {
+                                    let result = results.get(index);
+                                    let failed = result.is_some_and(|result| result.output.is_err());
+                                    let body = match result.map(|result| &result.output) {
+                                        Some(Ok(output)) => {
+                                            let output = output.clone();
+                                            let copied = output.clone();
+                                            view! {
+                                                <div class="relative">
+                                                    <pre class="p-2 max-h-48 bg-gray-50 border border-gray-200 rounded-md overflow-auto whitespace-pre-wrap font-mono text-sm">
+                                                        {output}
+                                                    </pre>
+                                                    <button
+                                                        class="absolute top-2 right-2 inline-flex items-center px-3 py-1 border border-gray-300 shadow-sm text-sm leading-4 font-medium rounded-md text-gray-700 bg-white hover:bg-gray-50"
+                                                        on:click=move |_| copy_to_clipboard(&copied)
+                                                    >
+                                                        {t!("tools.common.copy")}
+                                                    </button>
+                                                </div>
+                                            }.into_view()
+                                        }
+                                        Some(Err(e)) => view! {
+                                            <div class="p-2 bg-red-50 border border-red-200 rounded-md text-red-700 whitespace-pre-wrap text-sm">
+                                                {e.to_string()}
+                                            </div>
+                                        }.into_view(),
+                                        // 前面的步骤失败，本步骤未执行
+                                        None => view! {
+                                            <p class="text-sm text-gray-400">{t!("tools.pipeline.skipped")}</p>
+                                        }.into_view(),
+                                    };
+                                    view! {
+                                        <li class=if failed {
+                                            "p-3 border border-red-300 rounded-lg"
+                                        } else {
+                                            "p-3 border border-gray-200 rounded-lg"
+                                        }>
+                                            <div class="flex justify-between items-center mb-2">
+                                                <span class="text-sm font-medium text-gray-700">
+                                                    {format!("{}. {}", index + 1, step_name(step))}
+                                                </span>
+                                                <button
+                                                    class="text-xs text-gray-500 hover:text-red-600"
+                                                    on:click=move |_| update_steps(&|steps| {
+                                                        if index < steps.len() {
+                                                            steps.remove(index);
+                                                        }
+                                                    })
+                                                >
+                                                    {t!("tools.pipeline.remove_step")}
+                                                </button>
+                                            </div>
+                                            {body}
+                                        </li>
+                                    }
+                                })
+                                .collect_view()
+                        }}
+                    </ol>
+                </Show>
+            </div>
+        </div>
+    }
+}
@@ -1,30 +1,78 @@
 use leptos::*;
 use rust_i18n::t;
-use urlencoding;
-use crate::utils::{copy_to_clipboard, I18nState};
+use dev_tools_core::url as core_url;
+use crate::tools::{DevTool, ToolCategory};
+use crate::utils::{copy_to_clipboard, use_query_setter, I18nState};
+use crate::utils::history::{load_draft, save_draft, HistoryDrawer, ToolHistory};
+use crate::utils::share::{initial_option, read_shared_state, ShareButton, SharedState};
 use std::rc::Rc;
 
+const ID: &str = "url";
+
+pub struct Url;
+
+impl DevTool for Url {
+    fn id(&self) -> &'static str {
+        ID
+    }
+
+    fn name_key(&self) -> &'static str {
+        "nav.url_encoder"
+    }
+
+    fn category(&self) -> ToolCategory {
+        ToolCategory::Encoding
+    }
+
+    fn icon(&self) -> &'static str {
+        "M13.828 10.172a4 4 0 00-5.656 0l-4 4a4 4 0 105.656 5.656l1.102-1.101m-.758-4.899a4 4 0 005.656 0l4-4a4 4 0 00-5.656-5.656l-1.1 1.1"
+    }
+
+    fn view(&self, i18n: Rc<I18nState>) -> View {
+        view! { <UrlTool i18n=i18n/> }.into_view()
+    }
+}
+
 #[component]
 pub fn UrlTool(i18n: Rc<I18nState>) -> impl IntoView {
     let current_locale = i18n.locale();
     
-    let (input, set_input) = create_signal(String::new());
+    let shared = read_shared_state();
+    let (input, set_input) = create_signal(
+        shared
+            .as_ref()
+            .map(|state| state.input.clone())
+            .or_else(|| load_draft(ID))
+            .unwrap_or_default(),
+    );
+    let history = ToolHistory::new(ID);
+    create_effect(move |_| save_draft(ID, &input.get()));
     let (output, set_output) = create_signal(String::new());
     let (error, set_error) = create_signal(String::new());
-    let (mode, set_mode) = create_signal(true); // true for encode, false for decode
+    // true for encode, false for decode, `/url?mode=decode` 可直接打开解码
+    let (mode, set_mode) = create_signal(initial_option(&shared, "mode").as_deref() != Some("decode"));
+    let set_query = use_query_setter();
+    let select_mode = move |encode: bool| {
+        set_mode.set(encode);
+        set_query.call(("mode", if encode { "encode" } else { "decode" }.to_string()));
+    };
+
+    let share_state = move || {
+        SharedState::new(input.get_untracked())
+            .with_option("mode", if mode.get_untracked() { "encode" } else { "decode" })
+    };
 
     let encode = move || {
-        let input_text = input.get();
-        let result = urlencoding::encode(&input_text);
-        set_output.set(result.into_owned());
+        history.record(share_state());
+        set_output.set(core_url::encode(&input.get()));
         set_error.set(String::new());
     };
 
     let decode = move || {
-        let input_text = input.get();
-        match urlencoding::decode(&input_text) {
+        history.record(share_state());
+        match core_url::decode(&input.get()) {
             Ok(result) => {
-                set_output.set(result.into_owned());
+                set_output.set(result);
                 set_error.set(String::new());
             }
             Err(e) => {
@@ -33,6 +81,26 @@ pub fn UrlTool(i18n: Rc<I18nState>) -> impl IntoView {
         }
     };
 
+    // 通过分享链接打开时，直接显示对方看到的结果
+    if shared.is_some() {
+        if mode.get_untracked() {
+            encode();
+        } else {
+            decode();
+        }
+    }
+
+    let restore = move |state: SharedState| {
+        set_input.set(state.input.clone());
+        if state.option("mode") == Some("decode") {
+            select_mode(false);
+            decode();
+        } else {
+            select_mode(true);
+            encode();
+        }
+    };
+
     view! {
         <div class="space-y-4 p-6">
             <div class="flex justify-between items-center">
@@ -48,7 +116,7 @@ pub fn UrlTool(i18n: Rc<I18nState>) -> impl IntoView {
                             type="radio"
                             class="form-radio"
                             prop:checked=move || mode.get()
-                            on:change=move |_| set_mode.set(true)
+                            on:change=move |_| select_mode(true)
                         />
                         <span class="ml-2">
                             {move || {
@@ -62,7 +130,7 @@ pub fn UrlTool(i18n: Rc<I18nState>) -> impl IntoView {
                             type="radio"
                             class="form-radio"
                             prop:checked=move || !mode.get()
-                            on:change=move |_| set_mode.set(false)
+                            on:change=move |_| select_mode(false)
                         />
                         <span class="ml-2">
                             {move || {
@@ -110,6 +178,8 @@ pub fn UrlTool(i18n: Rc<I18nState>) -> impl IntoView {
                         }
                     }}
                 </button>
+                <ShareButton state=share_state current_locale=current_locale/>
+                <HistoryDrawer history=history current_locale=current_locale on_restore=restore/>
             </div>
             <Show
                 when=move || !error.get().is_empty()
@@ -139,7 +209,7 @@ pub fn UrlTool(i18n: Rc<I18nState>) -> impl IntoView {
                     <button
                         class="absolute top-2 right-2 inline-flex items-center px-3 py-1 border border-gray-300 shadow-sm text-sm leading-4 font-medium rounded-md text-gray-700 bg-white hover:bg-gray-50 focus:outline-none focus:ring-2 focus:ring-offset-2 focus:ring-blue-500"
                         on:click=move |_| {
-                            let _ = copy_to_clipboard(&output.get());
+                            copy_to_clipboard(&output.get());
                         }
                     >
                         {move || {
@@ -0,0 +1,255 @@
+use leptos::*;
+use rust_i18n::t;
+use chrono::{DateTime, Local, Utc};
+use dev_tools_core::history::{self, HistoryEntry, DEFAULT_LIMIT, MAX_BYTES};
+use dev_tools_core::share::MAX_INPUT;
+use web_sys::Storage;
+use super::share::SharedState;
+
+/// 超过该大小（字节）的输入不写入 localStorage，避免超出配额；与分享链接的上限相同
+const MAX_STORED_INPUT: usize = MAX_INPUT;
+
+pub(crate) fn local_storage() -> Option<Storage> {
+    window().local_storage().ok().flatten()
+}
+
+fn history_key(tool_id: &str) -> String {
+    format!("history.{}", tool_id)
+}
+
+fn draft_key(tool_id: &str) -> String {
+    format!("draft.{}", tool_id)
+}
+
+/// 读取工具未提交的输入，切换工具后回来时恢复
+pub fn load_draft(tool_id: &str) -> Option<String> {
+    local_storage()?.get_item(&draft_key(tool_id)).ok().flatten()
+}
+
+pub fn save_draft(tool_id: &str, input: &str) {
+    let Some(storage) = local_storage() else {
+        return;
+    };
+    // 太大的输入不保存，同时删掉旧草稿，免得切换回来时恢复出更早的内容
+    if input.len() > MAX_STORED_INPUT {
+        let _ = storage.remove_item(&draft_key(tool_id));
+    } else {
+        let _ = storage.set_item(&draft_key(tool_id), input);
+    }
+}
+
+/// 每个工具的输入历史，保存在 localStorage 的 `history.<工具 id>` 中
+#[derive(Clone, Copy)]
+pub struct ToolHistory {
+    tool_id: &'static str,
+    entries: RwSignal<Vec<HistoryEntry>>,
+    // 最近一次写入 localStorage 是否失败（通常是超出配额），失败时列表中的记录刷新后会丢失
+    save_failed: RwSignal<bool>,
+}
+
+impl ToolHistory {
+    pub fn new(tool_id: &'static str) -> Self {
+        let entries = local_storage()
+            .and_then(|storage| storage.get_item(&history_key(tool_id)).ok().flatten())
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+        Self {
+            tool_id,
+            entries: create_rw_signal(entries),
+            save_failed: create_rw_signal(false),
+        }
+    }
+
+    pub fn entries(&self) -> Signal<Vec<HistoryEntry>> {
+        self.entries.into()
+    }
+
+    pub fn record(&self, state: SharedState) {
+        if state.input.is_empty() || state.input.len() > MAX_STORED_INPUT {
+            return;
+        }
+        let timestamp = Utc::now().timestamp_millis();
+        self.update(|entries| history::record(entries, state, timestamp, DEFAULT_LIMIT));
+    }
+
+    /// 记录并固定，用于用户主动保存的内容，不会因条数或大小限制被挤掉
+    pub fn save(&self, state: SharedState) {
+        if state.input.is_empty() || state.input.len() > MAX_STORED_INPUT {
+            return;
+        }
+        let timestamp = Utc::now().timestamp_millis();
+        self.update(|entries| {
+            history::record(entries, state, timestamp, DEFAULT_LIMIT);
+            entries[0].pinned = true;
+        });
+    }
+
+    pub fn toggle_pin(&self, index: usize) {
+        self.update(|entries| {
+            if let Some(entry) = entries.get_mut(index) {
+                entry.pinned = !entry.pinned;
+            }
+        });
+    }
+
+    pub fn remove(&self, index: usize) {
+        self.update(|entries| {
+            if index < entries.len() {
+                entries.remove(index);
+            }
+        });
+    }
+
+    /// 清空历史，保留已固定的记录
+    pub fn clear(&self) {
+        self.update(|entries| entries.retain(|entry| entry.pinned));
+    }
+
+    pub fn save_failed(&self) -> Signal<bool> {
+        self.save_failed.into()
+    }
+
+    /// 修改记录并写回 localStorage；总大小超过 `MAX_BYTES` 时先删除最旧的未固定记录
+    fn update(&self, f: impl FnOnce(&mut Vec<HistoryEntry>)) {
+        let mut json = String::new();
+        self.entries.update(|entries| {
+            f(entries);
+            json = history::serialize_within(entries, MAX_BYTES);
+        });
+        if let Some(storage) = local_storage() {
+            self.save_failed.set(storage.set_item(&history_key(self.tool_id), &json).is_err());
+        }
+    }
+}
+
+fn format_timestamp(timestamp: i64) -> String {
+    DateTime::from_timestamp_millis(timestamp)
+        .map(|time| time.with_timezone(&Local).format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_default()
+}
+
+fn preview(input: &str) -> String {
+    let line = input.trim().lines().next().unwrap_or_default();
+    let mut preview: String = line.chars().take(60).collect();
+    if preview.len() < input.trim().len() {
+        preview.push('…');
+    }
+    preview
+}
+
+/// 历史记录按钮及下拉列表，点击记录时通过 `on_restore` 恢复输入和选项
+#[component]
+pub fn HistoryDrawer(
+    history: ToolHistory,
+    current_locale: RwSignal<String>,
+    #[prop(into)] on_restore: Callback<SharedState>,
+) -> impl IntoView {
+    let (open, set_open) = create_signal(false);
+    let entries = history.entries();
+
+    view! {
+        <div class="relative">
+            <button
+                class="inline-flex items-center px-4 py-2 border border-gray-300 text-sm font-medium rounded-md shadow-sm text-gray-700 bg-white hover:bg-gray-50 focus:outline-none focus:ring-2 focus:ring-offset-2 focus:ring-blue-500"
+                on:click=move |_| set_open.update(|open| *open = !*open)
+            >
+                {move || {
+                    let _ = current_locale.get();
+                    format!("{} ({})", t!("tools.history.title"), entries.with(Vec::len))
+                }}
+            </button>
+            <Show when=move || open.get() fallback=|| view! { }>
+                <div class="absolute z-10 mt-2 w-96 max-h-96 overflow-auto bg-white border border-gray-200 rounded-md shadow-lg">
+                    <Show when=move || history.save_failed().get() fallback=|| view! { }>
+                        <p class="p-3 text-xs text-red-700 bg-red-50 border-b border-red-100">
+                            {move || {
+                                let _ = current_locale.get();
+                                t!("tools.history.save_failed")
+                            }}
+                        </p>
+                    </Show>
+                    <Show
+                        when=move || !entries.with(Vec::is_empty)
+                        fallback=move || view! {
+                            <p class="p-4 text-sm text-gray-500">
+                                {move || {
+                                    let _ = current_locale.get();
+                                    t!("tools.history.empty")
+                                }}
+                            </p>
+                        }
+                    >
+                        <ul class="divide-y divide-gray-100">
+                            {move || {
+                                entries
+                                    .get()
+                                    .into_iter()
+                                    .enumerate()
+                                    .map(|(index, entry)| {
+                                        let state = entry.state.clone();
+                                        let options = entry
+                                            .state
+                                            .options
+                                            .iter()
+                                            .map(|(name, value)| format!("{}={}", name, value))
+                                            .collect::<Vec<_>>()
+                                            .join(" ");
+                                        view! {
+                                            <li class="p-3 hover:bg-gray-50">
+                                                <button
+                                                    class="block w-full text-left"
+                                                    on:click=move |_| {
+                                                        on_restore.call(state.clone());
+                                                        set_open.set(false);
+                                                    }
+                                                >
+                                                    <span class="block font-mono text-sm text-gray-900 truncate">
+                                                        {preview(&entry.state.input)}
+                                                    </span>
+                                                    <span class="block text-xs text-gray-500">
+                                                        {format_timestamp(entry.timestamp)} " " {options}
+                                                    </span>
+                                                </button>
+                                                <div class="mt-1 flex gap-3 text-xs">
+                                                    <button
+                                                        class=if entry.pinned { "text-blue-600 font-medium" } else { "text-gray-500 hover:text-blue-600" }
+                                                        on:click=move |_| history.toggle_pin(index)
+                                                    >
+                                                        {move || {
+                                                            let _ = current_locale.get();
+                                                            if entry.pinned { t!("tools.history.unpin") } else { t!("tools.history.pin") }
+                                                        }}
+                                                    </button>
+                                                    <button
+                                                        class="text-gray-500 hover:text-red-600"
+                                                        on:click=move |_| history.remove(index)
+                                                    >
+                                                        {move || {
+                                                            let _ = current_locale.get();
+                                                            t!("tools.history.delete")
+                                                        }}
+                                                    </button>
+                                                </div>
+                                            </li>
+                                        }
+                                    })
+                                    .collect_view()
+                            }}
+                        </ul>
+                        <div class="p-2 border-t border-gray-100 text-right">
+                            <button
+                                class="text-xs text-gray-500 hover:text-red-600"
+                                on:click=move |_| history.clear()
+                            >
+                                {move || {
+                                    let _ = current_locale.get();
+                                    t!("tools.history.clear")
+                                }}
+                            </button>
+                        </div>
+                    </Show>
+                </div>
+            </Show>
+        </div>
+    }
+}
@@ -1,3 +1,7 @@
+pub mod history;
+pub mod share;
+pub mod worker;
+
 pub fn copy_to_clipboard(text: &str) {
     if let Some(window) = web_sys::window() {
         let navigator = window.navigator();
@@ -7,6 +11,7 @@ pub fn copy_to_clipboard(text: &str) {
 }
 
 use leptos::*;
+use leptos_router::{use_location, use_navigate, use_query_map, NavigateOptions};
 use std::cell::RefCell;
 use std::rc::Rc;
 use web_sys::Storage;
@@ -29,7 +34,7 @@ impl I18nState {
 }
 
 thread_local! {
-    static I18N_STATE: RefCell<Option<Rc<I18nState>>> = RefCell::new(None);
+    static I18N_STATE: RefCell<Option<Rc<I18nState>>> = const { RefCell::new(None) };
 }
 
 pub fn get_saved_locale() -> Option<String> {
@@ -39,4 +44,27 @@ pub fn get_saved_locale() -> Option<String> {
         .flatten()
         .and_then(|storage: Storage| storage.get_item("locale").ok())
         .flatten()
-}
\ No newline at end of file
+}
+
+/// 读取当前路由查询参数中的工具选项
+pub fn query_param(name: &str) -> Option<String> {
+    use_query_map().with_untracked(|query| query.get(name).cloned())
+}
+
+/// 返回一个更新查询参数的回调，替换当前历史记录而不是新增
+pub fn use_query_setter() -> Callback<(&'static str, String)> {
+    let navigate = use_navigate();
+    let location = use_location();
+    Callback::new(move |(name, value): (&'static str, String)| {
+        let mut query = location.query.get_untracked();
+        query.insert(name.to_string(), value);
+        navigate(
+            &format!("{}{}", location.pathname.get_untracked(), query.to_query_string()),
+            NavigateOptions {
+                replace: true,
+                scroll: false,
+                ..Default::default()
+            },
+        );
+    })
+}
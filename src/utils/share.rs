@@ -0,0 +1,72 @@
+use leptos::*;
+use leptos_router::use_location;
+use rust_i18n::t;
+use dev_tools_core::share::{decode_state, encode_state};
+use std::time::Duration;
+use super::copy_to_clipboard;
+
+pub use dev_tools_core::share::SharedState;
+
+/// URL 片段前缀，例如 `/json#share=...`
+const FRAGMENT_PREFIX: &str = "share=";
+
+/// 读取当前路由 URL 片段中的分享状态
+///
+/// 使用路由的 location 而不是 `window.location`，应用内跳转时浏览器地址会稍后才更新
+pub fn read_shared_state() -> Option<SharedState> {
+    let hash = use_location().hash.get_untracked();
+    let encoded = hash.trim_start_matches('#').strip_prefix(FRAGMENT_PREFIX)?;
+    decode_state(encoded)
+}
+
+/// 应用内打开工具并带上输入和选项，例如 `/json#share=...`
+pub fn share_path(tool_path: &str, state: &SharedState) -> String {
+    format!("{}#{}{}", tool_path, FRAGMENT_PREFIX, encode_state(state))
+}
+
+/// 工具选项的初始值：分享链接优先，其次是查询参数
+pub fn initial_option(shared: &Option<SharedState>, name: &str) -> Option<String> {
+    shared
+        .as_ref()
+        .and_then(|state| state.option(name).map(str::to_string))
+        .or_else(|| super::query_param(name))
+}
+
+/// 以当前页面地址（不含原有片段）为基础生成分享链接
+pub fn share_url(state: &SharedState) -> String {
+    let location = window().location();
+    let href = location.href().unwrap_or_default();
+    let base = href.split('#').next().unwrap_or_default();
+    format!("{}#{}{}", base, FRAGMENT_PREFIX, encode_state(state))
+}
+
+/// 复制分享链接到剪贴板的按钮
+#[component]
+pub fn ShareButton<F>(state: F, current_locale: RwSignal<String>) -> impl IntoView
+where
+    F: Fn() -> SharedState + 'static,
+{
+    let (copied, set_copied) = create_signal(false);
+
+    let share = move |_| {
+        copy_to_clipboard(&share_url(&state()));
+        set_copied.set(true);
+        set_timeout(move || set_copied.set(false), Duration::from_secs(2));
+    };
+
+    view! {
+        <button
+            class="inline-flex items-center px-4 py-2 border border-gray-300 text-sm font-medium rounded-md shadow-sm text-gray-700 bg-white hover:bg-gray-50 focus:outline-none focus:ring-2 focus:ring-offset-2 focus:ring-blue-500"
+            on:click=share
+        >
+            {move || {
+                let _ = current_locale.get();
+                if copied.get() {
+                    t!("tools.common.link_copied")
+                } else {
+                    t!("tools.common.share")
+                }
+            }}
+        </button>
+    }
+}
@@ -0,0 +1,231 @@
+use leptos::*;
+use rust_i18n::t;
+use dev_tools_core::task::{Output, Request, Response, Task, TaskError};
+use js_sys::Array;
+use std::time::Duration;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{MessageEvent, Worker};
+
+/// 输入超过该大小（字节）时放到 Web Worker 中处理，避免阻塞页面；
+/// 在主线程上重新解析输入的预览（树形视图、表格、查询）也以此为界
+pub const WORKER_THRESHOLD: usize = 256 * 1024;
+
+/// Trunk 为 `dev-tools-worker` 生成的加载脚本，见 index.html
+const WORKER_URL: &str = "/dev-tools-worker_loader.js";
+
+type OnDone = Box<dyn FnOnce(Result<Output, TaskError>)>;
+
+struct Pending {
+    id: u32,
+    task: Task,
+    on_done: OnDone,
+    // Worker 出错后会用新的 Worker 重试一次
+    retried: bool,
+}
+
+struct WorkerHandle {
+    worker: Worker,
+    _onmessage: Closure<dyn Fn(MessageEvent)>,
+    _onerror: Closure<dyn Fn(JsValue)>,
+}
+
+/// 执行耗时转换：小输入直接同步执行，大输入交给 Web Worker 并报告进度
+///
+/// 大输入不会退回到主线程执行：Worker 无法创建或重试后仍然出错时，任务以错误结束。
+#[derive(Clone, Copy)]
+pub struct TaskRunner {
+    busy: RwSignal<bool>,
+    progress: RwSignal<u8>,
+    worker: StoredValue<Option<WorkerHandle>>,
+    pending: StoredValue<Option<Pending>>,
+    next_id: StoredValue<u32>,
+}
+
+impl TaskRunner {
+    pub fn new() -> Self {
+        let runner = Self {
+            busy: create_rw_signal(false),
+            progress: create_rw_signal(0),
+            worker: store_value(None),
+            pending: store_value(None),
+            next_id: store_value(0),
+        };
+        on_cleanup(move || runner.terminate());
+        runner
+    }
+
+    pub fn busy(&self) -> bool {
+        self.busy.get()
+    }
+
+    pub fn progress(&self) -> u8 {
+        self.progress.get()
+    }
+
+    pub fn run(&self, task: Task, on_done: impl FnOnce(Result<Output, TaskError>) + 'static) {
+        if task.input().len() < WORKER_THRESHOLD {
+            on_done(task.run(|_| ()));
+            return;
+        }
+        // 同一时间只处理一个任务，新任务取消旧任务
+        if self.busy.get_untracked() {
+            self.cancel();
+        }
+
+        let id = self.next_id.get_value().wrapping_add(1);
+        self.next_id.set_value(id);
+        self.pending.set_value(Some(Pending {
+            id,
+            task,
+            on_done: Box::new(on_done),
+            retried: false,
+        }));
+        self.progress.set(0);
+        self.busy.set(true);
+        self.post();
+    }
+
+    /// 把当前任务发给 Worker，必要时先创建 Worker
+    ///
+    /// 输入作为普通字符串发送，只有 id 和选项经过 JSON 序列化。
+    fn post(&self) {
+        self.ensure_worker();
+        let Some((envelope, input)) = self.pending.with_value(|pending| {
+            pending
+                .as_ref()
+                .map(|pending| Request { id: pending.id, task: pending.task.clone() }.into_parts())
+        }) else {
+            return;
+        };
+        let message = Array::of2(&JsValue::from_str(&envelope), &JsValue::from_str(&input));
+        let posted = self.worker.with_value(|handle| {
+            handle.as_ref().is_some_and(|handle| handle.worker.post_message(&message).is_ok())
+        });
+        if !posted {
+            self.fail();
+        }
+    }
+
+    /// 终止正在执行的任务，下次执行时重新创建 Worker
+    pub fn cancel(&self) {
+        self.terminate();
+        self.pending.set_value(None);
+        self.busy.set(false);
+    }
+
+    fn terminate(&self) {
+        self.worker.update_value(|handle| {
+            if let Some(handle) = handle.take() {
+                handle.worker.terminate();
+            }
+        });
+    }
+
+    fn ensure_worker(&self) {
+        if self.worker.with_value(Option::is_some) {
+            return;
+        }
+        let Ok(worker) = Worker::new(WORKER_URL) else {
+            return;
+        };
+
+        let runner = *self;
+        let onmessage = Closure::<dyn Fn(MessageEvent)>::new(move |event: MessageEvent| {
+            let Some(response) = event.data().dyn_into::<Array>().ok().and_then(|parts| {
+                Response::from_parts(&parts.get(0).as_string()?, parts.get(1).as_string()?)
+            }) else {
+                return;
+            };
+            runner.handle(response);
+        });
+        // 这里不能释放 WorkerHandle，否则会销毁正在执行的闭包；换新的 Worker 推迟到回调结束后
+        let onerror = Closure::<dyn Fn(JsValue)>::new(move |_| {
+            runner.worker.with_value(|handle| {
+                if let Some(handle) = handle {
+                    handle.worker.terminate();
+                }
+            });
+            set_timeout(move || runner.retry(), Duration::ZERO);
+        });
+        worker.set_onmessage(Some(onmessage.as_ref().unchecked_ref()));
+        worker.set_onerror(Some(onerror.as_ref().unchecked_ref()));
+
+        self.worker.set_value(Some(WorkerHandle {
+            worker,
+            _onmessage: onmessage,
+            _onerror: onerror,
+        }));
+    }
+
+    fn handle(&self, response: Response) {
+        let current = self.pending.with_value(|pending| pending.as_ref().map(|pending| pending.id));
+        match response {
+            Response::Progress { id, percent } if Some(id) == current => self.progress.set(percent),
+            Response::Done { id, result } if Some(id) == current => {
+                let pending = self.pending.try_update_value(Option::take).flatten();
+                self.busy.set(false);
+                if let Some(pending) = pending {
+                    (pending.on_done)(result);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Worker 出错（例如加载失败或任务中 panic）后换一个新的 Worker 重试当前任务，第二次出错时放弃
+    fn retry(&self) {
+        self.terminate();
+        let retry = self.pending.try_update_value(|pending| {
+            pending.as_mut().is_some_and(|pending| !std::mem::replace(&mut pending.retried, true))
+        });
+        if retry == Some(true) {
+            self.progress.set(0);
+            self.post();
+        } else {
+            self.fail();
+        }
+    }
+
+    /// Worker 不可用时以错误结束当前任务
+    fn fail(&self) {
+        self.terminate();
+        let pending = self.pending.try_update_value(Option::take).flatten();
+        self.busy.set(false);
+        if let Some(pending) = pending {
+            (pending.on_done)(Err(t!("tools.common.worker_failed").to_string().into()));
+        }
+    }
+}
+
+/// 任务执行中显示的进度条和取消按钮
+#[component]
+pub fn TaskProgress(runner: TaskRunner, current_locale: RwSignal<String>) -> impl IntoView {
+    view! {
+        <Show when=move || runner.busy() fallback=|| view! { }>
+            <div class="flex items-center gap-3 p-3 bg-blue-50 border border-blue-200 rounded-lg">
+                <div class="flex-1 h-2 bg-blue-100 rounded-full overflow-hidden">
+                    <div
+                        class="h-2 bg-blue-600 transition-all"
+                        style=move || format!("width: {}%", runner.progress())
+                    ></div>
+                </div>
+                <span class="text-sm text-blue-700">
+                    {move || {
+                        let _ = current_locale.get();
+                        format!("{} {}%", t!("tools.common.working"), runner.progress())
+                    }}
+                </span>
+                <button
+                    class="inline-flex items-center px-3 py-1 border border-gray-300 shadow-sm text-sm leading-4 font-medium rounded-md text-gray-700 bg-white hover:bg-gray-50 focus:outline-none focus:ring-2 focus:ring-offset-2 focus:ring-blue-500"
+                    on:click=move |_| runner.cancel()
+                >
+                    {move || {
+                        let _ = current_locale.get();
+                        t!("tools.common.cancel")
+                    }}
+                </button>
+            </div>
+        </Show>
+    }
+}
@@ -0,0 +1,9 @@
+/** @type {import('tailwindcss').Config} */
+module.exports = {
+  // 类名都以完整字符串写在 Rust 源码中，构建时扫描即可
+  content: ["./index.html", "./src/**/*.rs"],
+  theme: {
+    extend: {},
+  },
+  plugins: [],
+};
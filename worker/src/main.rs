@@ -0,0 +1,36 @@
+//! Web Worker that runs heavy conversions for the web app off the main thread.
+//!
+//! Built by Trunk as a worker (see `index.html`); messages follow
+//! `dev_tools_core::task` and are posted as `[envelope, text]` string pairs.
+
+use dev_tools_core::task::{Request, Response};
+use js_sys::Array;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{DedicatedWorkerGlobalScope, MessageEvent};
+
+fn main() {
+    let scope: DedicatedWorkerGlobalScope = js_sys::global().unchecked_into();
+    let reply_scope = scope.clone();
+
+    let onmessage = Closure::<dyn Fn(MessageEvent)>::new(move |event: MessageEvent| {
+        let Some(request) = event.data().dyn_into::<Array>().ok().and_then(|parts| {
+            Request::from_parts(&parts.get(0).as_string()?, parts.get(1).as_string()?)
+        }) else {
+            return;
+        };
+
+        let post = |response: Response| {
+            let (envelope, text) = response.into_parts();
+            let message = Array::of2(&JsValue::from_str(&envelope), &JsValue::from_str(&text));
+            let _ = reply_scope.post_message(&message);
+        };
+
+        let id = request.id;
+        let result = request.task.run(|percent| post(Response::Progress { id, percent }));
+        post(Response::Done { id, result });
+    });
+
+    scope.set_onmessage(Some(onmessage.as_ref().unchecked_ref()));
+    onmessage.forget();
+}